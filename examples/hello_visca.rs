@@ -3,7 +3,7 @@ use grafton_visca::{
         pan_tilt::{PanSpeed, PanTiltDirection, TiltSpeed},
         InquiryCommand, PanTiltCommand, ZoomCommand,
     },
-    send_command_and_wait, AppError, TcpTransport, UdpTransport, ViscaInquiryResponse,
+    AppError, Camera, CameraModel, Protocol, TcpTransport, UdpTransport, ViscaInquiryResponse,
     ViscaResponse, ViscaTransport,
 };
 use log::{debug, error, info};
@@ -28,21 +28,22 @@ fn main() -> Result<(), AppError> {
         default_ip_address
     };
 
-    let udp_port = "1259";
-    let tcp_port = "5678";
-
     let use_udp = protocol.eq_ignore_ascii_case("udp");
-    let address = if use_udp {
-        format!("{}:{}", ip_address, udp_port)
+    let port = CameraModel::default().default_port(if use_udp {
+        Protocol::Udp
     } else {
-        format!("{}:{}", ip_address, tcp_port)
-    };
+        Protocol::Tcp
+    });
+    let address = format!("{}:{}", ip_address, port);
 
-    let mut transport: Box<dyn ViscaTransport> = if use_udp {
+    let transport: Box<dyn ViscaTransport> = if use_udp {
         Box::new(UdpTransport::new(&address)?)
     } else {
         Box::new(TcpTransport::new(&address)?)
     };
+    let mut camera = Camera::new(transport, CameraModel::default());
+    // Set VISCA_TRACE to log the name and frame of every command sent.
+    camera.set_trace_commands(env::var_os("VISCA_TRACE").is_some());
 
     debug!("Sending Pan/Tilt home command");
     let pan_tilt_home_command = PanTiltCommand {
@@ -50,12 +51,12 @@ fn main() -> Result<(), AppError> {
         pan_speed: PanSpeed::STOP,
         tilt_speed: TiltSpeed::STOP,
     };
-    send_command_and_wait(&mut *transport, &pan_tilt_home_command)?;
+    camera.send(&pan_tilt_home_command)?;
 
     std::thread::sleep(Duration::from_secs(1));
     debug!("Inquiring Pan/Tilt position");
     if let Ok(ViscaResponse::InquiryResponse(ViscaInquiryResponse::PanTiltPosition { pan, tilt })) =
-        send_command_and_wait(&mut *transport, &InquiryCommand::PanTiltPosition)
+        camera.send(&InquiryCommand::PanTiltPosition)
     {
         info!("Pan position: {}, Tilt position: {}", pan, tilt);
     } else {
@@ -78,7 +79,7 @@ fn main() -> Result<(), AppError> {
             pan_speed: PanSpeed::new(*pan_speed).expect("Invalid Pan Speed"),
             tilt_speed: TiltSpeed::new(*tilt_speed).expect("Invalid Tilt Speed"),
         };
-        send_command_and_wait(&mut *transport, &pan_tilt_command)?;
+        camera.send(&pan_tilt_command)?;
 
         std::thread::sleep(Duration::from_secs(3));
         debug!("Sending Pan/Tilt stop command");
@@ -87,13 +88,13 @@ fn main() -> Result<(), AppError> {
             pan_speed: PanSpeed::STOP,
             tilt_speed: TiltSpeed::STOP,
         };
-        send_command_and_wait(&mut *transport, &pan_tilt_stop_command)?;
+        camera.send(&pan_tilt_stop_command)?;
         std::thread::sleep(Duration::from_secs(1));
     }
 
     debug!("Inquiring Pan/Tilt position");
     if let Ok(ViscaResponse::InquiryResponse(ViscaInquiryResponse::PanTiltPosition { pan, tilt })) =
-        send_command_and_wait(&mut *transport, &InquiryCommand::PanTiltPosition)
+        camera.send(&InquiryCommand::PanTiltPosition)
     {
         info!("Pan position: {}, Tilt position: {}", pan, tilt);
     } else {
@@ -102,7 +103,7 @@ fn main() -> Result<(), AppError> {
 
     debug!("Inquiring initial Zoom position");
     if let Ok(ViscaResponse::InquiryResponse(ViscaInquiryResponse::ZoomPosition { position })) =
-        send_command_and_wait(&mut *transport, &InquiryCommand::ZoomPosition)
+        camera.send(&InquiryCommand::ZoomPosition)
     {
         info!("Initial Zoom position: {}", position);
     } else {
@@ -118,7 +119,7 @@ fn main() -> Result<(), AppError> {
 
     for command in zoom_movements.iter() {
         debug!("Sending {:?} command", command);
-        if let Err(e) = send_command_and_wait(&mut *transport, command) {
+        if let Err(e) = camera.send(command) {
             error!("Error while sending zoom command: {:?}", e);
             return Err(AppError::Visca(e));
         }
@@ -127,7 +128,7 @@ fn main() -> Result<(), AppError> {
 
         debug!("Inquiring Zoom position after {:?}", command);
         if let Ok(ViscaResponse::InquiryResponse(ViscaInquiryResponse::ZoomPosition { position })) =
-            send_command_and_wait(&mut *transport, &InquiryCommand::ZoomPosition)
+            camera.send(&InquiryCommand::ZoomPosition)
         {
             info!("Zoom position after {:?}: {}", command, position);
         } else {
@@ -136,11 +137,11 @@ fn main() -> Result<(), AppError> {
     }
 
     debug!("Sending Zoom stop command");
-    send_command_and_wait(&mut *transport, &ZoomCommand::Stop)?;
+    camera.send(&ZoomCommand::Stop)?;
 
     debug!("Inquiring final Zoom position");
     if let Ok(ViscaResponse::InquiryResponse(ViscaInquiryResponse::ZoomPosition { position })) =
-        send_command_and_wait(&mut *transport, &InquiryCommand::ZoomPosition)
+        camera.send(&InquiryCommand::ZoomPosition)
     {
         info!("Final Zoom position: {}", position);
     } else {
@@ -148,10 +149,10 @@ fn main() -> Result<(), AppError> {
     }
 
     debug!("Sending Pan/Tilt home command");
-    send_command_and_wait(&mut *transport, &pan_tilt_home_command)?;
+    camera.send(&pan_tilt_home_command)?;
 
     debug!("Sending Zoom home command");
-    send_command_and_wait(&mut *transport, &ZoomCommand::WideStandard)?;
+    camera.send(&ZoomCommand::WideStandard)?;
 
     Ok(())
 }
@@ -0,0 +1,178 @@
+use std::time::Duration;
+
+use super::{Camera, PanTiltLimits};
+use crate::{
+    BackpressurePolicy, CameraModel, Protocol, TcpTransport, UdpTransport, ViscaError,
+    ViscaTransport,
+};
+
+/// Collects connection and safety options for a [`Camera`], created with
+/// [`Camera::connect`].
+#[must_use]
+pub struct CameraBuilder {
+    host: String,
+    port: Option<u16>,
+    protocol: Protocol,
+    model: CameraModel,
+    read_timeout: Option<Duration>,
+    require_power_on: bool,
+    pan_tilt_limits: Option<PanTiltLimits>,
+    backpressure: BackpressurePolicy,
+    min_command_interval: Duration,
+}
+
+impl CameraBuilder {
+    pub(super) fn new(host: &str) -> Self {
+        Self {
+            host: host.to_string(),
+            port: None,
+            protocol: Protocol::Udp,
+            model: CameraModel::default(),
+            read_timeout: None,
+            require_power_on: false,
+            pan_tilt_limits: None,
+            backpressure: BackpressurePolicy::default(),
+            min_command_interval: Duration::ZERO,
+        }
+    }
+
+    pub fn model(mut self, model: CameraModel) -> Self {
+        self.model = model;
+        self
+    }
+
+    /// Defaults to UDP.
+    pub fn protocol(mut self, protocol: Protocol) -> Self {
+        self.protocol = protocol;
+        self
+    }
+
+    /// Overrides the model's default port, see [`CameraModel::default_port`].
+    pub fn port(mut self, port: u16) -> Self {
+        self.port = Some(port);
+        self
+    }
+
+    /// Overrides the transport's default read timeout.
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    /// See [`Camera::set_require_power_on`].
+    pub fn require_power_on(mut self, require_power_on: bool) -> Self {
+        self.require_power_on = require_power_on;
+        self
+    }
+
+    /// See [`Camera::set_pan_tilt_limits`].
+    pub fn pan_tilt_limits(mut self, limits: PanTiltLimits) -> Self {
+        self.pan_tilt_limits = Some(limits);
+        self
+    }
+
+    /// See [`Camera::set_backpressure`].
+    pub fn backpressure(mut self, policy: BackpressurePolicy) -> Self {
+        self.backpressure = policy;
+        self
+    }
+
+    /// See [`Camera::set_min_command_interval`].
+    pub fn min_command_interval(mut self, interval: Duration) -> Self {
+        self.min_command_interval = interval;
+        self
+    }
+
+    /// Opens the transport and applies the collected options.
+    pub fn build(self) -> Result<Camera, ViscaError> {
+        let port = self
+            .port
+            .unwrap_or_else(|| self.model.default_port(self.protocol));
+        let address = format!("{}:{}", self.host, port);
+
+        let transport: Box<dyn ViscaTransport> = match self.protocol {
+            Protocol::Udp => {
+                let transport = UdpTransport::new(&address)?;
+                if let Some(timeout) = self.read_timeout {
+                    transport.set_read_timeout(Some(timeout))?;
+                }
+                Box::new(transport)
+            }
+            Protocol::Tcp => {
+                let transport = TcpTransport::new(&address)?;
+                if let Some(timeout) = self.read_timeout {
+                    transport.set_read_timeout(Some(timeout))?;
+                }
+                Box::new(transport)
+            }
+        };
+
+        let mut camera = Camera::new(transport, self.model);
+        camera.set_require_power_on(self.require_power_on);
+        camera.set_pan_tilt_limits(self.pan_tilt_limits);
+        camera.set_backpressure(self.backpressure);
+        camera.set_min_command_interval(self.min_command_interval);
+        Ok(camera)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::InquiryCommand;
+    use std::{
+        net::{TcpListener, UdpSocket},
+        time::Instant,
+    };
+
+    #[test]
+    fn options_apply_to_the_built_camera() {
+        let silent = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let limits = PanTiltLimits {
+            pan_min: -10,
+            pan_max: 10,
+            tilt_min: -5,
+            tilt_max: 5,
+        };
+        let policy = BackpressurePolicy {
+            max_attempts: 2,
+            delay: Duration::from_millis(5),
+        };
+        let mut camera = Camera::connect("127.0.0.1")
+            .model(CameraModel::SonyFr7)
+            .port(silent.local_addr().unwrap().port())
+            .read_timeout(Duration::from_millis(50))
+            .require_power_on(true)
+            .pan_tilt_limits(limits)
+            .backpressure(policy)
+            .min_command_interval(Duration::from_millis(20))
+            .build()
+            .unwrap();
+
+        assert_eq!(camera.model(), CameraModel::SonyFr7);
+        assert_eq!(camera.pan_tilt_limits(), Some(limits));
+        assert!(camera.require_power_on);
+        assert_eq!(camera.backpressure, policy);
+        assert_eq!(camera.min_command_interval, Duration::from_millis(20));
+
+        // The short read timeout replaces the transport's 10 second default.
+        let start = Instant::now();
+        assert!(camera.send(&InquiryCommand::Power).is_err());
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn defaults_and_tcp() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let camera = Camera::connect("127.0.0.1")
+            .protocol(Protocol::Tcp)
+            .port(listener.local_addr().unwrap().port())
+            .build()
+            .unwrap();
+        assert!(listener.accept().is_ok());
+        assert_eq!(camera.model(), CameraModel::default());
+        assert_eq!(camera.pan_tilt_limits(), None);
+        assert!(!camera.require_power_on);
+        assert_eq!(camera.backpressure, BackpressurePolicy::default());
+    }
+}
@@ -0,0 +1,124 @@
+use log::{error, warn};
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc::{self, Sender},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use super::SharedTransport;
+use crate::{
+    command::{
+        pan_tilt::{PanSpeed, PanTiltDirection, TiltSpeed},
+        PanTiltCommand,
+    },
+    send_command_and_wait, Clock, ViscaError,
+};
+
+/// Keeps a pan/tilt drive running until the guard is stopped or dropped.
+///
+/// Dropping the guard sends `PanTiltDirection::Stop`, so a move cannot
+/// outlive its owner: if the guard is held by a future that gets cancelled
+/// (for example when a websocket client disconnects) the camera still stops.
+/// The stop is sent synchronously from `Drop`; errors there are only logged,
+/// use [`stop`](Self::stop) to observe them.
+///
+/// Only the guard from the latest move owns the drive. Starting another move
+/// disarms the older guards, so replacing a guard with a new one doesn't
+/// stop the new move when the old guard is dropped.
+#[must_use = "dropping the guard stops the move immediately"]
+pub struct MoveGuard {
+    transport: Option<SharedTransport>,
+    /// Generation of the drive the live guard owns, `0` once stopped.
+    drive: Arc<AtomicU64>,
+    generation: u64,
+    /// Dropped with the guard, which cancels the watchdog.
+    watchdog: Option<Sender<()>>,
+}
+
+impl MoveGuard {
+    pub(super) fn new(transport: SharedTransport, drive: Arc<AtomicU64>, generation: u64) -> Self {
+        Self {
+            transport: Some(transport),
+            drive,
+            generation,
+            watchdog: None,
+        }
+    }
+
+    /// Starts a watchdog thread that stops the move after `max_duration`
+    /// unless the guard is stopped or dropped first.
+    pub(super) fn with_watchdog(mut self, max_duration: Duration, clock: Arc<dyn Clock>) -> Self {
+        let Some(transport) = self.transport.clone() else {
+            return self;
+        };
+        let drive = Arc::clone(&self.drive);
+        let generation = self.generation;
+        let (cancel_tx, cancel_rx) = mpsc::channel::<()>();
+        thread::spawn(move || {
+            if !clock.wait(&cancel_rx, max_duration) {
+                if drive.load(Ordering::Relaxed) != generation {
+                    return;
+                }
+                warn!("Drive exceeded {:?}, stopping", max_duration);
+                match send_stop(&transport) {
+                    Ok(()) => release(&drive, generation),
+                    Err(e) => error!("Watchdog failed to stop pan/tilt: {}", e),
+                }
+            }
+        });
+        self.watchdog = Some(cancel_tx);
+        self
+    }
+
+    /// Stops the move and reports whether the stop was accepted.
+    pub fn stop(mut self) -> Result<(), ViscaError> {
+        match self.transport.take() {
+            Some(transport) => self.stop_move(&transport),
+            None => Ok(()),
+        }
+    }
+
+    /// Sends the stop, unless a newer move has taken over the drive.
+    fn stop_move(&self, transport: &SharedTransport) -> Result<(), ViscaError> {
+        if self.drive.load(Ordering::Relaxed) != self.generation {
+            return Ok(());
+        }
+        send_stop(transport)?;
+        release(&self.drive, self.generation);
+        Ok(())
+    }
+}
+
+impl Drop for MoveGuard {
+    fn drop(&mut self) {
+        if let Some(transport) = self.transport.take() {
+            if let Err(e) = self.stop_move(&transport) {
+                error!("Failed to stop pan/tilt on drop: {}", e);
+            }
+        }
+    }
+}
+
+/// Marks the drive idle if `generation` still owns it.
+fn release(drive: &AtomicU64, generation: u64) {
+    let _ = drive.compare_exchange(generation, 0, Ordering::Relaxed, Ordering::Relaxed);
+}
+
+fn send_stop(transport: &SharedTransport) -> Result<(), ViscaError> {
+    let mut transport = transport
+        .lock()
+        .map_err(|_| ViscaError::TransportError("Transport lock poisoned".into()))?;
+    send_command_and_wait(
+        &mut **transport,
+        &PanTiltCommand {
+            direction: PanTiltDirection::Stop,
+            pan_speed: PanSpeed::STOP,
+            tilt_speed: TiltSpeed::STOP,
+        },
+    )?;
+    Ok(())
+}
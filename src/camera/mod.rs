@@ -0,0 +1,2235 @@
+use log::{debug, info};
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    ops::RangeInclusive,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc::{self, Receiver, Sender, TryRecvError},
+        Arc, Mutex, MutexGuard,
+    },
+    time::{Duration, Instant},
+};
+
+mod builder;
+mod guard;
+mod poll;
+mod sequence;
+pub use builder::CameraBuilder;
+pub use guard::MoveGuard;
+pub use poll::PollHandle;
+use sequence::Step;
+pub use sequence::{Sequence, MAX_SEQUENCE_REPEAT};
+
+use crate::{
+    command::{
+        catalog,
+        pan_tilt::{PanSpeed, PanTiltDirection, TiltSpeed},
+        preset::{PresetAction, MAX_PRESET},
+        AeLevelCommand, BacklightCommand, ColorTemperatureCommand, ExposureCommand,
+        ExposureCompensationCommand, ExposureMode, ExposureSettings, FocusCommand, GainCommand,
+        IfClearCommand, InquiryCommand, PanTiltCommand, PanTiltRampCommand, PanTiltRelativeCommand,
+        Power, PowerCommand, PresetCommand, PresetSpeedCommand, ReplyPattern, SharpnessCommand,
+        TitleDisplayCommand, WhiteBalanceCommand, WhiteBalanceMode, ZoomCommand,
+        ZoomFocusDirectCommand,
+    },
+    frame_logger, send_command_with_retry_routing, BackpressurePolicy, CameraModel, Clock,
+    RangeFormat, SystemClock, ViscaCommand, ViscaError, ViscaInquiryResponse, ViscaResponse,
+    ViscaResponseType, ViscaTransport, ZoomLens,
+};
+
+/// How often and for how long a tour waits for a recalled preset to settle.
+const TOUR_SETTLE_INTERVAL: Duration = Duration::from_millis(200);
+const TOUR_SETTLE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A transport that can be shared with background workers.
+pub type SharedTransport = Arc<Mutex<Box<dyn ViscaTransport>>>;
+
+/// Soft pan/tilt limits, in the camera's native position units.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PanTiltLimits {
+    pub pan_min: i16,
+    pub pan_max: i16,
+    pub tilt_min: i16,
+    pub tilt_max: i16,
+}
+
+/// Where [`Camera::park`] sends the camera.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum ParkTarget {
+    /// The pan/tilt home position.
+    #[default]
+    Home,
+    /// A stored preset.
+    Preset(u8),
+}
+
+/// A patrol over stored presets for [`Camera::run_tour`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PresetTour {
+    /// Presets to visit in order, each with how long to dwell there once the
+    /// camera has settled.
+    pub stops: Vec<(u8, Duration)>,
+    /// Recall speed applied to every stop, `0x01` to `0x18`.
+    pub recall_speed: u8,
+}
+
+/// A camera bound to a transport and a model profile.
+pub struct Camera {
+    transport: SharedTransport,
+    model: CameraModel,
+    pan_tilt_limits: Option<PanTiltLimits>,
+    backpressure: BackpressurePolicy,
+    require_power_on: bool,
+    power: Option<Power>,
+    reply_patterns: Vec<(Vec<u8>, ReplyPattern)>,
+    park_target: ParkTarget,
+    notifications: Option<Sender<ViscaResponse>>,
+    min_command_interval: Duration,
+    last_send: Option<Instant>,
+    stop_before_direction_change: bool,
+    drive_direction: Option<PanTiltDirection>,
+    /// Generation of the running drive, `0` when none; see [`MoveGuard`].
+    drive: Arc<AtomicU64>,
+    drive_count: u64,
+    strict: bool,
+    /// Per-command overrides of [`ViscaCommand::completion_timeout`], by name.
+    completion_timeouts: HashMap<String, Duration>,
+    clock: Arc<dyn Clock>,
+    trace_commands: bool,
+    zoom_lens: Option<ZoomLens>,
+}
+
+/// Encodes the wrapped command for a specific profile.
+struct ProfiledCommand<'a> {
+    command: &'a dyn ViscaCommand,
+    model: CameraModel,
+    reply_pattern: ReplyPattern,
+    completion_timeout: Option<Duration>,
+}
+
+impl ViscaCommand for ProfiledCommand<'_> {
+    fn to_bytes(&self) -> Result<Vec<u8>, ViscaError> {
+        self.command.to_bytes_for(self.model)
+    }
+
+    fn response_type(&self) -> Option<ViscaResponseType> {
+        self.command.response_type()
+    }
+
+    fn reply_pattern(&self) -> ReplyPattern {
+        self.reply_pattern
+    }
+
+    fn completion_timeout(&self) -> Option<Duration> {
+        self.completion_timeout
+    }
+
+    fn name(&self) -> &'static str {
+        self.command.name()
+    }
+}
+
+/// Logs each send the wrapped transport accepted, for
+/// [`Camera::set_trace_commands`].
+struct TracingTransport<'a> {
+    inner: &'a mut dyn ViscaTransport,
+    frame: &'a [u8],
+}
+
+impl ViscaTransport for TracingTransport<'_> {
+    fn send_command(&mut self, command: &dyn ViscaCommand) -> Result<(), ViscaError> {
+        self.inner.send_command(command)?;
+        info!("-> {} {}", command.name(), frame_logger::hex(self.frame));
+        Ok(())
+    }
+
+    fn receive_response(&mut self) -> Result<Vec<Vec<u8>>, ViscaError> {
+        self.inner.receive_response()
+    }
+}
+
+impl Camera {
+    pub fn new(transport: Box<dyn ViscaTransport>, model: CameraModel) -> Self {
+        Self {
+            transport: Arc::new(Mutex::new(transport)),
+            model,
+            pan_tilt_limits: None,
+            backpressure: BackpressurePolicy::default(),
+            require_power_on: false,
+            power: None,
+            reply_patterns: Vec::new(),
+            park_target: ParkTarget::default(),
+            notifications: None,
+            min_command_interval: Duration::ZERO,
+            last_send: None,
+            stop_before_direction_change: false,
+            drive_direction: None,
+            drive: Arc::new(AtomicU64::new(0)),
+            drive_count: 0,
+            strict: false,
+            completion_timeouts: HashMap::new(),
+            clock: Arc::new(SystemClock),
+            trace_commands: false,
+            zoom_lens: None,
+        }
+    }
+
+    /// Starts configuring a camera at `host`. The port defaults to the
+    /// model's port for the chosen protocol.
+    pub fn connect(host: &str) -> CameraBuilder {
+        CameraBuilder::new(host)
+    }
+
+    pub fn model(&self) -> CameraModel {
+        self.model
+    }
+
+    /// Issues the version inquiry and switches to the matching profile, see
+    /// [`CameraModel::from_version`].
+    pub fn detect_model(&mut self) -> Result<CameraModel, ViscaError> {
+        match self.send(&InquiryCommand::Version)? {
+            ViscaResponse::InquiryResponse(ViscaInquiryResponse::Version {
+                vendor_id,
+                model_id,
+                ..
+            }) => {
+                self.model = CameraModel::from_version(vendor_id, model_id);
+                debug!(
+                    "Detected {:?} from vendor {:04X} model {:04X}",
+                    self.model, vendor_id, model_id
+                );
+                Ok(self.model)
+            }
+            _ => Err(ViscaError::UnexpectedResponseType),
+        }
+    }
+
+    /// Records the pan/tilt limits the camera is configured with so
+    /// relative moves can be kept inside them.
+    pub fn set_pan_tilt_limits(&mut self, limits: Option<PanTiltLimits>) {
+        self.pan_tilt_limits = limits;
+    }
+
+    pub fn pan_tilt_limits(&self) -> Option<PanTiltLimits> {
+        self.pan_tilt_limits
+    }
+
+    /// Sets how commands rejected with `CommandBufferFull` are retried.
+    pub fn set_backpressure(&mut self, policy: BackpressurePolicy) {
+        self.backpressure = policy;
+    }
+
+    /// When enabled, pan/tilt, zoom and focus commands are rejected with
+    /// `CommandNotExecutable` while the camera is in standby instead of
+    /// being sent. The power state is inquired once and then tracked from
+    /// the `PowerCommand`s sent through this camera.
+    pub fn set_require_power_on(&mut self, require_power_on: bool) {
+        self.require_power_on = require_power_on;
+    }
+
+    /// Enforces a minimum gap between commands sent through this camera,
+    /// sleeping before a send when needed. For cameras that drop commands
+    /// sent back-to-back. Defaults to zero.
+    pub fn set_min_command_interval(&mut self, interval: Duration) {
+        self.min_command_interval = interval;
+    }
+
+    /// When enabled, [`move_direction`](Self::move_direction) sends a stop
+    /// before a drive in a different direction than the one still running,
+    /// so a joystick reversal doesn't reach the camera mid-acceleration.
+    /// Only moves started through this camera are tracked. Disabled by
+    /// default.
+    pub fn set_stop_before_direction_change(&mut self, enabled: bool) {
+        self.stop_before_direction_change = enabled;
+    }
+
+    /// When enabled, a reply frame the parser doesn't recognise fails the
+    /// command with `ViscaError::ParseError` instead of being skipped, to
+    /// surface misbehaving cameras during development. Disabled by default.
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// Overrides how long the Completion of the command named `name` (see
+    /// [`ViscaCommand::name`]) is waited for past the read timeout, e.g.
+    /// `"PresetRecall"` on a slow head. `None` restores the command's own
+    /// [`ViscaCommand::completion_timeout`].
+    pub fn set_completion_timeout(&mut self, name: &str, timeout: Option<Duration>) {
+        match timeout {
+            Some(timeout) => {
+                self.completion_timeouts.insert(name.to_string(), timeout);
+            }
+            None => {
+                self.completion_timeouts.remove(name);
+            }
+        }
+    }
+
+    /// When enabled, logs each command sent through this camera at info
+    /// level with its name and frame, e.g. `-> ZoomDirect 81 01 04 47 00 05
+    /// 00 00 FF`, once the transport has sent it. To include replies, wrap
+    /// the transport in a [`crate::FrameLogger`] instead. Disabled by
+    /// default.
+    pub fn set_trace_commands(&mut self, enabled: bool) {
+        self.trace_commands = enabled;
+    }
+
+    /// Replaces the time source used for command pacing, busy retries,
+    /// completion deadlines, sequence delays, tours,
+    /// [`wait_until_still`](Self::wait_until_still), and the watchdog and
+    /// polling loops started after the call.
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    /// Sets where [`park`](Self::park) sends the camera.
+    pub fn set_park_target(&mut self, target: ParkTarget) {
+        self.park_target = target;
+    }
+
+    /// Returns a channel of the unsolicited frames (see
+    /// [`ViscaResponse::is_unsolicited`]) read while waiting for replies to
+    /// commands sent through this camera, including menu events and network
+    /// changes. Frames read by background pollers are not included, nor are
+    /// unknown frames under [`set_strict`](Self::set_strict), which fail the
+    /// command instead. Calling this again replaces the previous channel.
+    ///
+    /// A std channel rather than a `Stream`, as the crate has no async
+    /// runtime; bridge it with a blocking task where one is needed.
+    pub fn notifications(&mut self) -> Receiver<ViscaResponse> {
+        let (sender, receiver) = mpsc::channel();
+        self.notifications = Some(sender);
+        receiver
+    }
+
+    /// Overrides the replies expected for commands whose frame starts with
+    /// `prefix`, for firmware that skips the ACK or the completion where the
+    /// profile's [`CameraModel::reply_pattern`] table doesn't know it. The
+    /// longest matching prefix wins.
+    pub fn expect_reply(&mut self, prefix: &[u8], pattern: ReplyPattern) {
+        self.reply_patterns
+            .retain(|(existing, _)| existing != prefix);
+        self.reply_patterns.push((prefix.to_vec(), pattern));
+    }
+
+    /// Sends a command encoded for the active profile and waits for its
+    /// completion or inquiry reply, backing off while the camera's command
+    /// buffer is full.
+    pub fn send(&mut self, command: &dyn ViscaCommand) -> Result<ViscaResponse, ViscaError> {
+        let bytes = command.to_bytes_for(self.model)?;
+        if self.require_power_on && is_movement(&bytes) && self.power()? == Power::Standby {
+            debug!("Blocking movement command while in standby: {:02X?}", bytes);
+            return Err(ViscaError::CommandNotExecutable);
+        }
+
+        let response = self.send_unchecked(command)?;
+        if let [0x81, 0x01, 0x04, 0x00, state, 0xFF] = bytes[..] {
+            self.power = Power::try_from(state).ok();
+        }
+        Ok(response)
+    }
+
+    /// Builds the command called `name` from `params` with
+    /// [`crate::command::catalog::named`] and sends it.
+    pub fn send_named(&mut self, name: &str, params: &[i64]) -> Result<ViscaResponse, ViscaError> {
+        let command = catalog::named(name, params)?;
+        self.send(&*command)
+    }
+
+    /// Sends each command in order, stopping at the first failure.
+    pub fn send_batch(
+        &mut self,
+        commands: &[&dyn ViscaCommand],
+    ) -> Result<Vec<ViscaResponse>, ViscaError> {
+        commands.iter().map(|command| self.send(*command)).collect()
+    }
+
+    /// Issues each inquiry in turn and returns the results in the same order.
+    /// A failed inquiry doesn't stop the rest.
+    pub fn inquire_many(
+        &mut self,
+        inquiries: &[InquiryCommand],
+    ) -> Vec<Result<ViscaInquiryResponse, ViscaError>> {
+        inquiries
+            .iter()
+            .map(|inquiry| match self.send(inquiry)? {
+                ViscaResponse::InquiryResponse(inquiry_response) => Ok(inquiry_response),
+                _ => Err(ViscaError::UnexpectedResponseType),
+            })
+            .collect()
+    }
+
+    /// Runs `sequence` step by step, stopping at the first failure, and
+    /// returns the replies to its inquiry steps in order. Branch inquiries
+    /// are not included. Repeats over [`MAX_SEQUENCE_REPEAT`] fail with
+    /// `InvalidParameter` when reached.
+    pub fn run_sequence(
+        &mut self,
+        sequence: &Sequence,
+    ) -> Result<Vec<ViscaInquiryResponse>, ViscaError> {
+        let mut results = Vec::new();
+        self.run_steps(sequence, &mut results)?;
+        Ok(results)
+    }
+
+    fn run_steps(
+        &mut self,
+        sequence: &Sequence,
+        results: &mut Vec<ViscaInquiryResponse>,
+    ) -> Result<(), ViscaError> {
+        for step in &sequence.steps {
+            match step {
+                Step::Command(command) => {
+                    self.send(&**command)?;
+                }
+                Step::Inquiry(inquiry) => results.push(self.inquire(inquiry)?),
+                Step::Delay(duration) => self.clock.sleep(*duration),
+                Step::When {
+                    inquiry,
+                    condition,
+                    then,
+                    otherwise,
+                } => {
+                    let branch = if condition(&self.inquire(inquiry)?) {
+                        then
+                    } else {
+                        otherwise
+                    };
+                    self.run_steps(branch, results)?;
+                }
+                Step::Repeat { times, body } => {
+                    if *times > MAX_SEQUENCE_REPEAT {
+                        return Err(ViscaError::out_of_range(
+                            "Sequence repeat count",
+                            *times,
+                            0,
+                            MAX_SEQUENCE_REPEAT.into(),
+                        ));
+                    }
+                    for _ in 0..*times {
+                        self.run_steps(body, results)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn inquire(&mut self, inquiry: &InquiryCommand) -> Result<ViscaInquiryResponse, ViscaError> {
+        match self.send(inquiry)? {
+            ViscaResponse::InquiryResponse(inquiry_response) => Ok(inquiry_response),
+            _ => Err(ViscaError::UnexpectedResponseType),
+        }
+    }
+
+    /// Sends `set` and then `inquiry`, and fails with
+    /// `ViscaError::VerificationFailed` unless `eq` accepts the reply as
+    /// matching `expected`. Catches settings a model silently ignores.
+    pub fn set_and_verify<T: Debug>(
+        &mut self,
+        set: &dyn ViscaCommand,
+        inquiry: &InquiryCommand,
+        expected: T,
+        eq: impl Fn(&ViscaInquiryResponse, &T) -> bool,
+    ) -> Result<(), ViscaError> {
+        self.send(set)?;
+        match self.send(inquiry)? {
+            ViscaResponse::InquiryResponse(actual) if eq(&actual, &expected) => Ok(()),
+            ViscaResponse::InquiryResponse(actual) => Err(ViscaError::VerificationFailed {
+                expected: format!("{:?}", expected),
+                actual: format!("{:?}", actual),
+            }),
+            _ => Err(ViscaError::UnexpectedResponseType),
+        }
+    }
+
+    /// Switches to the exposure mode for `settings`, then sets only the
+    /// parameters that mode accepts, so none is rejected with
+    /// `CommandNotExecutable`.
+    pub fn set_exposure(&mut self, settings: ExposureSettings) -> Result<(), ViscaError> {
+        self.send(&ExposureCommand {
+            mode: settings.mode(),
+        })?;
+        for parameter in settings.parameters() {
+            self.send(&parameter)?;
+        }
+        Ok(())
+    }
+
+    /// Steps the color temperature after checking the camera is in
+    /// [`WhiteBalanceMode::ColorTemperature`], the only mode that accepts it.
+    pub fn adjust_color_temperature(
+        &mut self,
+        step: ColorTemperatureCommand,
+    ) -> Result<(), ViscaError> {
+        match self.send(&InquiryCommand::WhiteBalanceMode)? {
+            ViscaResponse::InquiryResponse(ViscaInquiryResponse::WhiteBalance {
+                mode: WhiteBalanceMode::ColorTemperature,
+            }) => {}
+            ViscaResponse::InquiryResponse(ViscaInquiryResponse::WhiteBalance { mode }) => {
+                return Err(ViscaError::InvalidParameter(format!(
+                    "Color temperature steps need white balance mode ColorTemperature, camera is in {:?}",
+                    mode
+                )));
+            }
+            _ => return Err(ViscaError::UnexpectedResponseType),
+        }
+        self.send(&step)?;
+        Ok(())
+    }
+
+    /// Returns the image settings to a known baseline, in this order:
+    ///
+    /// 1. Exposure mode to auto
+    /// 2. Exposure compensation reset, then off
+    /// 3. Backlight compensation off
+    /// 4. White balance to auto
+    /// 5. Gain reset
+    /// 6. Sharpness reset
+    ///
+    /// Luminance, contrast, pan/tilt, zoom and focus are left untouched.
+    pub fn reset_image_defaults(&mut self) -> Result<(), ViscaError> {
+        self.send_batch(&[
+            &ExposureCommand {
+                mode: ExposureMode::Auto,
+            },
+            &ExposureCompensationCommand::Reset,
+            &ExposureCompensationCommand::Off,
+            &BacklightCommand { status: false },
+            &WhiteBalanceCommand {
+                mode: WhiteBalanceMode::Auto,
+            },
+            &GainCommand::Reset,
+            &SharpnessCommand::Reset,
+        ])?;
+        Ok(())
+    }
+
+    /// Leaves the camera in a clean end-of-day state: clears the command
+    /// buffers, moves to the park target and, if `standby` is set, powers
+    /// down once the move has completed.
+    pub fn park(&mut self, standby: bool) -> Result<(), ViscaError> {
+        self.send(&IfClearCommand)?;
+        match self.park_target {
+            ParkTarget::Home => self.send(&PanTiltCommand {
+                direction: PanTiltDirection::Home,
+                pan_speed: PanSpeed::STOP,
+                tilt_speed: TiltSpeed::STOP,
+            })?,
+            ParkTarget::Preset(preset_number) => self.send(&PresetCommand {
+                action: PresetAction::Recall,
+                preset_number,
+            })?,
+        };
+        if standby {
+            self.send(&PowerCommand {
+                power: Power::Standby,
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Visits each stop of `tour` in a loop: recalls the preset, waits for
+    /// the move to settle, then dwells. Returns once `stop` receives a value
+    /// or its sender is dropped.
+    pub fn run_tour(&mut self, tour: &PresetTour, stop: &Receiver<()>) -> Result<(), ViscaError> {
+        for &(preset_number, _) in &tour.stops {
+            self.send(&PresetSpeedCommand {
+                preset_number,
+                speed: tour.recall_speed,
+            })?;
+        }
+
+        for &(preset_number, dwell) in tour.stops.iter().cycle() {
+            if !matches!(stop.try_recv(), Err(TryRecvError::Empty)) {
+                break;
+            }
+            self.send(&PresetCommand {
+                action: PresetAction::Recall,
+                preset_number,
+            })?;
+            self.wait_until_still(TOUR_SETTLE_INTERVAL, 2, TOUR_SETTLE_TIMEOUT)?;
+            if self.clock.wait(stop, dwell) {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Resets each preset in `range` in turn, as most models have no single
+    /// "clear all" command. `progress` is called with each preset number
+    /// once it has been cleared; the first failure stops the run. A range
+    /// past [`MAX_PRESET`] fails before anything is sent.
+    pub fn clear_all_presets(
+        &mut self,
+        range: RangeInclusive<u8>,
+        mut progress: impl FnMut(u8),
+    ) -> Result<(), ViscaError> {
+        if *range.end() > MAX_PRESET {
+            return Err(ViscaError::out_of_range(
+                "Preset number",
+                *range.end(),
+                0,
+                MAX_PRESET.into(),
+            )
+            .with_range_format(RangeFormat::HexAndDecimal));
+        }
+        for preset_number in range {
+            self.send(&PresetCommand {
+                action: PresetAction::Reset,
+                preset_number,
+            })?;
+            progress(preset_number);
+        }
+        Ok(())
+    }
+
+    /// Moves zoom and focus to absolute positions, in one command where the
+    /// profile supports it and otherwise as a zoom then a focus command.
+    pub fn zoom_focus_direct(&mut self, zoom: u16, focus: u16) -> Result<(), ViscaError> {
+        if self.model.supports_zoom_focus_direct() {
+            self.send(&ZoomFocusDirectCommand { zoom, focus })?;
+        } else {
+            self.send(&ZoomCommand::Direct(zoom))?;
+            self.send(&FocusCommand::Direct(focus))?;
+        }
+        Ok(())
+    }
+
+    /// Declares the zoom lens whose table [`zoom_to_ratio`](Self::zoom_to_ratio)
+    /// uses. No profile implies one, as the table depends on the lens
+    /// rather than the camera. Unset by default.
+    pub fn set_zoom_lens(&mut self, lens: Option<ZoomLens>) {
+        self.zoom_lens = lens;
+    }
+
+    /// Zooms to an optical ratio such as 4.5x, clamped to the lens's range;
+    /// see [`ZoomLens::zoom_position`]. Fails with `InvalidParameter` for a
+    /// non-finite ratio or without a [`set_zoom_lens`](Self::set_zoom_lens).
+    pub fn zoom_to_ratio(&mut self, ratio: f32) -> Result<(), ViscaError> {
+        let lens = self
+            .zoom_lens
+            .ok_or_else(|| ViscaError::InvalidParameter("No zoom lens set".into()))?;
+        let position = lens.zoom_position(ratio).ok_or_else(|| {
+            ViscaError::InvalidParameter(format!("Zoom ratio must be finite, got {}", ratio))
+        })?;
+        self.send(&ZoomCommand::Direct(position))?;
+        Ok(())
+    }
+
+    /// Shows or hides the title overlay, e.g. to identify a camera on site.
+    pub fn set_title_display(&mut self, enabled: bool) -> Result<(), ViscaError> {
+        self.send(&TitleDisplayCommand { enabled })?;
+        Ok(())
+    }
+
+    /// Returns the cached power state, inquiring it if unknown.
+    pub fn power(&mut self) -> Result<Power, ViscaError> {
+        if let Some(power) = self.power {
+            return Ok(power);
+        }
+        match self.send_unchecked(&InquiryCommand::Power)? {
+            ViscaResponse::InquiryResponse(ViscaInquiryResponse::Power { power }) => {
+                self.power = Some(power);
+                Ok(power)
+            }
+            _ => Err(ViscaError::UnexpectedResponseType),
+        }
+    }
+
+    fn send_unchecked(&mut self, command: &dyn ViscaCommand) -> Result<ViscaResponse, ViscaError> {
+        let bytes = command.to_bytes_for(self.model)?;
+        if let Some(last_send) = self.last_send {
+            let elapsed = self.clock.now().saturating_duration_since(last_send);
+            if elapsed < self.min_command_interval {
+                self.clock.sleep(self.min_command_interval - elapsed);
+            }
+        }
+        self.last_send = Some(self.clock.now());
+
+        let reply_pattern = self
+            .reply_patterns
+            .iter()
+            .filter(|(prefix, _)| bytes.starts_with(prefix))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|&(_, pattern)| pattern)
+            .or_else(|| self.model.reply_pattern(command.name()))
+            .unwrap_or_else(|| command.reply_pattern());
+        let profiled = ProfiledCommand {
+            command,
+            model: self.model,
+            reply_pattern,
+            completion_timeout: self
+                .completion_timeouts
+                .get(command.name())
+                .copied()
+                .or_else(|| command.completion_timeout()),
+        };
+        let mut transport = self.lock_transport()?;
+        let mut tracing;
+        let transport: &mut dyn ViscaTransport = if self.trace_commands {
+            tracing = TracingTransport {
+                inner: &mut **transport,
+                frame: &bytes,
+            };
+            &mut tracing
+        } else {
+            &mut **transport
+        };
+        send_command_with_retry_routing(
+            transport,
+            &profiled,
+            &self.backpressure,
+            self.strict,
+            &*self.clock,
+            &mut |response| {
+                if let Some(notifications) = &self.notifications {
+                    // The receiver may have been dropped; nobody is listening then.
+                    let _ = notifications.send(response);
+                }
+            },
+        )
+    }
+
+    /// Sets the auto-exposure level, failing with `InvalidParameter` without
+    /// sending anything on profiles that lack it.
+    pub fn set_ae_level(&mut self, value: u8) -> Result<(), ViscaError> {
+        let command = AeLevelCommand { value };
+        self.require_support(&command)?;
+        self.send(&command)?;
+        Ok(())
+    }
+
+    /// Sets the pan/tilt acceleration ramp, see [`PanTiltRampCommand`].
+    /// Fails with `InvalidParameter` without sending anything on profiles
+    /// that lack it.
+    pub fn set_ramp(&mut self, accel: u8) -> Result<(), ViscaError> {
+        let command = PanTiltRampCommand { accel };
+        self.require_support(&command)?;
+        self.send(&command)?;
+        Ok(())
+    }
+
+    fn require_support(&self, command: &dyn ViscaCommand) -> Result<(), ViscaError> {
+        if self.model.supports(command.name()) {
+            Ok(())
+        } else {
+            Err(ViscaError::InvalidParameter(format!(
+                "{} is not supported by {:?}",
+                command.name(),
+                self.model
+            )))
+        }
+    }
+
+    /// Whether the on-screen menu is open. While it is, pan/tilt commands
+    /// navigate the menu instead of moving the camera.
+    pub fn is_menu_open(&mut self) -> Result<bool, ViscaError> {
+        match self.send(&InquiryCommand::MenuOpen)? {
+            ViscaResponse::InquiryResponse(ViscaInquiryResponse::MenuOpen { open }) => Ok(open),
+            _ => Err(ViscaError::UnexpectedResponseType),
+        }
+    }
+
+    /// Number of the last recalled preset. Fails with `InvalidParameter`
+    /// without sending anything on profiles that don't support the inquiry.
+    pub fn last_preset(&mut self) -> Result<u8, ViscaError> {
+        let inquiry = InquiryCommand::LastPreset;
+        self.require_support(&inquiry)?;
+        match self.send(&inquiry)? {
+            ViscaResponse::InquiryResponse(ViscaInquiryResponse::LastPreset { number }) => {
+                Ok(number)
+            }
+            _ => Err(ViscaError::UnexpectedResponseType),
+        }
+    }
+
+    pub fn pan_tilt_position(&mut self) -> Result<(i16, i16), ViscaError> {
+        match self.send(&InquiryCommand::PanTiltPosition)? {
+            ViscaResponse::InquiryResponse(ViscaInquiryResponse::PanTiltPosition { pan, tilt }) => {
+                Ok((pan, tilt))
+            }
+            _ => Err(ViscaError::UnexpectedResponseType),
+        }
+    }
+
+    /// Takes `samples` pan/tilt readings and returns the median of each
+    /// axis, which rides out single noisy readings while the camera settles.
+    pub fn pan_tilt_position_averaged(&mut self, samples: usize) -> Result<(i16, i16), ViscaError> {
+        if samples == 0 {
+            return Err(ViscaError::InvalidParameter(
+                "At least one position sample is required".into(),
+            ));
+        }
+        let mut pans = Vec::with_capacity(samples);
+        let mut tilts = Vec::with_capacity(samples);
+        for _ in 0..samples {
+            let (pan, tilt) = self.pan_tilt_position()?;
+            pans.push(pan);
+            tilts.push(tilt);
+        }
+        pans.sort_unstable();
+        tilts.sort_unstable();
+        Ok((pans[samples / 2], tilts[samples / 2]))
+    }
+
+    /// Reads `(pan, tilt, zoom)`.
+    pub fn position(&self) -> Result<(i16, i16, u16), ViscaError> {
+        poll::read_position(&self.transport)
+    }
+
+    /// Blocks until `stable_count` consecutive polls, `poll_interval` apart,
+    /// report the same pan/tilt/zoom. Fails with `ViscaError::Timeout` if the
+    /// camera is still moving after `timeout`.
+    pub fn wait_until_still(
+        &self,
+        poll_interval: Duration,
+        stable_count: usize,
+        timeout: Duration,
+    ) -> Result<(i16, i16, u16), ViscaError> {
+        let deadline = self.clock.now() + timeout;
+        let mut last = self.position()?;
+        let mut stable = 0;
+
+        while stable < stable_count {
+            if self.clock.now() + poll_interval > deadline {
+                return Err(ViscaError::Timeout(timeout));
+            }
+            self.clock.sleep(poll_interval);
+            let current = self.position()?;
+            stable = if current == last { stable + 1 } else { 0 };
+            last = current;
+        }
+        Ok(last)
+    }
+
+    /// Polls pan/tilt/zoom in the background and emits `(pan, tilt, zoom)`
+    /// only when a value moves by more than `threshold`. The first reading
+    /// is always emitted.
+    pub fn watch_position(
+        &self,
+        interval: Duration,
+        threshold: u16,
+    ) -> PollHandle<(i16, i16, u16)> {
+        poll::watch_position(
+            Arc::clone(&self.transport),
+            Arc::clone(&self.clock),
+            interval,
+            threshold,
+        )
+    }
+
+    /// Starts a pan/tilt drive that runs until the returned guard is
+    /// stopped or dropped. See
+    /// [`set_stop_before_direction_change`](Self::set_stop_before_direction_change)
+    /// for direction reversals.
+    pub fn move_direction(
+        &mut self,
+        direction: PanTiltDirection,
+        pan_speed: PanSpeed,
+        tilt_speed: TiltSpeed,
+    ) -> Result<MoveGuard, ViscaError> {
+        let drives = !matches!(direction, PanTiltDirection::Stop | PanTiltDirection::Home);
+        if self.stop_before_direction_change
+            && drives
+            && self.drive.load(Ordering::Relaxed) != 0
+            && self.drive_direction != Some(direction)
+        {
+            self.send(&PanTiltCommand {
+                direction: PanTiltDirection::Stop,
+                pan_speed: PanSpeed::STOP,
+                tilt_speed: TiltSpeed::STOP,
+            })?;
+        }
+        self.send(&PanTiltCommand {
+            direction,
+            pan_speed,
+            tilt_speed,
+        })?;
+        // The new guard owns the drive, which disarms any older guard.
+        self.drive_count += 1;
+        let generation = if drives { self.drive_count } else { 0 };
+        self.drive.store(generation, Ordering::Relaxed);
+        self.drive_direction = Some(direction);
+        Ok(MoveGuard::new(
+            Arc::clone(&self.transport),
+            Arc::clone(&self.drive),
+            generation,
+        ))
+    }
+
+    /// Like [`move_direction`](Self::move_direction), but a watchdog stops
+    /// the drive after `max_duration` if the guard hasn't been stopped or
+    /// dropped by then, so a controller that hangs with the guard alive
+    /// can't leave the camera panning.
+    pub fn drive_continuous(
+        &mut self,
+        direction: PanTiltDirection,
+        pan_speed: PanSpeed,
+        tilt_speed: TiltSpeed,
+        max_duration: Duration,
+    ) -> Result<MoveGuard, ViscaError> {
+        Ok(self
+            .move_direction(direction, pan_speed, tilt_speed)?
+            .with_watchdog(max_duration, Arc::clone(&self.clock)))
+    }
+
+    /// Keeps an idle connection from being dropped by NAT or firewalls by
+    /// sending a power inquiry every `interval`. Failed keepalives are
+    /// emitted on the handle so the caller can reconnect.
+    pub fn keep_alive(&self, interval: Duration) -> PollHandle<ViscaError> {
+        poll::keep_alive(
+            Arc::clone(&self.transport),
+            Arc::clone(&self.clock),
+            interval,
+        )
+    }
+
+    /// Moves by `dpan`/`dtilt`, reducing the delta so the target stays
+    /// within the configured limits. Returns the delta actually applied.
+    ///
+    /// Without limits the delta is only reduced to keep the target inside
+    /// the `i16` position range.
+    pub fn move_relative_clamped(
+        &mut self,
+        dpan: i16,
+        dtilt: i16,
+        pan_speed: PanSpeed,
+        tilt_speed: TiltSpeed,
+    ) -> Result<(i16, i16), ViscaError> {
+        let (pan, tilt) = self.pan_tilt_position()?;
+        let limits = self.pan_tilt_limits.unwrap_or(PanTiltLimits {
+            pan_min: i16::MIN,
+            pan_max: i16::MAX,
+            tilt_min: i16::MIN,
+            tilt_max: i16::MAX,
+        });
+
+        let pan_target =
+            (pan as i32 + dpan as i32).clamp(limits.pan_min as i32, limits.pan_max as i32);
+        let tilt_target =
+            (tilt as i32 + dtilt as i32).clamp(limits.tilt_min as i32, limits.tilt_max as i32);
+
+        let applied = (
+            clamp_to_i16(pan_target - pan as i32),
+            clamp_to_i16(tilt_target - tilt as i32),
+        );
+        if applied != (dpan, dtilt) {
+            debug!(
+                "Relative move ({}, {}) limited to ({}, {})",
+                dpan, dtilt, applied.0, applied.1
+            );
+        }
+
+        self.send(&PanTiltRelativeCommand {
+            pan_speed,
+            tilt_speed,
+            pan: applied.0,
+            tilt: applied.1,
+        })?;
+        Ok(applied)
+    }
+
+    fn lock_transport(&self) -> Result<MutexGuard<'_, Box<dyn ViscaTransport>>, ViscaError> {
+        self.transport
+            .lock()
+            .map_err(|_| ViscaError::TransportError("Transport lock poisoned".into()))
+    }
+}
+
+/// Pan/tilt (`81 01 06 ..`), zoom (`81 01 04 07/47`) and focus
+/// (`81 01 04 08/48/18`) commands.
+fn is_movement(bytes: &[u8]) -> bool {
+    matches!(
+        bytes,
+        [0x81, 0x01, 0x06, ..] | [0x81, 0x01, 0x04, 0x07 | 0x47 | 0x08 | 0x48 | 0x18, ..]
+    )
+}
+
+fn clamp_to_i16(value: i32) -> i16 {
+    value.clamp(i16::MIN as i32, i16::MAX as i32) as i16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        clock::TestClock, command::codec::decode_i16_nibbles, inquiry_frame, mock::MockTransport,
+    };
+
+    const PAN_TILT_POSITION: [u8; 4] = [0x81, 0x09, 0x06, 0x12];
+    const PAN_TILT_RELATIVE: [u8; 4] = [0x81, 0x01, 0x06, 0x03];
+
+    /// A G2 camera on `mock`, with a test clock that each read timeout
+    /// moves on by a second.
+    fn camera(mock: &MockTransport) -> Camera {
+        let clock = Arc::new(TestClock::new());
+        let transport = mock
+            .clone()
+            .with_clock(clock.clone(), Duration::from_secs(1));
+        let mut camera = Camera::new(Box::new(transport), CameraModel::PtzOpticsG2);
+        camera.set_clock(clock);
+        camera
+    }
+
+    fn at_position(mock: &MockTransport, pan: i16, tilt: i16) {
+        let frame = inquiry_frame(&ViscaInquiryResponse::PanTiltPosition { pan, tilt }).unwrap();
+        mock.respond(&PAN_TILT_POSITION, &[&frame]);
+    }
+
+    /// The pan and tilt deltas of every relative move sent.
+    fn relative_moves(mock: &MockTransport) -> Vec<(i16, i16)> {
+        mock.sent_with_prefix(&PAN_TILT_RELATIVE)
+            .iter()
+            .map(|frame| {
+                (
+                    decode_i16_nibbles(&frame[6..10]).unwrap(),
+                    decode_i16_nibbles(&frame[10..14]).unwrap(),
+                )
+            })
+            .collect()
+    }
+
+    fn limited(mock: &MockTransport) -> Camera {
+        let mut camera = camera(mock);
+        camera.set_pan_tilt_limits(Some(PanTiltLimits {
+            pan_min: -100,
+            pan_max: 100,
+            tilt_min: -50,
+            tilt_max: 50,
+        }));
+        camera
+    }
+
+    fn move_by(camera: &mut Camera, dpan: i16, dtilt: i16) -> (i16, i16) {
+        camera
+            .move_relative_clamped(dpan, dtilt, PanSpeed::LOW_SPEED, TiltSpeed::LOW_SPEED)
+            .unwrap()
+    }
+
+    #[test]
+    fn relative_move_within_limits_is_unchanged() {
+        let mock = MockTransport::acking();
+        at_position(&mock, 90, 0);
+        let mut camera = limited(&mock);
+        assert_eq!(move_by(&mut camera, -30, 20), (-30, 20));
+        assert_eq!(relative_moves(&mock), [(-30, 20)]);
+    }
+
+    #[test]
+    fn relative_move_past_a_limit_is_clipped() {
+        let mock = MockTransport::acking();
+        at_position(&mock, 90, -40);
+        let mut camera = limited(&mock);
+        assert_eq!(move_by(&mut camera, 50, -30), (10, -10));
+        assert_eq!(relative_moves(&mock), [(10, -10)]);
+    }
+
+    #[test]
+    fn relative_move_without_limits_stays_in_i16() {
+        let mock = MockTransport::acking();
+        at_position(&mock, i16::MAX - 5, 0);
+        let mut camera = camera(&mock);
+        assert_eq!(move_by(&mut camera, 100, 0), (5, 0));
+    }
+
+    const POWER_INQUIRY: [u8; 5] = [0x81, 0x09, 0x04, 0x00, 0xFF];
+
+    fn home() -> PanTiltCommand {
+        PanTiltCommand {
+            direction: PanTiltDirection::Home,
+            pan_speed: PanSpeed::LOW_SPEED,
+            tilt_speed: TiltSpeed::LOW_SPEED,
+        }
+    }
+
+    #[test]
+    fn movement_in_standby_is_blocked_without_sending() {
+        let mock = MockTransport::acking();
+        mock.respond(&POWER_INQUIRY, &[&[0x90, 0x50, 0x03, 0xFF]]);
+        let mut camera = camera(&mock);
+        camera.set_require_power_on(true);
+
+        assert!(matches!(
+            camera.send(&home()),
+            Err(ViscaError::CommandNotExecutable)
+        ));
+        assert!(matches!(
+            camera.send(&ZoomCommand::Stop),
+            Err(ViscaError::CommandNotExecutable)
+        ));
+        // The power state was inquired once and cached; nothing else went out.
+        assert_eq!(mock.sent(), [POWER_INQUIRY.to_vec()]);
+    }
+
+    #[test]
+    fn movement_when_powered_on_is_sent() {
+        let mock = MockTransport::acking();
+        mock.respond(&POWER_INQUIRY, &[&[0x90, 0x50, 0x02, 0xFF]]);
+        let mut camera = camera(&mock);
+        camera.set_require_power_on(true);
+
+        camera.send(&home()).unwrap();
+        camera.send(&home()).unwrap();
+        assert_eq!(mock.sent_with_prefix(&POWER_INQUIRY).len(), 1);
+        assert_eq!(mock.sent_with_prefix(&[0x81, 0x01, 0x06, 0x04]).len(), 2);
+    }
+
+    #[test]
+    fn power_command_refreshes_the_cached_state() {
+        let mock = MockTransport::acking();
+        mock.respond(&POWER_INQUIRY, &[&[0x90, 0x50, 0x03, 0xFF]]);
+        let mut camera = camera(&mock);
+        camera.set_require_power_on(true);
+        assert!(camera.send(&home()).is_err());
+
+        camera.send(&PowerCommand { power: Power::On }).unwrap();
+        camera.send(&home()).unwrap();
+        assert_eq!(camera.power().unwrap(), Power::On);
+        assert_eq!(mock.sent_with_prefix(&POWER_INQUIRY).len(), 1);
+    }
+
+    #[test]
+    fn non_movement_commands_are_not_gated() {
+        let mock = MockTransport::acking();
+        let mut camera = camera(&mock);
+        camera.set_require_power_on(true);
+        camera.send(&PowerCommand { power: Power::On }).unwrap();
+        assert!(mock.sent_with_prefix(&POWER_INQUIRY).is_empty());
+    }
+
+    const ZOOM_POSITION: [u8; 4] = [0x81, 0x09, 0x04, 0x47];
+
+    #[test]
+    fn watch_position_emits_only_changes_beyond_the_threshold() {
+        let mock = MockTransport::new();
+        // Moving, a jitter within the threshold, moving again, then still.
+        for pan in [0, 10, 11, 50] {
+            let frame =
+                inquiry_frame(&ViscaInquiryResponse::PanTiltPosition { pan, tilt: 0 }).unwrap();
+            mock.respond(&PAN_TILT_POSITION, &[&frame]);
+        }
+        let zoom = inquiry_frame(&ViscaInquiryResponse::ZoomPosition { position: 0x100 }).unwrap();
+        mock.respond(&ZOOM_POSITION, &[&zoom]);
+        let camera = camera(&mock);
+
+        let watch = camera.watch_position(Duration::from_millis(100), 2);
+        let timeout = Duration::from_secs(5);
+        let events: Vec<_> = (0..3)
+            .map(|_| watch.receiver().recv_timeout(timeout).unwrap())
+            .collect();
+        assert_eq!(events, [(0, 0, 0x100), (10, 0, 0x100), (50, 0, 0x100)]);
+
+        // Further polls keep reading the same position.
+        while mock.sent_with_prefix(&PAN_TILT_POSITION).len() < 10 {
+            std::thread::yield_now();
+        }
+        assert!(watch.receiver().try_recv().is_err());
+        watch.stop();
+    }
+
+    const HOME: [u8; 4] = [0x81, 0x01, 0x06, 0x04];
+    const ACK: [u8; 3] = [0x90, 0x41, 0xFF];
+    const COMPLETION: [u8; 3] = [0x90, 0x51, 0xFF];
+
+    #[test]
+    fn ack_only_pattern_ends_the_wait_on_the_ack() {
+        let mock = MockTransport::new();
+        mock.respond(&HOME, &[&ACK]);
+        let mut camera = camera(&mock);
+        // Without the expectation the wait runs into the motion timeout.
+        assert!(matches!(camera.send(&home()), Err(ViscaError::Timeout(_))));
+
+        camera.expect_reply(&HOME, ReplyPattern::AckOnly);
+        assert!(matches!(camera.send(&home()), Ok(ViscaResponse::Ack(_))));
+    }
+
+    #[test]
+    fn completion_only_pattern_needs_no_ack() {
+        let mock = MockTransport::new();
+        mock.respond(&HOME, &[&COMPLETION]);
+        let mut camera = camera(&mock);
+        camera.expect_reply(&HOME, ReplyPattern::CompletionOnly);
+        assert!(matches!(
+            camera.send(&home()),
+            Ok(ViscaResponse::Completion(_))
+        ));
+    }
+
+    #[test]
+    fn ack_and_completion_pattern_waits_past_the_ack() {
+        let mock = MockTransport::new();
+        mock.respond(&HOME, &[&ACK]);
+        mock.reply(&[&COMPLETION]);
+        let mut camera = camera(&mock);
+        camera.expect_reply(&HOME, ReplyPattern::AckAndCompletion);
+        assert!(matches!(
+            camera.send(&home()),
+            Ok(ViscaResponse::Completion(_))
+        ));
+    }
+
+    #[test]
+    fn profile_reply_patterns_apply_by_command_name() {
+        assert_eq!(
+            CameraModel::PtzOpticsG2.reply_pattern("IfClear"),
+            Some(ReplyPattern::CompletionOnly)
+        );
+        assert_eq!(CameraModel::PtzOpticsG2.reply_pattern("PanTiltHome"), None);
+
+        let mock = MockTransport::new();
+        mock.respond(&[0x81, 0x01, 0x00, 0x01], &[&COMPLETION]);
+        let mut camera = camera(&mock);
+        assert!(matches!(
+            camera.send(&IfClearCommand),
+            Ok(ViscaResponse::Completion(_))
+        ));
+    }
+
+    #[test]
+    fn reset_image_defaults_sends_the_documented_sequence() {
+        let mock = MockTransport::acking();
+        let mut camera = camera(&mock);
+        camera.reset_image_defaults().unwrap();
+        assert_eq!(
+            mock.sent(),
+            [
+                vec![0x81, 0x01, 0x04, 0x39, 0x00, 0xFF],
+                vec![0x81, 0x01, 0x04, 0x0E, 0x00, 0xFF],
+                vec![0x81, 0x01, 0x04, 0x3E, 0x03, 0xFF],
+                vec![0x81, 0x01, 0x04, 0x33, 0x03, 0xFF],
+                vec![0x81, 0x01, 0x04, 0x35, 0x00, 0xFF],
+                vec![0x81, 0x01, 0x04, 0x0C, 0x00, 0xFF],
+                vec![0x81, 0x01, 0x04, 0x02, 0x00, 0xFF],
+            ]
+        );
+    }
+
+    const STOP: [u8; 9] = [0x81, 0x01, 0x06, 0x01, 0x00, 0x00, 0x03, 0x03, 0xFF];
+
+    fn drive(camera: &mut Camera, direction: PanTiltDirection) -> MoveGuard {
+        camera
+            .move_direction(direction, PanSpeed::LOW_SPEED, TiltSpeed::LOW_SPEED)
+            .unwrap()
+    }
+
+    #[test]
+    fn dropping_the_guard_stops_the_move() {
+        let mock = MockTransport::acking();
+        let mut camera = camera(&mock);
+        let guard = drive(&mut camera, PanTiltDirection::Left);
+        assert!(mock.sent_with_prefix(&STOP).is_empty());
+        drop(guard);
+        assert_eq!(mock.sent_with_prefix(&STOP).len(), 1);
+    }
+
+    #[test]
+    fn stopping_the_guard_sends_one_stop_and_reports_errors() {
+        let mock = MockTransport::acking();
+        let mut camera = camera(&mock);
+        drive(&mut camera, PanTiltDirection::Up).stop().unwrap();
+        assert_eq!(mock.sent_with_prefix(&STOP).len(), 1);
+
+        let guard = drive(&mut camera, PanTiltDirection::Up);
+        mock.send_error(ViscaError::TransportError("down".into()));
+        assert!(guard.stop().is_err());
+    }
+
+    #[test]
+    fn a_replaced_guard_does_not_stop_the_new_move() {
+        let mock = MockTransport::acking();
+        let mut camera = camera(&mock);
+        let first = drive(&mut camera, PanTiltDirection::Left);
+        let second = drive(&mut camera, PanTiltDirection::Right);
+        drop(first);
+        assert!(mock.sent_with_prefix(&STOP).is_empty());
+        drop(second);
+        assert_eq!(mock.sent_with_prefix(&STOP).len(), 1);
+    }
+
+    /// Scripts pan readings for successive position polls, at tilt 0 and a
+    /// fixed zoom. The last reading repeats.
+    fn pan_readings(mock: &MockTransport, pans: &[i16]) {
+        for &pan in pans {
+            at_position(mock, pan, 0);
+        }
+        let zoom = inquiry_frame(&ViscaInquiryResponse::ZoomPosition { position: 0 }).unwrap();
+        mock.respond(&ZOOM_POSITION, &[&zoom]);
+    }
+
+    #[test]
+    fn wait_until_still_returns_once_positions_settle() {
+        let mock = MockTransport::new();
+        pan_readings(&mock, &[0, 40, 80, 100]);
+        let camera = camera(&mock);
+
+        let interval = Duration::from_millis(100);
+        let start = camera.clock.now();
+        let position = camera
+            .wait_until_still(interval, 3, Duration::from_secs(5))
+            .unwrap();
+        assert_eq!(position, (100, 0, 0));
+        // Three moving polls after the first reading, then three stable ones.
+        assert_eq!(mock.sent_with_prefix(&PAN_TILT_POSITION).len(), 7);
+        assert_eq!(camera.clock.now() - start, interval * 6);
+    }
+
+    #[test]
+    fn wait_until_still_times_out_while_moving() {
+        let mock = MockTransport::new();
+        let pans: Vec<i16> = (0..100).map(|step| step * 10).collect();
+        pan_readings(&mock, &pans);
+        let camera = camera(&mock);
+
+        let timeout = Duration::from_secs(1);
+        assert!(matches!(
+            camera.wait_until_still(Duration::from_millis(100), 3, timeout),
+            Err(ViscaError::Timeout(t)) if t == timeout
+        ));
+    }
+
+    #[test]
+    fn inquire_many_keeps_going_past_failures() {
+        let mock = MockTransport::new();
+        mock.respond(&POWER_INQUIRY, &[&[0x90, 0x50, 0x02, 0xFF]]);
+        // Not executable at the moment.
+        mock.respond(&ZOOM_POSITION, &[&[0x90, 0x60, 0x41, 0xFF]]);
+        at_position(&mock, 5, -5);
+        let mut camera = camera(&mock);
+
+        let results = camera.inquire_many(&[
+            InquiryCommand::Power,
+            InquiryCommand::ZoomPosition,
+            InquiryCommand::FocusPosition,
+            InquiryCommand::PanTiltPosition,
+        ]);
+        assert_eq!(results.len(), 4);
+        assert!(matches!(
+            results[0],
+            Ok(ViscaInquiryResponse::Power { power: Power::On })
+        ));
+        assert!(matches!(results[1], Err(ViscaError::CommandNotExecutable)));
+        // Unanswered, so the read timed out.
+        assert!(matches!(results[2], Err(ViscaError::Io(_))));
+        assert!(matches!(
+            results[3],
+            Ok(ViscaInquiryResponse::PanTiltPosition { pan: 5, tilt: -5 })
+        ));
+        assert_eq!(mock.sent().len(), 4);
+    }
+
+    #[test]
+    fn keep_alive_sends_a_power_inquiry_every_interval() {
+        let mock = MockTransport::new();
+        mock.respond(&POWER_INQUIRY, &[&[0x90, 0x50, 0x02, 0xFF]]);
+        let camera = camera(&mock);
+        let interval = Duration::from_secs(30);
+        let start = camera.clock.now();
+
+        let keepalive = camera.keep_alive(interval);
+        while mock.sent().len() < 3 {
+            std::thread::yield_now();
+        }
+        keepalive.stop();
+
+        let sent = mock.sent();
+        assert!(sent.iter().all(|frame| frame[..] == POWER_INQUIRY));
+        // One interval between consecutive inquiries, plus possibly the wait
+        // the loop was stopped in.
+        let elapsed = camera.clock.now() - start;
+        let gaps = sent.len() as u32 - 1;
+        assert!(
+            elapsed == interval * gaps || elapsed == interval * (gaps + 1),
+            "{:?} for {} inquiries",
+            elapsed,
+            sent.len()
+        );
+    }
+
+    #[test]
+    fn keep_alive_emits_failed_inquiries() {
+        let mock = MockTransport::new();
+        mock.send_error(ViscaError::TransportError("connection reset".into()));
+        let camera = camera(&mock);
+        let keepalive = camera.keep_alive(Duration::from_secs(30));
+        let error = keepalive
+            .receiver()
+            .recv_timeout(Duration::from_secs(5))
+            .unwrap();
+        assert!(matches!(error, ViscaError::TransportError(_)));
+    }
+
+    const IF_CLEAR: [u8; 5] = [0x81, 0x01, 0x00, 0x01, 0xFF];
+    const STANDBY: [u8; 6] = [0x81, 0x01, 0x04, 0x00, 0x03, 0xFF];
+
+    #[test]
+    fn park_clears_goes_home_then_powers_down() {
+        let mock = MockTransport::acking();
+        let mut camera = camera(&mock);
+        camera.park(true).unwrap();
+        assert_eq!(
+            mock.sent(),
+            [
+                IF_CLEAR.to_vec(),
+                vec![0x81, 0x01, 0x06, 0x04, 0xFF],
+                STANDBY.to_vec()
+            ]
+        );
+    }
+
+    #[test]
+    fn park_at_a_preset_without_standby() {
+        let mock = MockTransport::acking();
+        let mut camera = camera(&mock);
+        camera.set_park_target(ParkTarget::Preset(0x12));
+        camera.park(false).unwrap();
+        assert_eq!(
+            mock.sent(),
+            [
+                IF_CLEAR.to_vec(),
+                vec![0x81, 0x01, 0x04, 0x3F, 0x02, 0x12, 0xFF]
+            ]
+        );
+    }
+
+    #[test]
+    fn park_stops_before_standby_if_the_move_fails() {
+        let mock = MockTransport::acking();
+        mock.respond(&HOME, &[&ACK, &[0x90, 0x61, 0x41, 0xFF]]);
+        let mut camera = camera(&mock);
+        assert!(camera.park(true).is_err());
+        assert!(mock.sent_with_prefix(&STANDBY).is_empty());
+    }
+
+    const RECALL: [u8; 5] = [0x81, 0x01, 0x04, 0x3F, 0x02];
+
+    /// A test clock whose dwell waits report a stop request from the
+    /// `stop_on`th wait onwards.
+    struct StopAfter {
+        clock: TestClock,
+        waits: AtomicU64,
+        stop_on: u64,
+    }
+
+    impl Clock for StopAfter {
+        fn now(&self) -> Instant {
+            self.clock.now()
+        }
+
+        fn sleep(&self, duration: Duration) {
+            self.clock.sleep(duration);
+        }
+
+        fn wait(&self, cancel: &Receiver<()>, timeout: Duration) -> bool {
+            self.clock.wait(cancel, timeout)
+                || self.waits.fetch_add(1, Ordering::Relaxed) + 1 >= self.stop_on
+        }
+    }
+
+    fn tour() -> PresetTour {
+        PresetTour {
+            stops: vec![(3, Duration::from_secs(10)), (7, Duration::from_secs(20))],
+            recall_speed: 0x10,
+        }
+    }
+
+    #[test]
+    fn tour_runs_one_full_loop() {
+        let mock = MockTransport::acking();
+        pan_readings(&mock, &[0]);
+        let mut camera = camera(&mock);
+        let clock = Arc::new(StopAfter {
+            clock: TestClock::new(),
+            waits: AtomicU64::new(0),
+            stop_on: 2,
+        });
+        camera.set_clock(clock.clone());
+        let (_stop_tx, stop_rx) = mpsc::channel();
+
+        camera.run_tour(&tour(), &stop_rx).unwrap();
+        assert_eq!(
+            mock.sent_with_prefix(&[0x81, 0x01, 0x7E, 0x01, 0x0B]),
+            [
+                vec![0x81, 0x01, 0x7E, 0x01, 0x0B, 3, 0x10, 0xFF],
+                vec![0x81, 0x01, 0x7E, 0x01, 0x0B, 7, 0x10, 0xFF]
+            ]
+        );
+        let recalled: Vec<u8> = mock
+            .sent_with_prefix(&RECALL)
+            .iter()
+            .map(|frame| frame[5])
+            .collect();
+        assert_eq!(recalled, [3, 7]);
+        // Each stop settled before its dwell.
+        assert!(clock.clock.elapsed() >= Duration::from_secs(30));
+    }
+
+    #[test]
+    fn tour_stops_early_when_signalled() {
+        let mock = MockTransport::acking();
+        pan_readings(&mock, &[0]);
+        let mut camera = camera(&mock);
+        let (stop_tx, stop_rx) = mpsc::channel();
+        stop_tx.send(()).unwrap();
+
+        camera.run_tour(&tour(), &stop_rx).unwrap();
+        assert!(mock.sent_with_prefix(&RECALL).is_empty());
+
+        // A dropped sender stops the tour too.
+        let (stop_tx, stop_rx) = mpsc::channel::<()>();
+        drop(stop_tx);
+        camera.run_tour(&tour(), &stop_rx).unwrap();
+        assert!(mock.sent_with_prefix(&RECALL).is_empty());
+    }
+
+    fn reports_version(mock: &MockTransport, vendor_id: u16, model_id: u16) {
+        let frame = inquiry_frame(&ViscaInquiryResponse::Version {
+            vendor_id,
+            model_id,
+            rom_version: 0x0100,
+            max_socket: 2,
+        })
+        .unwrap();
+        mock.respond(&[0x81, 0x09, 0x00, 0x02], &[&frame]);
+    }
+
+    #[test]
+    fn detect_model_switches_the_profile() {
+        let mock = MockTransport::new();
+        reports_version(&mock, 0x0001, 0x0711);
+        let mut camera = camera(&mock);
+        assert_eq!(camera.detect_model().unwrap(), CameraModel::SonyFr7);
+        assert_eq!(camera.model(), CameraModel::SonyFr7);
+    }
+
+    #[test]
+    fn detect_model_falls_back_to_generic() {
+        let mock = MockTransport::new();
+        reports_version(&mock, 0x7777, 0x0001);
+        let mut camera = camera(&mock);
+        assert_eq!(camera.detect_model().unwrap(), CameraModel::Generic);
+        assert_eq!(camera.model(), CameraModel::Generic);
+    }
+
+    fn sharpness_auto(response: &ViscaInquiryResponse, auto: &bool) -> bool {
+        matches!(response, ViscaInquiryResponse::SharpnessMode { auto: a } if a == auto)
+    }
+
+    #[test]
+    fn set_and_verify_accepts_a_matching_reply() {
+        let mock = MockTransport::acking();
+        mock.respond(&[0x81, 0x09, 0x04, 0x05], &[&[0x90, 0x50, 0x02, 0xFF]]);
+        let mut camera = camera(&mock);
+
+        camera
+            .set_and_verify(
+                &crate::command::SharpnessModeCommand { auto: true },
+                &InquiryCommand::SharpnessMode,
+                true,
+                sharpness_auto,
+            )
+            .unwrap();
+        assert_eq!(
+            mock.sent(),
+            [
+                vec![0x81, 0x01, 0x04, 0x05, 0x02, 0xFF],
+                vec![0x81, 0x09, 0x04, 0x05, 0xFF]
+            ]
+        );
+    }
+
+    #[test]
+    fn set_and_verify_reports_a_mismatch() {
+        let mock = MockTransport::acking();
+        // The model ignored the setting and stayed in manual.
+        mock.respond(&[0x81, 0x09, 0x04, 0x05], &[&[0x90, 0x50, 0x03, 0xFF]]);
+        let mut camera = camera(&mock);
+
+        let result = camera.set_and_verify(
+            &crate::command::SharpnessModeCommand { auto: true },
+            &InquiryCommand::SharpnessMode,
+            true,
+            sharpness_auto,
+        );
+        assert!(matches!(
+            result,
+            Err(ViscaError::VerificationFailed { expected, actual })
+                if expected == "true" && actual.contains("auto: false")
+        ));
+    }
+
+    #[test]
+    fn unsolicited_frames_go_to_notifications() {
+        const MENU_EVENT: [u8; 4] = [0x90, 0x07, 0x01, 0xFF];
+        let mock = MockTransport::new();
+        mock.respond(
+            &HOME,
+            &[&ACK, &[0x90, 0x38, 0xFF], &MENU_EVENT, &COMPLETION],
+        );
+        let mut camera = camera(&mock);
+        let notifications = camera.notifications();
+
+        assert!(matches!(
+            camera.send(&home()),
+            Ok(ViscaResponse::Completion(_))
+        ));
+        let received: Vec<_> = notifications.try_iter().collect();
+        assert!(matches!(
+            received.as_slice(),
+            [ViscaResponse::NetworkChange, ViscaResponse::Unknown(frame)]
+                if *frame == MENU_EVENT
+        ));
+    }
+
+    #[test]
+    fn zoom_focus_direct_falls_back_to_two_commands() {
+        let mock = MockTransport::acking();
+        camera(&mock).zoom_focus_direct(0x1234, 0x0567).unwrap();
+        assert_eq!(
+            mock.sent(),
+            [
+                vec![0x81, 0x01, 0x04, 0x47, 0x01, 0x02, 0x03, 0x04, 0xFF],
+                vec![0x81, 0x01, 0x04, 0x48, 0x00, 0x05, 0x06, 0x07, 0xFF]
+            ]
+        );
+
+        let mock = MockTransport::acking();
+        let mut generic = Camera::new(Box::new(mock.clone()), CameraModel::Generic);
+        generic.zoom_focus_direct(0x1234, 0x0567).unwrap();
+        assert_eq!(
+            mock.sent(),
+            [vec![
+                0x81, 0x01, 0x04, 0x47, 0x01, 0x02, 0x03, 0x04, 0x00, 0x05, 0x06, 0x07, 0xFF
+            ]]
+        );
+    }
+
+    #[test]
+    fn sends_are_spaced_by_the_min_command_interval() {
+        let mock = MockTransport::acking();
+        let clock = Arc::new(TestClock::new());
+        let mut camera = Camera::new(Box::new(mock.clone()), CameraModel::PtzOpticsG2);
+        camera.set_clock(clock.clone());
+        let sharpness = crate::command::SharpnessModeCommand { auto: true };
+        let start = clock.now();
+
+        // No spacing by default.
+        camera.send(&sharpness).unwrap();
+        camera.send(&sharpness).unwrap();
+        assert_eq!(clock.now(), start);
+
+        camera.set_min_command_interval(Duration::from_millis(100));
+        camera.send(&sharpness).unwrap();
+        camera.send(&sharpness).unwrap();
+        assert_eq!(clock.now() - start, Duration::from_millis(200));
+        // Time already spent counts towards the gap.
+        clock.advance(Duration::from_millis(60));
+        camera.send(&sharpness).unwrap();
+        assert_eq!(clock.now() - start, Duration::from_millis(300));
+        clock.advance(Duration::from_millis(150));
+        camera.send(&sharpness).unwrap();
+        assert_eq!(clock.now() - start, Duration::from_millis(450));
+        assert_eq!(mock.sent().len(), 6);
+    }
+
+    #[test]
+    fn set_exposure_sends_the_mode_then_its_parameters() {
+        let mode = |mode: u8| vec![0x81, 0x01, 0x04, 0x39, mode, 0xFF];
+        let direct = |opcode: u8, value: u8| {
+            vec![
+                0x81,
+                0x01,
+                0x04,
+                opcode,
+                0x00,
+                0x00,
+                value >> 4,
+                value & 0x0F,
+                0xFF,
+            ]
+        };
+        let cases = [
+            (ExposureSettings::Auto, vec![mode(0x00)]),
+            (
+                ExposureSettings::Manual {
+                    iris: 0x0A,
+                    shutter: 0x11,
+                    gain: 0x03,
+                },
+                vec![
+                    mode(0x03),
+                    direct(0x4B, 0x0A),
+                    direct(0x4A, 0x11),
+                    direct(0x4C, 0x03),
+                ],
+            ),
+            (
+                ExposureSettings::ShutterPriority { shutter: 0x15 },
+                vec![mode(0x0A), direct(0x4A, 0x15)],
+            ),
+            (
+                ExposureSettings::IrisPriority { iris: 0x08 },
+                vec![mode(0x0B), direct(0x4B, 0x08)],
+            ),
+            (
+                ExposureSettings::Bright { level: 0x1B },
+                vec![mode(0x0D), direct(0x4D, 0x1B)],
+            ),
+        ];
+        for (settings, frames) in cases {
+            let mock = MockTransport::acking();
+            camera(&mock).set_exposure(settings).unwrap();
+            assert_eq!(mock.sent(), frames, "{:?}", settings);
+        }
+    }
+
+    #[test]
+    fn paused_polling_sends_no_inquiries() {
+        let mock = MockTransport::new();
+        mock.respond(&POWER_INQUIRY, &[&[0x90, 0x50, 0x02, 0xFF]]);
+        let camera = camera(&mock);
+        let interval = Duration::from_secs(30);
+
+        let keepalive = camera.keep_alive(interval);
+        while mock.sent().is_empty() {
+            std::thread::yield_now();
+        }
+        keepalive.pause();
+        assert!(keepalive.is_paused());
+        // A cycle already under way may still finish.
+        let before = mock.sent().len() + 1;
+        let paused_at = camera.clock.now();
+        while camera.clock.now() - paused_at < interval * 5 {
+            std::thread::yield_now();
+        }
+        assert!(mock.sent().len() <= before);
+
+        keepalive.resume();
+        while mock.sent().len() <= before {
+            std::thread::yield_now();
+        }
+        keepalive.stop();
+    }
+
+    const WB_MODE_INQUIRY: [u8; 5] = [0x81, 0x09, 0x04, 0x35, 0xFF];
+
+    #[test]
+    fn color_temperature_steps_need_color_temperature_mode() {
+        let mock = MockTransport::acking();
+        mock.respond(&WB_MODE_INQUIRY, &[&[0x90, 0x50, 0x20, 0xFF]]);
+        camera(&mock)
+            .adjust_color_temperature(ColorTemperatureCommand::Up)
+            .unwrap();
+        assert_eq!(
+            mock.sent(),
+            [
+                WB_MODE_INQUIRY.to_vec(),
+                vec![0x81, 0x01, 0x04, 0x20, 0x02, 0xFF]
+            ]
+        );
+
+        let mock = MockTransport::acking();
+        mock.respond(&WB_MODE_INQUIRY, &[&[0x90, 0x50, 0x00, 0xFF]]);
+        assert!(matches!(
+            camera(&mock).adjust_color_temperature(ColorTemperatureCommand::Up),
+            Err(ViscaError::InvalidParameter(message)) if message.contains("Auto")
+        ));
+        assert_eq!(mock.sent(), [WB_MODE_INQUIRY.to_vec()]);
+    }
+
+    #[test]
+    fn set_title_display_sends_the_overlay_toggle() {
+        let mock = MockTransport::acking();
+        let mut camera = camera(&mock);
+        camera.set_title_display(true).unwrap();
+        camera.set_title_display(false).unwrap();
+        assert_eq!(
+            mock.sent(),
+            [
+                vec![0x81, 0x01, 0x04, 0x74, 0x2F, 0xFF],
+                vec![0x81, 0x01, 0x04, 0x74, 0x3F, 0xFF]
+            ]
+        );
+    }
+
+    /// The `(pan, tilt)` direction bytes of each drive frame sent.
+    fn drive_directions(mock: &MockTransport) -> Vec<[u8; 2]> {
+        mock.sent_with_prefix(&[0x81, 0x01, 0x06, 0x01])
+            .iter()
+            .map(|frame| [frame[6], frame[7]])
+            .collect()
+    }
+
+    const LEFT: [u8; 2] = [0x01, 0x03];
+    const RIGHT: [u8; 2] = [0x02, 0x03];
+    const STOPPED: [u8; 2] = [0x03, 0x03];
+
+    #[test]
+    fn direction_changes_stop_first_when_enabled() {
+        let mock = MockTransport::acking();
+        let mut camera = camera(&mock);
+        camera.set_stop_before_direction_change(true);
+        let _left = drive(&mut camera, PanTiltDirection::Left);
+        let _left_again = drive(&mut camera, PanTiltDirection::Left);
+        let right = drive(&mut camera, PanTiltDirection::Right);
+        assert_eq!(drive_directions(&mock), [LEFT, LEFT, STOPPED, RIGHT]);
+
+        // Once stopped, the next drive starts straight away.
+        right.stop().unwrap();
+        let _left = drive(&mut camera, PanTiltDirection::Left);
+        assert_eq!(
+            drive_directions(&mock),
+            [LEFT, LEFT, STOPPED, RIGHT, STOPPED, LEFT]
+        );
+    }
+
+    #[test]
+    fn direction_changes_are_sent_directly_by_default() {
+        let mock = MockTransport::acking();
+        let mut camera = camera(&mock);
+        let _left = drive(&mut camera, PanTiltDirection::Left);
+        let _right = drive(&mut camera, PanTiltDirection::Right);
+        assert_eq!(drive_directions(&mock), [LEFT, RIGHT]);
+    }
+
+    #[test]
+    fn send_named_sends_the_catalog_command() {
+        let mock = MockTransport::acking();
+        let mut camera = camera(&mock);
+        camera.send_named("ZoomDirect", &[0x0500]).unwrap();
+        assert_eq!(
+            mock.sent(),
+            [vec![0x81, 0x01, 0x04, 0x47, 0x00, 0x05, 0x00, 0x00, 0xFF]]
+        );
+        assert!(camera.send_named("ZoomDirect", &[1, 2]).is_err());
+        assert_eq!(mock.sent().len(), 1);
+    }
+
+    #[test]
+    fn averaged_position_rides_out_a_noisy_reading() {
+        let mock = MockTransport::new();
+        for (pan, tilt) in [(100, -50), (102, -49), (5000, 300), (99, -51), (101, -50)] {
+            at_position(&mock, pan, tilt);
+        }
+        let mut camera = camera(&mock);
+        assert_eq!(camera.pan_tilt_position_averaged(5).unwrap(), (101, -50));
+        assert_eq!(mock.sent_with_prefix(&PAN_TILT_POSITION).len(), 5);
+
+        assert!(matches!(
+            camera.pan_tilt_position_averaged(0),
+            Err(ViscaError::InvalidParameter(_))
+        ));
+        assert_eq!(mock.sent().len(), 5);
+    }
+
+    #[test]
+    fn motion_sync_speed_set_then_get() {
+        let mock = MockTransport::acking();
+        mock.respond(&[0x81, 0x09, 0x11, 0x14], &[&[0x90, 0x50, 0x09, 0xFF]]);
+        let mut camera = camera(&mock);
+        camera
+            .set_and_verify(
+                &crate::command::MotionSyncCommand::MaxSpeed(9),
+                &InquiryCommand::MotionSyncSpeed,
+                9,
+                |response, speed| {
+                    matches!(response, ViscaInquiryResponse::MotionSyncSpeed { speed: s } if s == speed)
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            mock.sent(),
+            [
+                vec![0x81, 0x0A, 0x11, 0x14, 0x09, 0xFF],
+                vec![0x81, 0x09, 0x11, 0x14, 0xFF]
+            ]
+        );
+    }
+
+    #[test]
+    fn motion_sync_mode_set_then_get() {
+        let mock = MockTransport::acking();
+        mock.respond(&[0x81, 0x09, 0x11, 0x13], &[&[0x90, 0x50, 0x01, 0xFF]]);
+        let mut camera = camera(&mock);
+        camera
+            .set_and_verify(
+                &crate::command::MotionSyncCommand::On,
+                &InquiryCommand::MotionSync,
+                true,
+                |response, enabled| {
+                    matches!(response, ViscaInquiryResponse::MotionSync { enabled: e } if e == enabled)
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            mock.sent(),
+            [
+                vec![0x81, 0x0A, 0x11, 0x13, 0x02, 0xFF],
+                vec![0x81, 0x09, 0x11, 0x13, 0xFF]
+            ]
+        );
+    }
+
+    #[test]
+    fn last_preset_reads_the_number_where_supported() {
+        let mock = MockTransport::new();
+        mock.respond(&[0x81, 0x09, 0x04, 0x3F], &[&[0x90, 0x50, 0x2A, 0xFF]]);
+        let mut generic = Camera::new(Box::new(mock.clone()), CameraModel::Generic);
+        assert_eq!(generic.last_preset().unwrap(), 0x2A);
+
+        for model in [CameraModel::PtzOpticsG2, CameraModel::SonyFr7] {
+            let mock = MockTransport::new();
+            let mut camera = Camera::new(Box::new(mock.clone()), model);
+            assert!(matches!(
+                camera.last_preset(),
+                Err(ViscaError::InvalidParameter(_))
+            ));
+            assert!(mock.sent().is_empty());
+        }
+    }
+
+    #[test]
+    fn drive_watchdog_stops_a_runaway_move() {
+        let mock = MockTransport::acking();
+        let mut camera = camera(&mock);
+        let start = camera.clock.now();
+        let guard = camera
+            .drive_continuous(
+                PanTiltDirection::Left,
+                PanSpeed::LOW_SPEED,
+                TiltSpeed::LOW_SPEED,
+                Duration::from_secs(5),
+            )
+            .unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while mock.sent_with_prefix(&STOP).is_empty() {
+            assert!(
+                Instant::now() < deadline,
+                "watchdog never stopped the drive"
+            );
+            std::thread::yield_now();
+        }
+        assert!(camera.clock.now() - start >= Duration::from_secs(5));
+        // The watchdog released the drive, so dropping the guard is a no-op.
+        drop(guard);
+        assert_eq!(mock.sent_with_prefix(&STOP).len(), 1);
+    }
+
+    #[test]
+    fn stopping_before_the_watchdog_cancels_it() {
+        let mock = MockTransport::acking();
+        let clock = Arc::new(TestClock::manual());
+        let mut camera = Camera::new(Box::new(mock.clone()), CameraModel::PtzOpticsG2);
+        camera.set_clock(clock.clone());
+        camera
+            .drive_continuous(
+                PanTiltDirection::Left,
+                PanSpeed::LOW_SPEED,
+                TiltSpeed::LOW_SPEED,
+                Duration::from_secs(60),
+            )
+            .unwrap()
+            .stop()
+            .unwrap();
+        clock.advance(Duration::from_secs(61));
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while clock.finished_waits().is_empty() {
+            assert!(Instant::now() < deadline, "watchdog never woke up");
+            std::thread::yield_now();
+        }
+        // The watchdog saw the cancel rather than its timeout.
+        assert_eq!(clock.finished_waits(), [true]);
+        assert_eq!(mock.sent_with_prefix(&STOP).len(), 1);
+    }
+
+    #[test]
+    fn is_menu_open_reads_both_states() {
+        const MENU_INQUIRY: [u8; 5] = [0x81, 0x09, 0x06, 0x06, 0xFF];
+        let mock = MockTransport::new();
+        mock.respond(&MENU_INQUIRY, &[&[0x90, 0x50, 0x02, 0xFF]])
+            .respond(&MENU_INQUIRY, &[&[0x90, 0x50, 0x03, 0xFF]]);
+        let mut camera = camera(&mock);
+        assert!(camera.is_menu_open().unwrap());
+        assert!(!camera.is_menu_open().unwrap());
+        assert_eq!(mock.sent(), [MENU_INQUIRY.to_vec(), MENU_INQUIRY.to_vec()]);
+    }
+
+    fn recall(preset_number: u8) -> PresetCommand {
+        PresetCommand {
+            action: PresetAction::Recall,
+            preset_number,
+        }
+    }
+
+    /// Recalls preset 1 when zoomed past the midpoint and preset 2 otherwise.
+    fn zoom_branch() -> Sequence {
+        Sequence::new().when(
+            InquiryCommand::ZoomPosition,
+            |response| {
+                matches!(response, ViscaInquiryResponse::ZoomPosition { position } if *position > 0x2000)
+            },
+            Sequence::new().command(recall(1)),
+            Sequence::new().command(recall(2)),
+        )
+    }
+
+    #[test]
+    fn sequence_branch_taken_and_not_taken() {
+        for (zoom, preset) in [(0x3000, 1), (0x1000, 2)] {
+            let mock = MockTransport::acking();
+            let frame =
+                inquiry_frame(&ViscaInquiryResponse::ZoomPosition { position: zoom }).unwrap();
+            mock.respond(&ZOOM_POSITION, &[&frame]);
+            camera(&mock).run_sequence(&zoom_branch()).unwrap();
+            assert_eq!(
+                mock.sent_with_prefix(&RECALL),
+                [vec![0x81, 0x01, 0x04, 0x3F, 0x02, preset, 0xFF]]
+            );
+        }
+    }
+
+    #[test]
+    fn sequence_steps_run_in_order_with_bounded_repeats() {
+        let mock = MockTransport::acking();
+        let frame = inquiry_frame(&ViscaInquiryResponse::ZoomPosition { position: 0x10 }).unwrap();
+        mock.respond(&ZOOM_POSITION, &[&frame]);
+        let mut camera = camera(&mock);
+        let start = camera.clock.now();
+        let sequence = Sequence::new()
+            .repeat(2, Sequence::new().command(home()))
+            .delay(Duration::from_secs(3))
+            .inquiry(InquiryCommand::ZoomPosition);
+
+        let results = camera.run_sequence(&sequence).unwrap();
+        assert_eq!(
+            results,
+            [ViscaInquiryResponse::ZoomPosition { position: 0x10 }]
+        );
+        assert_eq!(mock.sent_with_prefix(&HOME).len(), 2);
+        assert_eq!(camera.clock.now() - start, Duration::from_secs(3));
+
+        let runaway = Sequence::new().repeat(MAX_SEQUENCE_REPEAT + 1, Sequence::new());
+        assert!(matches!(
+            camera.run_sequence(&runaway),
+            Err(ViscaError::ParameterOutOfRange { .. })
+        ));
+    }
+
+    #[test]
+    fn strict_cameras_fail_on_unknown_frames() {
+        let mock = MockTransport::new();
+        mock.respond(&HOME, &[&ACK, &[0x90, 0x07, 0x01, 0xFF], &COMPLETION]);
+        let mut camera = camera(&mock);
+        assert!(camera.send(&home()).is_ok());
+        camera.set_strict(true);
+        assert!(matches!(
+            camera.send(&home()),
+            Err(ViscaError::ParseError(_))
+        ));
+    }
+
+    #[test]
+    fn ae_level_is_refused_on_profiles_without_it() {
+        let mock = MockTransport::acking();
+        assert!(matches!(
+            camera(&mock).set_ae_level(0x05),
+            Err(ViscaError::InvalidParameter(_))
+        ));
+        assert!(mock.sent().is_empty());
+
+        let mut generic = Camera::new(Box::new(mock.clone()), CameraModel::Generic);
+        generic.set_ae_level(0x05).unwrap();
+        assert_eq!(
+            mock.sent(),
+            [[0x81, 0x01, 0x7E, 0x01, 0x4C, 0x00, 0x00, 0x00, 0x05, 0xFF]]
+        );
+    }
+
+    #[test]
+    fn clear_all_presets_resets_each_number_in_turn() {
+        let mock = MockTransport::acking();
+        let mut cleared = Vec::new();
+        camera(&mock)
+            .clear_all_presets(3..=5, |number| cleared.push(number))
+            .unwrap();
+        assert_eq!(cleared, [3, 4, 5]);
+        assert_eq!(
+            mock.sent(),
+            [3, 4, 5].map(|number| vec![0x81, 0x01, 0x04, 0x3F, 0x00, number, 0xFF])
+        );
+    }
+
+    #[test]
+    fn clear_all_presets_stops_at_the_first_failure() {
+        let mock = MockTransport::acking();
+        mock.respond(
+            &[0x81, 0x01, 0x04, 0x3F, 0x00, 0x04],
+            &[&[0x90, 0x61, 0x41, 0xFF]],
+        );
+        let mut cleared = Vec::new();
+        assert!(camera(&mock)
+            .clear_all_presets(3..=5, |number| cleared.push(number))
+            .is_err());
+        assert_eq!(cleared, [3]);
+        assert_eq!(mock.sent().len(), 2);
+
+        let mock = MockTransport::acking();
+        assert!(matches!(
+            camera(&mock).clear_all_presets(0..=MAX_PRESET + 1, |_| {}),
+            Err(ViscaError::ParameterOutOfRange { .. })
+        ));
+        assert!(mock.sent().is_empty());
+    }
+
+    #[test]
+    fn completion_timeouts_can_be_overridden_by_name() {
+        let mock = MockTransport::acking();
+        mock.respond(&RECALL, &[&ACK]);
+        let clock = Arc::new(TestClock::new());
+        let transport = mock
+            .clone()
+            .with_clock(clock.clone(), Duration::from_secs(1));
+        let mut camera = Camera::new(Box::new(transport), CameraModel::Generic);
+        camera.set_clock(clock);
+
+        camera.set_completion_timeout("PresetRecall", Some(Duration::from_secs(2)));
+        assert!(matches!(
+            camera.send(&recall(2)),
+            Err(ViscaError::Timeout(timeout)) if timeout == Duration::from_secs(2)
+        ));
+        camera.set_completion_timeout("PresetRecall", None);
+        assert!(matches!(
+            camera.send(&recall(2)),
+            Err(ViscaError::Timeout(timeout)) if timeout == crate::DEFAULT_MOTION_COMPLETION_TIMEOUT
+        ));
+    }
+
+    #[test]
+    fn zoom_to_ratio_needs_a_lens_and_sends_direct() {
+        let mock = MockTransport::acking();
+        let mut camera = camera(&mock);
+        assert!(matches!(
+            camera.zoom_to_ratio(4.5),
+            Err(ViscaError::InvalidParameter(_))
+        ));
+
+        camera.set_zoom_lens(Some(ZoomLens::SonyBlock30x));
+        camera.zoom_to_ratio(4.5).unwrap();
+        camera.zoom_to_ratio(100.0).unwrap();
+        assert!(camera.zoom_to_ratio(f32::NAN).is_err());
+        assert_eq!(
+            mock.sent(),
+            [
+                vec![0x81, 0x01, 0x04, 0x47, 0x02, 0x08, 0x02, 0x03, 0xFF],
+                vec![0x81, 0x01, 0x04, 0x47, 0x04, 0x00, 0x00, 0x00, 0xFF]
+            ]
+        );
+    }
+
+    #[test]
+    fn ramp_is_refused_on_profiles_without_it() {
+        let mock = MockTransport::acking();
+        assert!(matches!(
+            camera(&mock).set_ramp(2),
+            Err(ViscaError::InvalidParameter(_))
+        ));
+        assert!(mock.sent().is_empty());
+
+        let mut fr7 = Camera::new(Box::new(mock.clone()), CameraModel::SonyFr7);
+        fr7.set_ramp(2).unwrap();
+        assert_eq!(mock.sent(), [[0x81, 0x01, 0x7E, 0x01, 0x5B, 0x02, 0xFF]]);
+    }
+
+    #[test]
+    fn test_clock_runs_timeouts_and_keepalives_without_real_waits() {
+        let real_start = std::time::Instant::now();
+
+        // A minute-long Completion timeout, one second per read timeout.
+        let mock = MockTransport::new();
+        mock.respond(&RECALL, &[&ACK]);
+        let mut camera = camera(&mock);
+        let start = camera.clock.now();
+        assert!(matches!(
+            camera.send(&recall(2)),
+            Err(ViscaError::Timeout(timeout)) if timeout == crate::DEFAULT_MOTION_COMPLETION_TIMEOUT
+        ));
+        let waited = camera.clock.now() - start;
+        assert!(waited >= crate::DEFAULT_MOTION_COMPLETION_TIMEOUT);
+
+        // Hourly keepalives.
+        mock.respond(&POWER_INQUIRY, &[&[0x90, 0x50, 0x02, 0xFF]]);
+        let before = mock.sent_with_prefix(&POWER_INQUIRY).len();
+        let start = camera.clock.now();
+        let keepalive = camera.keep_alive(Duration::from_secs(3600));
+        while mock.sent_with_prefix(&POWER_INQUIRY).len() < before + 3 {
+            std::thread::yield_now();
+        }
+        keepalive.stop();
+        assert!(camera.clock.now() - start >= Duration::from_secs(2 * 3600));
+
+        assert!(real_start.elapsed() < Duration::from_secs(5));
+    }
+
+    /// Info-level lines logged on each thread, captured for the trace tests.
+    static LOGGED: Mutex<Vec<(std::thread::ThreadId, String)>> = Mutex::new(Vec::new());
+
+    struct CaptureLogger;
+
+    impl log::Log for CaptureLogger {
+        fn enabled(&self, metadata: &log::Metadata) -> bool {
+            metadata.level() <= log::Level::Info
+        }
+
+        fn log(&self, record: &log::Record) {
+            if self.enabled(record.metadata()) {
+                LOGGED
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+                    .push((std::thread::current().id(), record.args().to_string()));
+            }
+        }
+
+        fn flush(&self) {}
+    }
+
+    /// Lines logged so far on the current thread.
+    fn logged_here() -> Vec<String> {
+        static INSTALL: std::sync::Once = std::sync::Once::new();
+        INSTALL.call_once(|| {
+            log::set_logger(&CaptureLogger).unwrap();
+            log::set_max_level(log::LevelFilter::Info);
+        });
+        let here = std::thread::current().id();
+        LOGGED
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .iter()
+            .filter(|(thread, _)| *thread == here)
+            .map(|(_, line)| line.clone())
+            .collect()
+    }
+
+    #[test]
+    fn traced_commands_log_their_name_and_frame() {
+        let mock = MockTransport::acking();
+        let mut camera = camera(&mock);
+        let sharpness = crate::command::SharpnessModeCommand { auto: true };
+        let traces = || {
+            logged_here()
+                .into_iter()
+                .filter(|line| line.starts_with("-> "))
+                .collect::<Vec<_>>()
+        };
+
+        assert!(traces().is_empty());
+        camera.send(&sharpness).unwrap();
+        assert!(traces().is_empty());
+
+        camera.set_trace_commands(true);
+        camera.send(&sharpness).unwrap();
+        assert_eq!(traces(), ["-> SharpnessMode 81 01 04 05 02 FF"]);
+    }
+}
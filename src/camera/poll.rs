@@ -0,0 +1,186 @@
+use log::{debug, error};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, Sender},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use super::SharedTransport;
+use crate::{
+    command::InquiryCommand, send_command_and_wait, Clock, ViscaError, ViscaInquiryResponse,
+    ViscaResponse,
+};
+
+/// Handle to a background polling loop. Dropping it stops the loop.
+pub struct PollHandle<T> {
+    receiver: Receiver<T>,
+    stop: Option<Sender<()>>,
+    paused: Arc<AtomicBool>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl<T> PollHandle<T> {
+    /// Events emitted by the loop.
+    pub fn receiver(&self) -> &Receiver<T> {
+        &self.receiver
+    }
+
+    /// Skips the loop's inquiries until [`resume`](Self::resume), leaving the
+    /// bus free for manual control. The loop keeps its interval and state.
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::Relaxed);
+    }
+
+    /// Restarts inquiries from the next cycle after [`pause`](Self::pause).
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::Relaxed);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    /// Stops the loop and waits for the worker thread to exit.
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        // Dropping the sender wakes the worker out of its interval wait.
+        self.stop.take();
+        if let Some(worker) = self.worker.take() {
+            if worker.join().is_err() {
+                error!("Polling thread panicked");
+            }
+        }
+    }
+}
+
+impl<T> Drop for PollHandle<T> {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// Runs `tick` every `interval` on a worker thread and forwards whatever it
+/// returns to the handle's receiver. Cycles are skipped while paused; the
+/// interval is waited out on `clock`.
+fn spawn<T, F>(
+    transport: SharedTransport,
+    clock: Arc<dyn Clock>,
+    interval: Duration,
+    mut tick: F,
+) -> PollHandle<T>
+where
+    T: Send + 'static,
+    F: FnMut(&SharedTransport) -> Option<T> + Send + 'static,
+{
+    let (event_tx, event_rx) = mpsc::channel();
+    let (stop_tx, stop_rx) = mpsc::channel::<()>();
+    let paused = Arc::new(AtomicBool::new(false));
+    let worker_paused = Arc::clone(&paused);
+
+    let worker = thread::spawn(move || {
+        loop {
+            if !worker_paused.load(Ordering::Relaxed) {
+                if let Some(event) = tick(&transport) {
+                    if event_tx.send(event).is_err() {
+                        break;
+                    }
+                }
+            }
+
+            if clock.wait(&stop_rx, interval) {
+                break;
+            }
+        }
+        debug!("Polling loop stopped");
+    });
+
+    PollHandle {
+        receiver: event_rx,
+        stop: Some(stop_tx),
+        paused,
+        worker: Some(worker),
+    }
+}
+
+/// Polls pan/tilt/zoom every `interval` and emits `(pan, tilt, zoom)` when
+/// any value moved by more than `threshold` since the last emission.
+pub(super) fn watch_position(
+    transport: SharedTransport,
+    clock: Arc<dyn Clock>,
+    interval: Duration,
+    threshold: u16,
+) -> PollHandle<(i16, i16, u16)> {
+    let mut last: Option<(i16, i16, u16)> = None;
+    spawn(
+        transport,
+        clock,
+        interval,
+        move |transport| match read_position(transport) {
+            Ok(position) if last.is_none_or(|last| changed(last, position, threshold)) => {
+                last = Some(position);
+                Some(position)
+            }
+            Ok(_) => None,
+            Err(e) => {
+                error!("Position poll failed: {}", e);
+                None
+            }
+        },
+    )
+}
+
+/// Sends a power inquiry every `interval`, discarding the reply, and emits
+/// the error whenever one fails.
+pub(super) fn keep_alive(
+    transport: SharedTransport,
+    clock: Arc<dyn Clock>,
+    interval: Duration,
+) -> PollHandle<ViscaError> {
+    spawn(transport, clock, interval, |transport| {
+        let result = transport
+            .lock()
+            .map_err(|_| ViscaError::TransportError("Transport lock poisoned".into()))
+            .and_then(|mut transport| {
+                send_command_and_wait(&mut **transport, &InquiryCommand::Power)
+            });
+        match result {
+            Ok(_) => None,
+            Err(e) => {
+                error!("Keepalive inquiry failed: {}", e);
+                Some(e)
+            }
+        }
+    })
+}
+
+pub(super) fn read_position(transport: &SharedTransport) -> Result<(i16, i16, u16), ViscaError> {
+    let mut transport = transport
+        .lock()
+        .map_err(|_| ViscaError::TransportError("Transport lock poisoned".into()))?;
+
+    let (pan, tilt) =
+        match send_command_and_wait(&mut **transport, &InquiryCommand::PanTiltPosition)? {
+            ViscaResponse::InquiryResponse(ViscaInquiryResponse::PanTiltPosition { pan, tilt }) => {
+                (pan, tilt)
+            }
+            _ => return Err(ViscaError::UnexpectedResponseType),
+        };
+    let zoom = match send_command_and_wait(&mut **transport, &InquiryCommand::ZoomPosition)? {
+        ViscaResponse::InquiryResponse(ViscaInquiryResponse::ZoomPosition { position }) => position,
+        _ => return Err(ViscaError::UnexpectedResponseType),
+    };
+    Ok((pan, tilt, zoom))
+}
+
+fn changed(last: (i16, i16, u16), current: (i16, i16, u16), threshold: u16) -> bool {
+    last.0.abs_diff(current.0) > threshold
+        || last.1.abs_diff(current.1) > threshold
+        || last.2.abs_diff(current.2) > threshold
+}
@@ -0,0 +1,81 @@
+use std::time::Duration;
+
+use crate::{command::InquiryCommand, ViscaCommand, ViscaInquiryResponse};
+
+/// Upper bound on [`Sequence::repeat`] counts, so a scripted sequence can't
+/// run away.
+pub const MAX_SEQUENCE_REPEAT: u32 = 100;
+
+type Condition = Box<dyn Fn(&ViscaInquiryResponse) -> bool>;
+
+pub(super) enum Step {
+    Command(Box<dyn ViscaCommand>),
+    Inquiry(InquiryCommand),
+    Delay(Duration),
+    When {
+        inquiry: InquiryCommand,
+        condition: Condition,
+        then: Sequence,
+        otherwise: Sequence,
+    },
+    Repeat {
+        times: u32,
+        body: Sequence,
+    },
+}
+
+/// A small scripted macro of commands, inquiries, delays and branches, run
+/// with [`crate::Camera::run_sequence`]. Branches test an inquiry reply, e.g.
+/// recalling one preset when the zoom position is past a threshold and
+/// another otherwise.
+#[derive(Default)]
+#[must_use]
+pub struct Sequence {
+    pub(super) steps: Vec<Step>,
+}
+
+impl Sequence {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn command(mut self, command: impl ViscaCommand + 'static) -> Self {
+        self.steps.push(Step::Command(Box::new(command)));
+        self
+    }
+
+    /// Sends `inquiry`; its reply is included in the run's results.
+    pub fn inquiry(mut self, inquiry: InquiryCommand) -> Self {
+        self.steps.push(Step::Inquiry(inquiry));
+        self
+    }
+
+    pub fn delay(mut self, duration: Duration) -> Self {
+        self.steps.push(Step::Delay(duration));
+        self
+    }
+
+    /// Sends `inquiry` and runs `then` if `condition` holds for the reply,
+    /// `otherwise` if not.
+    pub fn when(
+        mut self,
+        inquiry: InquiryCommand,
+        condition: impl Fn(&ViscaInquiryResponse) -> bool + 'static,
+        then: Sequence,
+        otherwise: Sequence,
+    ) -> Self {
+        self.steps.push(Step::When {
+            inquiry,
+            condition: Box::new(condition),
+            then,
+            otherwise,
+        });
+        self
+    }
+
+    /// Runs `body` `times` times, at most [`MAX_SEQUENCE_REPEAT`].
+    pub fn repeat(mut self, times: u32, body: Sequence) -> Self {
+        self.steps.push(Step::Repeat { times, body });
+        self
+    }
+}
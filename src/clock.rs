@@ -0,0 +1,181 @@
+//! Time source for [`crate::Camera`]'s pacing, retries, timeouts and
+//! background loops, so they can be driven without real sleeps.
+
+#[cfg(test)]
+use std::sync::{mpsc::TryRecvError, Mutex, MutexGuard};
+use std::{
+    sync::mpsc::{Receiver, RecvTimeoutError},
+    thread,
+    time::{Duration, Instant},
+};
+
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+    fn sleep(&self, duration: Duration);
+
+    /// Waits up to `timeout` for `cancel` to receive a value or lose its
+    /// sender, returning `true` if it did. Background loops use this for
+    /// their interval waits so they can be stopped promptly.
+    fn wait(&self, cancel: &Receiver<()>, timeout: Duration) -> bool {
+        !matches!(cancel.recv_timeout(timeout), Err(RecvTimeoutError::Timeout))
+    }
+}
+
+/// The real clock: [`Instant::now`] and [`thread::sleep`].
+#[derive(Debug, Default, Copy, Clone)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        thread::sleep(duration);
+    }
+}
+
+/// A clock that only moves when told to. `sleep` returns at once after
+/// advancing the clock by the requested duration, so time-dependent code
+/// runs deterministically in tests.
+#[cfg(test)]
+#[derive(Debug)]
+pub(crate) struct TestClock {
+    start: Instant,
+    elapsed: Mutex<Duration>,
+    /// Whether `wait` blocks until the test advances the clock, see
+    /// [`manual`](Self::manual).
+    manual: bool,
+    /// Outcome of each finished `wait` of a manual clock.
+    finished_waits: Mutex<Vec<bool>>,
+}
+
+#[cfg(test)]
+impl TestClock {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            elapsed: Mutex::new(Duration::ZERO),
+            manual: false,
+            finished_waits: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Like [`new`](Self::new), but `wait` blocks until the test advances
+    /// the clock past its timeout or the wait is cancelled, so a test
+    /// decides which of the two happens first.
+    pub fn manual() -> Self {
+        Self {
+            manual: true,
+            ..Self::new()
+        }
+    }
+
+    /// How each finished `wait` of a [`manual`](Self::manual) clock ended,
+    /// in order: `true` if cancelled, `false` if it timed out.
+    pub fn finished_waits(&self) -> Vec<bool> {
+        self.finished_waits
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        *self.lock() += duration;
+    }
+
+    /// Total time advanced since the clock was created.
+    pub fn elapsed(&self) -> Duration {
+        *self.lock()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Duration> {
+        // A panic mid-update can't leave a Duration inconsistent.
+        self.elapsed
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+impl Default for TestClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+impl Clock for TestClock {
+    fn now(&self) -> Instant {
+        self.start + self.elapsed()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        self.advance(duration);
+    }
+
+    fn wait(&self, cancel: &Receiver<()>, timeout: Duration) -> bool {
+        if self.manual {
+            let deadline = self.elapsed() + timeout;
+            let cancelled = loop {
+                if !matches!(cancel.try_recv(), Err(TryRecvError::Empty)) {
+                    break true;
+                }
+                if self.elapsed() >= deadline {
+                    break false;
+                }
+                thread::sleep(Duration::from_millis(1));
+            };
+            self.finished_waits
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .push(cancelled);
+            return cancelled;
+        }
+        match cancel.try_recv() {
+            Err(TryRecvError::Empty) => {
+                self.advance(timeout);
+                // Let the thread that would cancel the wait get a look in.
+                thread::yield_now();
+                false
+            }
+            _ => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn test_clock_sleep_advances_without_blocking() {
+        let clock = TestClock::new();
+        let start = clock.now();
+        clock.sleep(Duration::from_secs(3600));
+        assert_eq!(clock.now() - start, Duration::from_secs(3600));
+        assert_eq!(clock.elapsed(), Duration::from_secs(3600));
+    }
+
+    #[test]
+    fn test_clock_wait_times_out_until_cancelled() {
+        let clock = TestClock::new();
+        let (cancel_tx, cancel_rx) = mpsc::channel();
+        assert!(!clock.wait(&cancel_rx, Duration::from_secs(5)));
+        assert_eq!(clock.elapsed(), Duration::from_secs(5));
+
+        cancel_tx.send(()).unwrap();
+        assert!(clock.wait(&cancel_rx, Duration::from_secs(5)));
+        drop(cancel_tx);
+        assert!(clock.wait(&cancel_rx, Duration::from_secs(5)));
+        assert_eq!(clock.elapsed(), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn system_clock_wait_returns_when_sender_dropped() {
+        let (cancel_tx, cancel_rx) = mpsc::channel::<()>();
+        drop(cancel_tx);
+        assert!(SystemClock.wait(&cancel_rx, Duration::from_secs(60)));
+    }
+}
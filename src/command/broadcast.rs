@@ -0,0 +1,129 @@
+//! Broadcast color-matching controls.
+//!
+//! These use the Sony extended (`7E`) opcode family and are only accepted by
+//! the `SonyFr7` profile's cameras; PTZOptics G2 cameras reject them.
+
+use crate::command::ViscaCommand;
+use crate::error::{RangeFormat, ViscaError};
+
+use super::ViscaResponseType;
+
+fn direct_bytes(op1: u8, op2: u8, value: u8) -> Vec<u8> {
+    vec![
+        0x81,
+        0x01,
+        0x7E,
+        op1,
+        op2,
+        0x00,
+        0x00,
+        value >> 4,
+        value & 0x0F,
+        0xFF,
+    ]
+}
+
+#[derive(Debug, Copy, Clone)]
+pub enum KneeCommand {
+    /// Knee point, `81 01 7E 01 6F 00 00 0p 0q FF` (pq: 0x00 ~ 0x0C).
+    Point(u8),
+    /// Knee slope, `81 01 7E 01 6E 00 00 0p 0q FF` (pq: 0x00 ~ 0x0E).
+    Slope(u8),
+}
+
+impl ViscaCommand for KneeCommand {
+    fn to_bytes(&self) -> Result<Vec<u8>, ViscaError> {
+        match self {
+            KneeCommand::Point(point) => {
+                if *point <= 0x0C {
+                    Ok(direct_bytes(0x01, 0x6F, *point))
+                } else {
+                    Err(ViscaError::out_of_range("Knee point", *point, 0, 12))
+                }
+            }
+            KneeCommand::Slope(slope) => {
+                if *slope <= 0x0E {
+                    Ok(direct_bytes(0x01, 0x6E, *slope))
+                } else {
+                    Err(ViscaError::out_of_range("Knee slope", *slope, 0, 14))
+                }
+            }
+        }
+    }
+
+    fn response_type(&self) -> Option<ViscaResponseType> {
+        None
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            KneeCommand::Point(_) => "KneePoint",
+            KneeCommand::Slope(_) => "KneeSlope",
+        }
+    }
+}
+
+/// Black level (pedestal), `81 01 7E 04 48 00 00 0p 0q FF` (pq: 0x00 ~ 0x30).
+pub struct BlackLevelCommand {
+    pub level: u8,
+}
+
+impl ViscaCommand for BlackLevelCommand {
+    fn to_bytes(&self) -> Result<Vec<u8>, ViscaError> {
+        if self.level <= 0x30 {
+            Ok(direct_bytes(0x04, 0x48, self.level))
+        } else {
+            Err(ViscaError::out_of_range("Black level", self.level, 0, 48)
+                .with_range_format(RangeFormat::Hex))
+        }
+    }
+
+    fn response_type(&self) -> Option<ViscaResponseType> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::InquiryCommand;
+
+    #[test]
+    fn knee_frames_and_limits() {
+        assert_eq!(
+            KneeCommand::Point(0x0C).to_bytes().unwrap(),
+            [0x81, 0x01, 0x7E, 0x01, 0x6F, 0x00, 0x00, 0x00, 0x0C, 0xFF]
+        );
+        assert_eq!(
+            KneeCommand::Slope(0x0E).to_bytes().unwrap(),
+            [0x81, 0x01, 0x7E, 0x01, 0x6E, 0x00, 0x00, 0x00, 0x0E, 0xFF]
+        );
+        assert!(KneeCommand::Point(0x0D).to_bytes().is_err());
+        assert!(KneeCommand::Slope(0x0F).to_bytes().is_err());
+    }
+
+    #[test]
+    fn black_level_frame_and_limit() {
+        assert_eq!(
+            BlackLevelCommand { level: 0x30 }.to_bytes().unwrap(),
+            [0x81, 0x01, 0x7E, 0x04, 0x48, 0x00, 0x00, 0x03, 0x00, 0xFF]
+        );
+        assert!(BlackLevelCommand { level: 0x31 }.to_bytes().is_err());
+    }
+
+    #[test]
+    fn inquiries_share_the_set_opcodes() {
+        for (inquiry, set) in [
+            (InquiryCommand::KneePoint, KneeCommand::Point(0).to_bytes()),
+            (InquiryCommand::KneeSlope, KneeCommand::Slope(0).to_bytes()),
+            (
+                InquiryCommand::BlackLevel,
+                BlackLevelCommand { level: 0 }.to_bytes(),
+            ),
+        ] {
+            let inquiry = inquiry.to_bytes().unwrap();
+            assert_eq!(inquiry[1], 0x09);
+            assert_eq!(inquiry[2..5], set.unwrap()[2..5]);
+        }
+    }
+}
@@ -0,0 +1,183 @@
+//! Builds commands from their [`ViscaCommand::name`] and integer
+//! parameters, for scripting layers that map cue strings to camera actions.
+
+use super::{
+    pan_tilt::{PanSpeed, PanTiltDirection, TiltSpeed},
+    preset::PresetAction,
+    FocusCommand, PanTiltAbsoluteCommand, PanTiltCommand, PanTiltRelativeCommand, Power,
+    PowerCommand, PresetCommand, ViscaCommand, ZoomCommand,
+};
+use crate::error::ViscaError;
+
+/// Names accepted by [`named`], with their parameters in order.
+pub const NAMED_COMMANDS: &[(&str, &[&str])] = &[
+    ("ZoomStop", &[]),
+    ("ZoomTeleStandard", &[]),
+    ("ZoomWideStandard", &[]),
+    ("ZoomTeleVariable", &["speed"]),
+    ("ZoomWideVariable", &["speed"]),
+    ("ZoomDirect", &["position"]),
+    ("FocusStop", &[]),
+    ("FocusFarStandard", &[]),
+    ("FocusNearStandard", &[]),
+    ("FocusFarVariable", &["speed"]),
+    ("FocusNearVariable", &["speed"]),
+    ("FocusDirect", &["position"]),
+    ("FocusAuto", &[]),
+    ("FocusManual", &[]),
+    ("FocusToggleAutoManual", &[]),
+    ("FocusOnePushTrigger", &[]),
+    ("PanTiltStop", &[]),
+    ("PanTiltHome", &[]),
+    (
+        "PanTiltAbsolute",
+        &["pan speed", "tilt speed", "pan", "tilt"],
+    ),
+    (
+        "PanTiltRelative",
+        &["pan speed", "tilt speed", "pan", "tilt"],
+    ),
+    ("PresetSet", &["preset"]),
+    ("PresetRecall", &["preset"]),
+    ("PresetReset", &["preset"]),
+    ("PowerOn", &[]),
+    ("PowerStandby", &[]),
+];
+
+/// Builds the command called `name` from `params`. Unknown names, a wrong
+/// parameter count and values outside the parameter's type fail here; the
+/// command's own range checks apply when it is encoded.
+pub fn named(name: &str, params: &[i64]) -> Result<Box<dyn ViscaCommand>, ViscaError> {
+    let unknown = || ViscaError::InvalidParameter(format!("Unknown command: {}", name));
+    let (_, expected) = NAMED_COMMANDS
+        .iter()
+        .find(|(known, _)| *known == name)
+        .ok_or_else(unknown)?;
+    if params.len() != expected.len() {
+        return Err(ViscaError::InvalidParameter(format!(
+            "{} takes {} parameters ({}), got {}",
+            name,
+            expected.len(),
+            expected.join(", "),
+            params.len()
+        )));
+    }
+
+    let u8_param = |index: usize| param::<u8>(params[index], expected[index], 0, 0xFF);
+    let u16_param = |index: usize| param::<u16>(params[index], expected[index], 0, 0xFFFF);
+    let i16_param = |index: usize| {
+        param::<i16>(
+            params[index],
+            expected[index],
+            i16::MIN.into(),
+            i16::MAX.into(),
+        )
+    };
+    let pan_tilt_drive = |direction| PanTiltCommand {
+        direction,
+        pan_speed: PanSpeed::STOP,
+        tilt_speed: TiltSpeed::STOP,
+    };
+
+    let command: Box<dyn ViscaCommand> = match name {
+        "ZoomStop" => Box::new(ZoomCommand::Stop),
+        "ZoomTeleStandard" => Box::new(ZoomCommand::TeleStandard),
+        "ZoomWideStandard" => Box::new(ZoomCommand::WideStandard),
+        "ZoomTeleVariable" => Box::new(ZoomCommand::TeleVariable(u8_param(0)?)),
+        "ZoomWideVariable" => Box::new(ZoomCommand::WideVariable(u8_param(0)?)),
+        "ZoomDirect" => Box::new(ZoomCommand::Direct(u16_param(0)?)),
+        "FocusStop" => Box::new(FocusCommand::Stop),
+        "FocusFarStandard" => Box::new(FocusCommand::FarStandard),
+        "FocusNearStandard" => Box::new(FocusCommand::NearStandard),
+        "FocusFarVariable" => Box::new(FocusCommand::FarVariable(u8_param(0)?)),
+        "FocusNearVariable" => Box::new(FocusCommand::NearVariable(u8_param(0)?)),
+        "FocusDirect" => Box::new(FocusCommand::Direct(u16_param(0)?)),
+        "FocusAuto" => Box::new(FocusCommand::Auto),
+        "FocusManual" => Box::new(FocusCommand::Manual),
+        "FocusToggleAutoManual" => Box::new(FocusCommand::ToggleAutoManual),
+        "FocusOnePushTrigger" => Box::new(FocusCommand::OnePushTrigger),
+        "PanTiltStop" => Box::new(pan_tilt_drive(PanTiltDirection::Stop)),
+        "PanTiltHome" => Box::new(pan_tilt_drive(PanTiltDirection::Home)),
+        "PanTiltAbsolute" => Box::new(PanTiltAbsoluteCommand {
+            pan_speed: PanSpeed::new(u8_param(0)?)?,
+            tilt_speed: TiltSpeed::new(u8_param(1)?)?,
+            pan: i16_param(2)?,
+            tilt: i16_param(3)?,
+        }),
+        "PanTiltRelative" => Box::new(PanTiltRelativeCommand {
+            pan_speed: PanSpeed::new(u8_param(0)?)?,
+            tilt_speed: TiltSpeed::new(u8_param(1)?)?,
+            pan: i16_param(2)?,
+            tilt: i16_param(3)?,
+        }),
+        "PresetSet" | "PresetRecall" | "PresetReset" => Box::new(PresetCommand {
+            action: match name {
+                "PresetSet" => PresetAction::Set,
+                "PresetRecall" => PresetAction::Recall,
+                _ => PresetAction::Reset,
+            },
+            preset_number: u8_param(0)?,
+        }),
+        "PowerOn" => Box::new(PowerCommand { power: Power::On }),
+        "PowerStandby" => Box::new(PowerCommand {
+            power: Power::Standby,
+        }),
+        _ => return Err(unknown()),
+    };
+    Ok(command)
+}
+
+fn param<T: TryFrom<i64>>(
+    value: i64,
+    field: &'static str,
+    min: i64,
+    max: i64,
+) -> Result<T, ViscaError> {
+    T::try_from(value).map_err(|_| ViscaError::out_of_range(field, value, min, max))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_listed_name_builds_a_command_of_that_name() {
+        for (name, params) in NAMED_COMMANDS {
+            let command = named(name, &vec![1; params.len()]).unwrap();
+            assert_eq!(command.name(), *name);
+            assert!(command.validate().is_ok(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn named_builds_the_documented_frame() {
+        assert_eq!(
+            named("ZoomDirect", &[0x0500]).unwrap().to_bytes().unwrap(),
+            [0x81, 0x01, 0x04, 0x47, 0x00, 0x05, 0x00, 0x00, 0xFF]
+        );
+        assert_eq!(
+            named("PresetRecall", &[3]).unwrap().to_bytes().unwrap(),
+            [0x81, 0x01, 0x04, 0x3F, 0x02, 0x03, 0xFF]
+        );
+    }
+
+    #[test]
+    fn unknown_names_and_bad_params_are_rejected() {
+        assert!(matches!(
+            named("ZoomSideways", &[]),
+            Err(ViscaError::InvalidParameter(message)) if message.contains("ZoomSideways")
+        ));
+        assert!(matches!(
+            named("ZoomDirect", &[]),
+            Err(ViscaError::InvalidParameter(message)) if message.contains("position")
+        ));
+        assert!(matches!(
+            named("ZoomDirect", &[0x10000]),
+            Err(ViscaError::ParameterOutOfRange {
+                field: "position",
+                ..
+            })
+        ));
+        assert!(named("PanTiltAbsolute", &[0x19, 1, 0, 0]).is_err());
+    }
+}
@@ -0,0 +1,91 @@
+//! Nibble encoding shared by position commands and replies.
+//!
+//! VISCA carries 16-bit values as four bytes holding one nibble each, most
+//! significant nibble first: `0x1234` is sent as `01 02 03 04`. Only the low
+//! nibble of each byte is meaningful, which keeps every byte below `0xFF`.
+//! Signed values (pan/tilt) are the two's complement `u16` sent the same way.
+
+use log::warn;
+
+use crate::error::ViscaError;
+
+/// Splits `value` into four nibbles, most significant first.
+pub fn encode_u16_nibbles(value: u16) -> [u8; 4] {
+    [
+        ((value >> 12) & 0x0F) as u8,
+        ((value >> 8) & 0x0F) as u8,
+        ((value >> 4) & 0x0F) as u8,
+        (value & 0x0F) as u8,
+    ]
+}
+
+/// Joins four nibbles, most significant first. The high nibble of each byte
+/// is masked off, with a warning, so a noisy frame can't shift stray bits
+/// into the value.
+pub fn decode_u16_nibbles(nibbles: &[u8]) -> Result<u16, ViscaError> {
+    let [a, b, c, d] = nibbles else {
+        return Err(ViscaError::InvalidResponseLength {
+            expected: 4,
+            actual: nibbles.len(),
+        });
+    };
+    if nibbles.iter().any(|byte| byte & 0xF0 != 0) {
+        warn!("Masking stray high bits in nibbles: {:02X?}", nibbles);
+    }
+    Ok((u16::from(a & 0x0F) << 12)
+        | (u16::from(b & 0x0F) << 8)
+        | (u16::from(c & 0x0F) << 4)
+        | u16::from(d & 0x0F))
+}
+
+/// Splits a signed value into four nibbles of its two's complement.
+pub fn encode_i16_nibbles(value: i16) -> [u8; 4] {
+    encode_u16_nibbles(value as u16)
+}
+
+/// Inverse of [`encode_i16_nibbles`].
+pub fn decode_i16_nibbles(nibbles: &[u8]) -> Result<i16, ViscaError> {
+    decode_u16_nibbles(nibbles).map(|value| value as i16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn u16_round_trips_over_the_whole_range() {
+        for value in u16::MIN..=u16::MAX {
+            let nibbles = encode_u16_nibbles(value);
+            assert!(nibbles.iter().all(|nibble| *nibble <= 0x0F));
+            assert_eq!(decode_u16_nibbles(&nibbles).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn i16_round_trips_over_the_whole_range() {
+        for value in i16::MIN..=i16::MAX {
+            assert_eq!(
+                decode_i16_nibbles(&encode_i16_nibbles(value)).unwrap(),
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn most_significant_nibble_comes_first() {
+        assert_eq!(encode_u16_nibbles(0x1234), [0x01, 0x02, 0x03, 0x04]);
+        assert_eq!(encode_i16_nibbles(-1), [0x0F, 0x0F, 0x0F, 0x0F]);
+        assert_eq!(decode_i16_nibbles(&[0x0F, 0x0F, 0x0F, 0x0E]).unwrap(), -2);
+    }
+
+    #[test]
+    fn decode_rejects_the_wrong_number_of_nibbles() {
+        assert!(matches!(
+            decode_u16_nibbles(&[0x01, 0x02, 0x03]),
+            Err(ViscaError::InvalidResponseLength {
+                expected: 4,
+                actual: 3
+            })
+        ));
+    }
+}
@@ -1,9 +1,9 @@
 use crate::error::ViscaError;
 use std::convert::TryFrom;
 
-use super::{response::ViscaResponseType, ViscaCommand};
+use super::{flip::Flip, response::ViscaResponseType, ViscaCommand};
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum ExposureMode {
     Auto = 0x00,
     Manual = 0x03,
@@ -26,6 +26,287 @@ impl ViscaCommand for ExposureCommand {
     }
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AntiFlickerMode {
+    Off = 0x00,
+    Hz50 = 0x01,
+    Hz60 = 0x02,
+}
+
+pub struct AntiFlickerCommand {
+    pub mode: AntiFlickerMode,
+}
+
+impl ViscaCommand for AntiFlickerCommand {
+    fn to_bytes(&self) -> Result<Vec<u8>, ViscaError> {
+        Ok(vec![0x81, 0x01, 0x04, 0x23, self.mode as u8, 0xFF])
+    }
+
+    fn response_type(&self) -> Option<ViscaResponseType> {
+        None
+    }
+}
+
+impl TryFrom<u8> for AntiFlickerMode {
+    type Error = ();
+
+    fn try_from(v: u8) -> Result<Self, Self::Error> {
+        match v {
+            0x00 => Ok(AntiFlickerMode::Off),
+            0x01 => Ok(AntiFlickerMode::Hz50),
+            0x02 => Ok(AntiFlickerMode::Hz60),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Wire value of 0 EV; the direct step covers `0x00 = -7` to `0x0E = +7`.
+const EXPOSURE_COMPENSATION_ZERO: u8 = 0x07;
+
+#[derive(Debug, Copy, Clone)]
+pub enum ExposureCompensationCommand {
+    On,
+    Off,
+    Reset,
+    Up,
+    Down,
+    Direct(i8), // -7..=7
+}
+
+impl ExposureCompensationCommand {
+    /// Builds the `On`/`Off` variant from either a `bool` or a `Flip`.
+    pub fn enabled(enabled: impl Into<Flip>) -> Self {
+        match enabled.into() {
+            Flip::On => ExposureCompensationCommand::On,
+            Flip::Off => ExposureCompensationCommand::Off,
+        }
+    }
+
+    /// Maps a signed step onto the direct command's wire value.
+    pub fn encode_step(value: i8) -> Result<u8, ViscaError> {
+        if (-7..=7).contains(&value) {
+            Ok((value + EXPOSURE_COMPENSATION_ZERO as i8) as u8)
+        } else {
+            Err(ViscaError::out_of_range(
+                "Exposure compensation",
+                value,
+                -7,
+                7,
+            ))
+        }
+    }
+
+    /// Inverse of [`encode_step`](Self::encode_step) for inquiry replies.
+    pub fn decode_step(raw: u8) -> Result<i8, ViscaError> {
+        if raw <= 0x0E {
+            Ok(raw as i8 - EXPOSURE_COMPENSATION_ZERO as i8)
+        } else {
+            Err(ViscaError::InvalidResponseFormat)
+        }
+    }
+}
+
+impl ViscaCommand for ExposureCompensationCommand {
+    fn to_bytes(&self) -> Result<Vec<u8>, ViscaError> {
+        match self {
+            ExposureCompensationCommand::On => Ok(vec![0x81, 0x01, 0x04, 0x3E, 0x02, 0xFF]),
+            ExposureCompensationCommand::Off => Ok(vec![0x81, 0x01, 0x04, 0x3E, 0x03, 0xFF]),
+            ExposureCompensationCommand::Reset => Ok(vec![0x81, 0x01, 0x04, 0x0E, 0x00, 0xFF]),
+            ExposureCompensationCommand::Up => Ok(vec![0x81, 0x01, 0x04, 0x0E, 0x02, 0xFF]),
+            ExposureCompensationCommand::Down => Ok(vec![0x81, 0x01, 0x04, 0x0E, 0x03, 0xFF]),
+            ExposureCompensationCommand::Direct(value) => {
+                let step = Self::encode_step(*value)?;
+                Ok(vec![
+                    0x81,
+                    0x01,
+                    0x04,
+                    0x4E,
+                    0x00,
+                    0x00,
+                    step >> 4,
+                    step & 0x0F,
+                    0xFF,
+                ])
+            }
+        }
+    }
+
+    fn response_type(&self) -> Option<ViscaResponseType> {
+        None
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            ExposureCompensationCommand::On => "ExposureCompensationOn",
+            ExposureCompensationCommand::Off => "ExposureCompensationOff",
+            ExposureCompensationCommand::Reset => "ExposureCompensationReset",
+            ExposureCompensationCommand::Up => "ExposureCompensationUp",
+            ExposureCompensationCommand::Down => "ExposureCompensationDown",
+            ExposureCompensationCommand::Direct(_) => "ExposureCompensationDirect",
+        }
+    }
+}
+
+/// Steps the gain in manual exposure, or resets it with `Reset`
+/// (`81 01 04 0C 00 FF`).
+#[derive(Debug, Copy, Clone)]
+pub enum GainCommand {
+    Reset,
+    Up,
+    Down,
+}
+
+impl ViscaCommand for GainCommand {
+    fn to_bytes(&self) -> Result<Vec<u8>, ViscaError> {
+        match self {
+            GainCommand::Reset => Ok(vec![0x81, 0x01, 0x04, 0x0C, 0x00, 0xFF]),
+            GainCommand::Up => Ok(vec![0x81, 0x01, 0x04, 0x0C, 0x02, 0xFF]),
+            GainCommand::Down => Ok(vec![0x81, 0x01, 0x04, 0x0C, 0x03, 0xFF]),
+        }
+    }
+
+    fn response_type(&self) -> Option<ViscaResponseType> {
+        None
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            GainCommand::Reset => "GainReset",
+            GainCommand::Up => "GainUp",
+            GainCommand::Down => "GainDown",
+        }
+    }
+}
+
+/// Sets the auto-exposure target level in the Sony extended command family
+/// (`81 01 7E 01 4C 00 00 0p 0q FF`), within `0..=0x0F`. Only applies in
+/// the automatic exposure modes.
+pub struct AeLevelCommand {
+    pub value: u8,
+}
+
+impl AeLevelCommand {
+    pub const MAX: u8 = 0x0F;
+}
+
+impl ViscaCommand for AeLevelCommand {
+    fn to_bytes(&self) -> Result<Vec<u8>, ViscaError> {
+        if self.value > Self::MAX {
+            return Err(ViscaError::out_of_range(
+                "AE level",
+                self.value,
+                0,
+                Self::MAX.into(),
+            ));
+        }
+        Ok(vec![
+            0x81,
+            0x01,
+            0x7E,
+            0x01,
+            0x4C,
+            0x00,
+            0x00,
+            self.value >> 4,
+            self.value & 0x0F,
+            0xFF,
+        ])
+    }
+
+    fn response_type(&self) -> Option<ViscaResponseType> {
+        None
+    }
+}
+
+/// Sets an exposure parameter directly (`81 01 04 xx 00 00 0p 0q FF`). Each
+/// is only accepted in the exposure modes that leave it to the user.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ExposureDirectCommand {
+    Shutter(u8),
+    Iris(u8),
+    Gain(u8),
+    Bright(u8),
+}
+
+impl ViscaCommand for ExposureDirectCommand {
+    fn to_bytes(&self) -> Result<Vec<u8>, ViscaError> {
+        let (opcode, value) = match *self {
+            ExposureDirectCommand::Shutter(value) => (0x4A, value),
+            ExposureDirectCommand::Iris(value) => (0x4B, value),
+            ExposureDirectCommand::Gain(value) => (0x4C, value),
+            ExposureDirectCommand::Bright(value) => (0x4D, value),
+        };
+        Ok(vec![
+            0x81,
+            0x01,
+            0x04,
+            opcode,
+            0x00,
+            0x00,
+            value >> 4,
+            value & 0x0F,
+            0xFF,
+        ])
+    }
+
+    fn response_type(&self) -> Option<ViscaResponseType> {
+        None
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            ExposureDirectCommand::Shutter(_) => "ShutterDirect",
+            ExposureDirectCommand::Iris(_) => "IrisDirect",
+            ExposureDirectCommand::Gain(_) => "GainDirect",
+            ExposureDirectCommand::Bright(_) => "BrightDirect",
+        }
+    }
+}
+
+/// A complete exposure configuration for [`crate::Camera::set_exposure`],
+/// carrying only the parameters its mode lets the user set.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ExposureSettings {
+    Auto,
+    Manual { iris: u8, shutter: u8, gain: u8 },
+    ShutterPriority { shutter: u8 },
+    IrisPriority { iris: u8 },
+    Bright { level: u8 },
+}
+
+impl ExposureSettings {
+    pub fn mode(&self) -> ExposureMode {
+        match self {
+            ExposureSettings::Auto => ExposureMode::Auto,
+            ExposureSettings::Manual { .. } => ExposureMode::Manual,
+            ExposureSettings::ShutterPriority { .. } => ExposureMode::Shutter,
+            ExposureSettings::IrisPriority { .. } => ExposureMode::Iris,
+            ExposureSettings::Bright { .. } => ExposureMode::Bright,
+        }
+    }
+
+    /// The direct commands to send once the mode is active, in order.
+    pub fn parameters(&self) -> Vec<ExposureDirectCommand> {
+        match *self {
+            ExposureSettings::Auto => Vec::new(),
+            ExposureSettings::Manual {
+                iris,
+                shutter,
+                gain,
+            } => vec![
+                ExposureDirectCommand::Iris(iris),
+                ExposureDirectCommand::Shutter(shutter),
+                ExposureDirectCommand::Gain(gain),
+            ],
+            ExposureSettings::ShutterPriority { shutter } => {
+                vec![ExposureDirectCommand::Shutter(shutter)]
+            }
+            ExposureSettings::IrisPriority { iris } => vec![ExposureDirectCommand::Iris(iris)],
+            ExposureSettings::Bright { level } => vec![ExposureDirectCommand::Bright(level)],
+        }
+    }
+}
+
 impl TryFrom<u8> for ExposureMode {
     type Error = ();
 
@@ -40,3 +321,80 @@ impl TryFrom<u8> for ExposureMode {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        command::{response::parse_visca_response, InquiryCommand, ViscaInquiryResponse},
+        ViscaResponse,
+    };
+
+    const ANTI_FLICKER_MODES: [AntiFlickerMode; 3] = [
+        AntiFlickerMode::Off,
+        AntiFlickerMode::Hz50,
+        AntiFlickerMode::Hz60,
+    ];
+
+    #[test]
+    fn anti_flicker_inquiry_uses_its_own_opcode() {
+        // Not the 0x23 of the set command, nor 0x35, which is white balance.
+        assert_eq!(
+            InquiryCommand::AntiFlicker.to_bytes().unwrap(),
+            [0x81, 0x09, 0x04, 0x55, 0xFF]
+        );
+    }
+
+    #[test]
+    fn anti_flicker_frames_for_each_mode() {
+        for (mode, byte) in ANTI_FLICKER_MODES.into_iter().zip([0x00, 0x01, 0x02]) {
+            assert_eq!(
+                AntiFlickerCommand { mode }.to_bytes().unwrap(),
+                [0x81, 0x01, 0x04, 0x23, byte, 0xFF]
+            );
+            let reply = [0x90, 0x50, byte, 0xFF];
+            assert!(matches!(
+                parse_visca_response(&reply, &ViscaResponseType::AntiFlicker),
+                Ok(ViscaResponse::InquiryResponse(ViscaInquiryResponse::AntiFlicker { mode: m }))
+                    if m == mode
+            ));
+        }
+        assert!(
+            parse_visca_response(&[0x90, 0x50, 0x03, 0xFF], &ViscaResponseType::AntiFlicker)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn ae_level_frame_and_range() {
+        assert_eq!(
+            AeLevelCommand { value: 0x0A }.to_bytes().unwrap(),
+            [0x81, 0x01, 0x7E, 0x01, 0x4C, 0x00, 0x00, 0x00, 0x0A, 0xFF]
+        );
+        assert!(matches!(
+            AeLevelCommand { value: 0x10 }.to_bytes(),
+            Err(ViscaError::ParameterOutOfRange { max: 0x0F, .. })
+        ));
+    }
+
+    #[test]
+    fn ae_level_inquiry_and_reply() {
+        assert_eq!(
+            InquiryCommand::AeLevel.to_bytes().unwrap(),
+            [0x81, 0x09, 0x7E, 0x01, 0x4C, 0xFF]
+        );
+        assert_eq!(
+            InquiryCommand::AeLevel.response_type(),
+            Some(ViscaResponseType::AeLevel)
+        );
+        assert!(matches!(
+            parse_visca_response(
+                &[0x90, 0x50, 0x00, 0x00, 0x00, 0x07, 0xFF],
+                &ViscaResponseType::AeLevel
+            ),
+            Ok(ViscaResponse::InquiryResponse(
+                ViscaInquiryResponse::AeLevel { value: 0x07 }
+            ))
+        ));
+    }
+}
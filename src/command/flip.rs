@@ -1,18 +1,57 @@
 use crate::command::ViscaCommand;
 use crate::error::ViscaError;
+use std::convert::TryFrom;
 
 use super::ViscaResponseType;
 
-#[derive(Debug, Copy, Clone)]
+/// On/off state shared by the simple toggle commands.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Flip {
     On = 0x02,
     Off = 0x03,
 }
 
+impl TryFrom<u8> for Flip {
+    type Error = ();
+
+    fn try_from(v: u8) -> Result<Self, Self::Error> {
+        match v {
+            0x02 => Ok(Flip::On),
+            0x03 => Ok(Flip::Off),
+            _ => Err(()),
+        }
+    }
+}
+
+impl From<bool> for Flip {
+    fn from(on: bool) -> Self {
+        if on {
+            Flip::On
+        } else {
+            Flip::Off
+        }
+    }
+}
+
+impl From<Flip> for bool {
+    fn from(flip: Flip) -> Self {
+        flip == Flip::On
+    }
+}
+
+/// Vertical image flip (`81 01 04 66 0p FF`), for ceiling mounts. See
+/// [`MirrorCommand`] for the horizontal axis.
 pub struct ImageFlipCommand {
     pub flip: Flip,
 }
 
+impl ImageFlipCommand {
+    /// Accepts either a `Flip` or a `bool`.
+    pub fn new(flip: impl Into<Flip>) -> Self {
+        Self { flip: flip.into() }
+    }
+}
+
 impl ViscaCommand for ImageFlipCommand {
     fn to_bytes(&self) -> Result<Vec<u8>, ViscaError> {
         Ok(vec![0x81, 0x01, 0x04, 0x66, self.flip as u8, 0xFF])
@@ -22,3 +61,100 @@ impl ViscaCommand for ImageFlipCommand {
         None
     }
 }
+
+/// Horizontal mirror (`81 01 04 61 0p FF`), for rear projection. This is the
+/// LR-reverse axis; [`ImageFlipCommand`] flips vertically.
+pub struct MirrorCommand {
+    pub enabled: bool,
+}
+
+impl ViscaCommand for MirrorCommand {
+    fn to_bytes(&self) -> Result<Vec<u8>, ViscaError> {
+        Ok(vec![
+            0x81,
+            0x01,
+            0x04,
+            0x61,
+            Flip::from(self.enabled) as u8,
+            0xFF,
+        ])
+    }
+
+    fn response_type(&self) -> Option<ViscaResponseType> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::{BacklightCommand, ExposureCompensationCommand};
+
+    #[test]
+    fn bool_converts_to_the_wire_value_and_back() {
+        assert_eq!(Flip::from(true) as u8, 0x02);
+        assert_eq!(Flip::from(false) as u8, 0x03);
+        for on in [true, false] {
+            assert_eq!(bool::from(Flip::from(on)), on);
+        }
+        assert_eq!(Flip::try_from(0x02), Ok(Flip::On));
+        assert_eq!(Flip::try_from(0x01), Err(()));
+    }
+
+    #[test]
+    fn bool_and_flip_constructors_build_the_same_frame() {
+        for (on, flip) in [(true, Flip::On), (false, Flip::Off)] {
+            assert_eq!(
+                ImageFlipCommand::new(on).to_bytes().unwrap(),
+                ImageFlipCommand::new(flip).to_bytes().unwrap()
+            );
+            assert_eq!(
+                BacklightCommand::new(on).to_bytes().unwrap(),
+                BacklightCommand::new(flip).to_bytes().unwrap()
+            );
+            assert_eq!(
+                ExposureCompensationCommand::enabled(on).to_bytes().unwrap(),
+                ExposureCompensationCommand::enabled(flip)
+                    .to_bytes()
+                    .unwrap()
+            );
+        }
+        assert_eq!(
+            ImageFlipCommand::new(true).to_bytes().unwrap(),
+            [0x81, 0x01, 0x04, 0x66, 0x02, 0xFF]
+        );
+    }
+
+    #[test]
+    fn mirror_and_flip_use_separate_axes() {
+        use crate::command::InquiryCommand;
+
+        // Horizontal (LR reverse) is 0x61, vertical flip 0x66.
+        for (enabled, state) in [(true, 0x02), (false, 0x03)] {
+            assert_eq!(
+                MirrorCommand { enabled }.to_bytes().unwrap(),
+                [0x81, 0x01, 0x04, 0x61, state, 0xFF]
+            );
+            assert_eq!(
+                ImageFlipCommand::new(enabled).to_bytes().unwrap(),
+                [0x81, 0x01, 0x04, 0x66, state, 0xFF]
+            );
+        }
+        assert_eq!(
+            InquiryCommand::HorizontalFlip.to_bytes().unwrap(),
+            [0x81, 0x09, 0x04, 0x61, 0xFF]
+        );
+        assert_eq!(
+            InquiryCommand::VerticalFlip.to_bytes().unwrap(),
+            [0x81, 0x09, 0x04, 0x66, 0xFF]
+        );
+    }
+
+    #[test]
+    fn flip_try_from_maps_the_documented_bytes() {
+        assert_eq!(Flip::try_from(0x03), Ok(Flip::Off));
+        for byte in [0x00, 0x04, 0xFF] {
+            assert_eq!(Flip::try_from(byte), Err(()));
+        }
+    }
+}
@@ -1,7 +1,27 @@
 use crate::command::ViscaCommand;
 use crate::error::ViscaError;
+use crate::CameraModel;
+use std::convert::TryFrom;
 
-use super::ViscaResponseType;
+use super::{codec::encode_u16_nibbles, ViscaResponseType};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FocusMode {
+    Auto = 0x02,
+    Manual = 0x03,
+}
+
+impl TryFrom<u8> for FocusMode {
+    type Error = ();
+
+    fn try_from(v: u8) -> Result<Self, Self::Error> {
+        match v {
+            0x02 => Ok(FocusMode::Auto),
+            0x03 => Ok(FocusMode::Manual),
+            _ => Err(()),
+        }
+    }
+}
 
 #[derive(Debug)]
 pub enum FocusCommand {
@@ -13,49 +33,269 @@ pub enum FocusCommand {
     Direct(u16),
     Auto,
     Manual,
+    /// Flips between auto and manual focus (`81 01 04 38 10 FF`). Unlike
+    /// [`FocusCommand::Auto`] and [`FocusCommand::Manual`] the resulting mode
+    /// depends on the current one, so read it back with
+    /// [`crate::command::InquiryCommand::FocusMode`] where that matters.
+    ToggleAutoManual,
+    /// One-push AF in the focus mode family (`81 01 04 38 04 FF`), used by
+    /// PTZOptics cameras alongside the Auto/Manual mode switch.
+    OnePushAf,
+    /// One-push AF trigger in the focus trigger family (`81 01 04 18 01 FF`),
+    /// the form Sony cameras accept while in manual focus.
     OnePushTrigger,
+    /// Racks focus to infinity (`81 01 04 18 02 FF`). Shares the `0x18`
+    /// trigger family with [`FocusCommand::OnePushTrigger`] but moves the lens
+    /// to a fixed position instead of running AF.
     Infinity,
+    /// Limits how close the lens may focus (`81 01 04 28 0p 0q 0r 0s FF`).
+    /// `0x1000` is the far end; higher values allow closer focus.
+    NearLimit(u16),
 }
 
 impl ViscaCommand for FocusCommand {
     fn to_bytes(&self) -> Result<Vec<u8>, ViscaError> {
+        self.to_bytes_for(CameraModel::default())
+    }
+
+    fn response_type(&self) -> Option<ViscaResponseType> {
+        None
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            FocusCommand::Stop => "FocusStop",
+            FocusCommand::FarStandard => "FocusFarStandard",
+            FocusCommand::NearStandard => "FocusNearStandard",
+            FocusCommand::FarVariable(_) => "FocusFarVariable",
+            FocusCommand::NearVariable(_) => "FocusNearVariable",
+            FocusCommand::Direct(_) => "FocusDirect",
+            FocusCommand::Auto => "FocusAuto",
+            FocusCommand::Manual => "FocusManual",
+            FocusCommand::ToggleAutoManual => "FocusToggleAutoManual",
+            FocusCommand::OnePushAf => "FocusOnePushAf",
+            FocusCommand::OnePushTrigger => "FocusOnePushTrigger",
+            FocusCommand::Infinity => "FocusInfinity",
+            FocusCommand::NearLimit(_) => "FocusNearLimit",
+        }
+    }
+
+    /// The variable speed range depends on the profile, see
+    /// [`CameraModel::max_focus_speed`].
+    fn to_bytes_for(&self, model: CameraModel) -> Result<Vec<u8>, ViscaError> {
+        let max_speed = model.max_focus_speed();
         match self {
             FocusCommand::Stop => Ok(vec![0x81, 0x01, 0x04, 0x08, 0x00, 0xFF]),
             FocusCommand::FarStandard => Ok(vec![0x81, 0x01, 0x04, 0x08, 0x02, 0xFF]),
             FocusCommand::NearStandard => Ok(vec![0x81, 0x01, 0x04, 0x08, 0x03, 0xFF]),
             FocusCommand::FarVariable(speed) => {
-                if *speed <= 7 {
+                if *speed <= max_speed {
                     Ok(vec![0x81, 0x01, 0x04, 0x08, 0x20 | speed, 0xFF])
                 } else {
-                    Err(ViscaError::InvalidParameter(
-                        "Focus speed must be in the range 0..=7".into(),
+                    Err(ViscaError::out_of_range(
+                        "Focus speed",
+                        *speed,
+                        0,
+                        max_speed.into(),
                     ))
                 }
             }
             FocusCommand::NearVariable(speed) => {
-                if *speed <= 7 {
+                if *speed <= max_speed {
                     Ok(vec![0x81, 0x01, 0x04, 0x08, 0x30 | speed, 0xFF])
                 } else {
-                    Err(ViscaError::InvalidParameter(
-                        "Focus speed must be in the range 0..=7".into(),
+                    Err(ViscaError::out_of_range(
+                        "Focus speed",
+                        *speed,
+                        0,
+                        max_speed.into(),
                     ))
                 }
             }
             FocusCommand::Direct(position) => {
-                let p = (*position >> 12) as u8;
-                let q = (*position >> 8) as u8;
-                let r = (*position >> 4) as u8;
-                let s = (*position & 0x0F) as u8;
+                let [p, q, r, s] = encode_u16_nibbles(*position);
                 Ok(vec![0x81, 0x01, 0x04, 0x48, p, q, r, s, 0xFF])
             }
-            FocusCommand::Auto => Ok(vec![0x81, 0x01, 0x04, 0x38, 0x02, 0xFF]),
-            FocusCommand::Manual => Ok(vec![0x81, 0x01, 0x04, 0x38, 0x03, 0xFF]),
+            FocusCommand::Auto => Ok(vec![0x81, 0x01, 0x04, 0x38, FocusMode::Auto as u8, 0xFF]),
+            FocusCommand::Manual => Ok(vec![0x81, 0x01, 0x04, 0x38, FocusMode::Manual as u8, 0xFF]),
+            FocusCommand::ToggleAutoManual => Ok(vec![0x81, 0x01, 0x04, 0x38, 0x10, 0xFF]),
+            FocusCommand::OnePushAf => Ok(vec![0x81, 0x01, 0x04, 0x38, 0x04, 0xFF]),
             FocusCommand::OnePushTrigger => Ok(vec![0x81, 0x01, 0x04, 0x18, 0x01, 0xFF]),
             FocusCommand::Infinity => Ok(vec![0x81, 0x01, 0x04, 0x18, 0x02, 0xFF]),
+            FocusCommand::NearLimit(position) => {
+                let [p, q, r, s] = encode_u16_nibbles(*position);
+                Ok(vec![0x81, 0x01, 0x04, 0x28, p, q, r, s, 0xFF])
+            }
         }
     }
+}
 
-    fn response_type(&self) -> Option<ViscaResponseType> {
-        None
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn variable_speed_boundaries_per_profile() {
+        for model in [CameraModel::PtzOpticsG2, CameraModel::SonyFr7] {
+            assert_eq!(
+                FocusCommand::FarVariable(7).to_bytes_for(model).unwrap(),
+                [0x81, 0x01, 0x04, 0x08, 0x27, 0xFF]
+            );
+            assert_eq!(
+                FocusCommand::NearVariable(7).to_bytes_for(model).unwrap(),
+                [0x81, 0x01, 0x04, 0x08, 0x37, 0xFF]
+            );
+            for speed in [8, 0x0F] {
+                assert!(matches!(
+                    FocusCommand::FarVariable(speed).to_bytes_for(model),
+                    Err(ViscaError::ParameterOutOfRange { max: 7, .. })
+                ));
+                assert!(FocusCommand::NearVariable(speed)
+                    .to_bytes_for(model)
+                    .is_err());
+            }
+        }
+
+        let generic = CameraModel::Generic;
+        for speed in [7, 8, 0x0F] {
+            assert_eq!(
+                FocusCommand::FarVariable(speed)
+                    .to_bytes_for(generic)
+                    .unwrap(),
+                [0x81, 0x01, 0x04, 0x08, 0x20 | speed, 0xFF]
+            );
+            assert_eq!(
+                FocusCommand::NearVariable(speed)
+                    .to_bytes_for(generic)
+                    .unwrap(),
+                [0x81, 0x01, 0x04, 0x08, 0x30 | speed, 0xFF]
+            );
+        }
+        assert!(FocusCommand::FarVariable(0x10)
+            .to_bytes_for(generic)
+            .is_err());
+    }
+
+    #[test]
+    fn to_bytes_uses_the_conservative_default() {
+        assert!(FocusCommand::FarVariable(7).to_bytes().is_ok());
+        assert!(FocusCommand::FarVariable(8).to_bytes().is_err());
+    }
+
+    #[test]
+    fn one_push_af_and_trigger_are_distinct_frames() {
+        // 0x38 04 is the mode-family one-push AF, 0x18 01 the trigger.
+        for model in [
+            CameraModel::PtzOpticsG2,
+            CameraModel::SonyFr7,
+            CameraModel::Generic,
+        ] {
+            assert_eq!(
+                FocusCommand::OnePushAf.to_bytes_for(model).unwrap(),
+                [0x81, 0x01, 0x04, 0x38, 0x04, 0xFF]
+            );
+            assert_eq!(
+                FocusCommand::OnePushTrigger.to_bytes_for(model).unwrap(),
+                [0x81, 0x01, 0x04, 0x18, 0x01, 0xFF]
+            );
+        }
+        assert_ne!(
+            FocusCommand::OnePushAf.name(),
+            FocusCommand::OnePushTrigger.name()
+        );
+    }
+
+    #[test]
+    fn focus_mode_set_then_read_back() {
+        use crate::{
+            command::InquiryCommand, inquiry_frame, mock::MockTransport, send_command_and_wait,
+            ViscaInquiryResponse, ViscaResponse,
+        };
+
+        for (command, mode) in [
+            (FocusCommand::Auto, FocusMode::Auto),
+            (FocusCommand::Manual, FocusMode::Manual),
+        ] {
+            let mut transport = MockTransport::acking();
+            let set = command.to_bytes().unwrap();
+            assert_eq!(set, [0x81, 0x01, 0x04, 0x38, mode as u8, 0xFF]);
+            // The camera reports back the mode byte it was set with.
+            let reply = inquiry_frame(&ViscaInquiryResponse::FocusMode {
+                mode: FocusMode::try_from(set[4]).unwrap(),
+            })
+            .unwrap();
+            transport.respond(&[0x81, 0x09, 0x04, 0x38], &[&reply]);
+
+            send_command_and_wait(&mut transport, &command).unwrap();
+            let read = send_command_and_wait(&mut transport, &InquiryCommand::FocusMode);
+            assert!(
+                matches!(
+                    read,
+                    Ok(ViscaResponse::InquiryResponse(ViscaInquiryResponse::FocusMode { mode: m }))
+                        if m == mode
+                ),
+                "{:?}",
+                read
+            );
+            assert_eq!(transport.sent()[1], [0x81, 0x09, 0x04, 0x38, 0xFF]);
+        }
+    }
+
+    #[test]
+    fn focus_mode_try_from_maps_the_documented_bytes() {
+        assert_eq!(FocusMode::try_from(0x02), Ok(FocusMode::Auto));
+        assert_eq!(FocusMode::try_from(0x03), Ok(FocusMode::Manual));
+        for byte in [0x00, 0x01, 0x04, 0x10] {
+            assert_eq!(FocusMode::try_from(byte), Err(()));
+        }
+    }
+
+    #[test]
+    fn infinity_and_near_limit_frames() {
+        assert_eq!(
+            FocusCommand::Infinity.to_bytes().unwrap(),
+            [0x81, 0x01, 0x04, 0x18, 0x02, 0xFF]
+        );
+        assert_ne!(
+            FocusCommand::Infinity.to_bytes().unwrap(),
+            FocusCommand::OnePushTrigger.to_bytes().unwrap()
+        );
+        assert_eq!(
+            FocusCommand::NearLimit(0x1000).to_bytes().unwrap(),
+            [0x81, 0x01, 0x04, 0x28, 0x01, 0x00, 0x00, 0x00, 0xFF]
+        );
+        assert_eq!(
+            FocusCommand::NearLimit(0xF000).to_bytes().unwrap(),
+            [0x81, 0x01, 0x04, 0x28, 0x0F, 0x00, 0x00, 0x00, 0xFF]
+        );
+    }
+
+    #[test]
+    fn variable_focus_speed_boundaries_follow_the_profile() {
+        let far = |speed, model| FocusCommand::FarVariable(speed).to_bytes_for(model);
+        let near = |speed, model| FocusCommand::NearVariable(speed).to_bytes_for(model);
+        let g2 = CameraModel::PtzOpticsG2;
+        assert_eq!(far(7, g2).unwrap(), [0x81, 0x01, 0x04, 0x08, 0x27, 0xFF]);
+        assert_eq!(near(7, g2).unwrap(), [0x81, 0x01, 0x04, 0x08, 0x37, 0xFF]);
+        assert!(matches!(
+            far(8, g2),
+            Err(ViscaError::ParameterOutOfRange {
+                value: 8,
+                max: 7,
+                ..
+            })
+        ));
+        let generic = CameraModel::Generic;
+        assert_eq!(near(0xF, generic).unwrap()[4], 0x3F);
+        assert!(far(0x10, generic).is_err());
+    }
+
+    #[test]
+    fn toggle_frame_differs_from_explicit_modes() {
+        let toggle = FocusCommand::ToggleAutoManual.to_bytes().unwrap();
+        assert_eq!(toggle, [0x81, 0x01, 0x04, 0x38, 0x10, 0xFF]);
+        assert_ne!(toggle, FocusCommand::Auto.to_bytes().unwrap());
+        assert_ne!(toggle, FocusCommand::Manual.to_bytes().unwrap());
+        assert_eq!(FocusCommand::ToggleAutoManual.response_type(), None);
     }
 }
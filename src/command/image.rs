@@ -1,19 +1,70 @@
 use crate::command::ViscaCommand;
 use crate::error::ViscaError;
 
-use super::ViscaResponseType;
+use super::{flip::Flip, ViscaResponseType};
 
 pub struct BacklightCommand {
     pub status: bool,
 }
 
+impl BacklightCommand {
+    /// Accepts either a `bool` or a `Flip`.
+    pub fn new(status: impl Into<Flip>) -> Self {
+        Self {
+            status: status.into().into(),
+        }
+    }
+}
+
 impl ViscaCommand for BacklightCommand {
     fn to_bytes(&self) -> Result<Vec<u8>, ViscaError> {
-        let status_byte = if self.status { 0x02 } else { 0x03 };
-        Ok(vec![0x81, 0x01, 0x04, 0x33, status_byte, 0xFF])
+        Ok(vec![
+            0x81,
+            0x01,
+            0x04,
+            0x33,
+            Flip::from(self.status) as u8,
+            0xFF,
+        ])
     }
 
     fn response_type(&self) -> Option<ViscaResponseType> {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::{response::parse_visca_response, InquiryCommand};
+    use crate::{ViscaInquiryResponse, ViscaResponse};
+
+    #[test]
+    fn backlight_round_trips_both_states() {
+        assert_eq!(
+            InquiryCommand::BacklightState.to_bytes().unwrap(),
+            [0x81, 0x09, 0x04, 0x33, 0xFF]
+        );
+        for (status, byte) in [(true, 0x02), (false, 0x03)] {
+            assert_eq!(
+                BacklightCommand::new(status).to_bytes().unwrap(),
+                [0x81, 0x01, 0x04, 0x33, byte, 0xFF]
+            );
+            assert!(matches!(
+                parse_visca_response(&[0x90, 0x50, byte, 0xFF], &ViscaResponseType::Backlight),
+                Ok(ViscaResponse::InquiryResponse(ViscaInquiryResponse::Backlight { status: s }))
+                    if s == status
+            ));
+        }
+    }
+
+    #[test]
+    fn backlight_rejects_zero_and_one() {
+        for byte in [0x00, 0x01] {
+            assert!(
+                parse_visca_response(&[0x90, 0x50, byte, 0xFF], &ViscaResponseType::Backlight)
+                    .is_err()
+            );
+        }
+    }
+}
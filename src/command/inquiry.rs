@@ -3,7 +3,7 @@ use crate::error::ViscaError;
 
 use super::ViscaResponseType;
 
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum InquiryCommand {
     PanTiltPosition,
     ZoomPosition,
@@ -12,7 +12,106 @@ pub enum InquiryCommand {
     WhiteBalanceMode,
     Luminance,
     Contrast,
-    // Add other inquiry commands as needed
+    ExposureCompensationPosition,
+    VideoTemplate,
+    Power,
+    LensType,
+    KneePoint,
+    KneeSlope,
+    BlackLevel,
+    FocusMode,
+    /// Anti-flicker (`81 09 04 55 FF`), per the G2 command list in `docs/`.
+    /// The set command uses `04 23` instead.
+    AntiFlicker,
+    HorizontalFlip,
+    VerticalFlip,
+    DateTime,
+    /// USB audio (`81 2A 02 A0 04 FF`). Unlike the other inquiries it has no
+    /// `09` byte; this is the frame the G2 command list in `docs/` gives.
+    UsbAudio,
+    Version,
+    SharpnessMode,
+    SharpnessPosition,
+    GainPosition,
+    IrisPosition,
+    ShutterPosition,
+    /// Backlight compensation, answered with `90 50 0p FF` where `02` is on
+    /// and `03` is off, as for the other on/off inquiries.
+    BacklightState,
+    BlockColorExposure,
+    BlockImage,
+    ImageFlip,
+    /// AF sensitivity (`81 09 04 58 FF`), answered with `90 50 0p FF` where
+    /// `01` is high, `02` normal and `03` low. Shares no opcode with the focus mode
+    /// inquiry (`04 38`).
+    AutoFocusSensitivity,
+    ColorTemperature,
+    Display,
+    MotionSync,
+    MotionSyncSpeed,
+    /// Last recalled preset (`81 09 04 3F FF`). No vendor document in
+    /// `docs/` lists it, so only `Generic` cameras send it; see
+    /// [`crate::Camera::last_preset`].
+    LastPreset,
+    /// On-screen menu state (`81 09 06 06 FF`), answered with `90 50 0p FF`
+    /// where `02` is open and `03` closed. `04 62` would be the freeze
+    /// image opcode, not the menu.
+    MenuOpen,
+    AeLevel,
+    /// Running RTMP streams (`81 09 11 53 FF`), answered with `90 50 0p FF`
+    /// where bit 0 is stream 1 and bit 1 stream 2.
+    Rtmp,
+    // Add other inquiry commands as needed, and to `InquiryCommand::ALL`.
+}
+
+impl InquiryCommand {
+    const ALL: &'static [InquiryCommand] = &[
+        InquiryCommand::PanTiltPosition,
+        InquiryCommand::ZoomPosition,
+        InquiryCommand::FocusPosition,
+        InquiryCommand::ExposureMode,
+        InquiryCommand::WhiteBalanceMode,
+        InquiryCommand::Luminance,
+        InquiryCommand::Contrast,
+        InquiryCommand::ExposureCompensationPosition,
+        InquiryCommand::VideoTemplate,
+        InquiryCommand::Power,
+        InquiryCommand::LensType,
+        InquiryCommand::KneePoint,
+        InquiryCommand::KneeSlope,
+        InquiryCommand::BlackLevel,
+        InquiryCommand::FocusMode,
+        InquiryCommand::AntiFlicker,
+        InquiryCommand::HorizontalFlip,
+        InquiryCommand::VerticalFlip,
+        InquiryCommand::DateTime,
+        InquiryCommand::UsbAudio,
+        InquiryCommand::Version,
+        InquiryCommand::SharpnessMode,
+        InquiryCommand::SharpnessPosition,
+        InquiryCommand::GainPosition,
+        InquiryCommand::IrisPosition,
+        InquiryCommand::ShutterPosition,
+        InquiryCommand::BacklightState,
+        InquiryCommand::BlockColorExposure,
+        InquiryCommand::BlockImage,
+        InquiryCommand::ImageFlip,
+        InquiryCommand::AutoFocusSensitivity,
+        InquiryCommand::ColorTemperature,
+        InquiryCommand::Display,
+        InquiryCommand::MotionSync,
+        InquiryCommand::MotionSyncSpeed,
+        InquiryCommand::LastPreset,
+        InquiryCommand::MenuOpen,
+        InquiryCommand::AeLevel,
+        InquiryCommand::Rtmp,
+    ];
+
+    /// Every inquiry, in declaration order, e.g. to refresh all state with
+    /// [`crate::Camera::inquire_many`].
+    pub fn all() -> &'static [InquiryCommand] {
+        Self::ALL
+    }
 }
 
 impl ViscaCommand for InquiryCommand {
@@ -25,10 +124,86 @@ impl ViscaCommand for InquiryCommand {
             InquiryCommand::WhiteBalanceMode => vec![0x81, 0x09, 0x04, 0x35, 0xFF],
             InquiryCommand::Luminance => vec![0x81, 0x09, 0x04, 0xA1, 0xFF],
             InquiryCommand::Contrast => vec![0x81, 0x09, 0x04, 0xA2, 0xFF],
+            InquiryCommand::ExposureCompensationPosition => vec![0x81, 0x09, 0x04, 0x4E, 0xFF],
+            InquiryCommand::VideoTemplate => vec![0x81, 0x09, 0x0B, 0x01, 0xFF],
+            InquiryCommand::Power => vec![0x81, 0x09, 0x04, 0x00, 0xFF],
+            InquiryCommand::LensType => vec![0x81, 0x09, 0x0A, 0x01, 0x04, 0x1B, 0xFF],
+            InquiryCommand::KneePoint => vec![0x81, 0x09, 0x7E, 0x01, 0x6F, 0xFF],
+            InquiryCommand::KneeSlope => vec![0x81, 0x09, 0x7E, 0x01, 0x6E, 0xFF],
+            InquiryCommand::BlackLevel => vec![0x81, 0x09, 0x7E, 0x04, 0x48, 0xFF],
+            InquiryCommand::FocusMode => vec![0x81, 0x09, 0x04, 0x38, 0xFF],
+            InquiryCommand::AntiFlicker => vec![0x81, 0x09, 0x04, 0x55, 0xFF],
+            InquiryCommand::HorizontalFlip => vec![0x81, 0x09, 0x04, 0x61, 0xFF],
+            InquiryCommand::VerticalFlip => vec![0x81, 0x09, 0x04, 0x66, 0xFF],
+            InquiryCommand::DateTime => vec![0x81, 0x09, 0x04, 0x70, 0xFF],
+            InquiryCommand::UsbAudio => vec![0x81, 0x2A, 0x02, 0xA0, 0x04, 0xFF],
+            InquiryCommand::Version => vec![0x81, 0x09, 0x00, 0x02, 0xFF],
+            InquiryCommand::SharpnessMode => vec![0x81, 0x09, 0x04, 0x05, 0xFF],
+            InquiryCommand::SharpnessPosition => vec![0x81, 0x09, 0x04, 0x42, 0xFF],
+            InquiryCommand::GainPosition => vec![0x81, 0x09, 0x04, 0x4C, 0xFF],
+            InquiryCommand::IrisPosition => vec![0x81, 0x09, 0x04, 0x4B, 0xFF],
+            InquiryCommand::ColorTemperature => vec![0x81, 0x09, 0x04, 0x20, 0xFF],
+            InquiryCommand::Display => vec![0x81, 0x09, 0x04, 0x15, 0xFF],
+            InquiryCommand::MotionSync => vec![0x81, 0x09, 0x11, 0x13, 0xFF],
+            InquiryCommand::MotionSyncSpeed => vec![0x81, 0x09, 0x11, 0x14, 0xFF],
+            InquiryCommand::LastPreset => vec![0x81, 0x09, 0x04, 0x3F, 0xFF],
+            InquiryCommand::MenuOpen => vec![0x81, 0x09, 0x06, 0x06, 0xFF],
+            InquiryCommand::Rtmp => vec![0x81, 0x09, 0x11, 0x53, 0xFF],
+            InquiryCommand::AeLevel => vec![0x81, 0x09, 0x7E, 0x01, 0x4C, 0xFF],
+            InquiryCommand::ShutterPosition => vec![0x81, 0x09, 0x04, 0x4A, 0xFF],
+            InquiryCommand::BacklightState => vec![0x81, 0x09, 0x04, 0x33, 0xFF],
+            InquiryCommand::BlockColorExposure => vec![0x81, 0x09, 0x7E, 0x7E, 0x01, 0xFF],
+            InquiryCommand::BlockImage => vec![0x81, 0x09, 0x7E, 0x7E, 0x03, 0xFF],
+            InquiryCommand::ImageFlip => vec![0x81, 0x09, 0x04, 0xA4, 0xFF],
+            InquiryCommand::AutoFocusSensitivity => vec![0x81, 0x09, 0x04, 0x58, 0xFF],
         };
         Ok(bytes)
     }
 
+    fn name(&self) -> &'static str {
+        match self {
+            InquiryCommand::PanTiltPosition => "InquiryPanTiltPosition",
+            InquiryCommand::ZoomPosition => "InquiryZoomPosition",
+            InquiryCommand::FocusPosition => "InquiryFocusPosition",
+            InquiryCommand::ExposureMode => "InquiryExposureMode",
+            InquiryCommand::WhiteBalanceMode => "InquiryWhiteBalanceMode",
+            InquiryCommand::Luminance => "InquiryLuminance",
+            InquiryCommand::Contrast => "InquiryContrast",
+            InquiryCommand::ExposureCompensationPosition => "InquiryExposureCompensationPosition",
+            InquiryCommand::VideoTemplate => "InquiryVideoTemplate",
+            InquiryCommand::Power => "InquiryPower",
+            InquiryCommand::LensType => "InquiryLensType",
+            InquiryCommand::KneePoint => "InquiryKneePoint",
+            InquiryCommand::KneeSlope => "InquiryKneeSlope",
+            InquiryCommand::BlackLevel => "InquiryBlackLevel",
+            InquiryCommand::FocusMode => "InquiryFocusMode",
+            InquiryCommand::AntiFlicker => "InquiryAntiFlicker",
+            InquiryCommand::HorizontalFlip => "InquiryHorizontalFlip",
+            InquiryCommand::VerticalFlip => "InquiryVerticalFlip",
+            InquiryCommand::DateTime => "InquiryDateTime",
+            InquiryCommand::UsbAudio => "InquiryUsbAudio",
+            InquiryCommand::Version => "InquiryVersion",
+            InquiryCommand::SharpnessMode => "InquirySharpnessMode",
+            InquiryCommand::SharpnessPosition => "InquirySharpnessPosition",
+            InquiryCommand::GainPosition => "InquiryGainPosition",
+            InquiryCommand::IrisPosition => "InquiryIrisPosition",
+            InquiryCommand::ColorTemperature => "InquiryColorTemperature",
+            InquiryCommand::Display => "InquiryDisplay",
+            InquiryCommand::MotionSync => "InquiryMotionSync",
+            InquiryCommand::MotionSyncSpeed => "InquiryMotionSyncSpeed",
+            InquiryCommand::LastPreset => "InquiryLastPreset",
+            InquiryCommand::MenuOpen => "InquiryMenuOpen",
+            InquiryCommand::Rtmp => "InquiryRtmp",
+            InquiryCommand::AeLevel => "InquiryAeLevel",
+            InquiryCommand::ShutterPosition => "InquiryShutterPosition",
+            InquiryCommand::BacklightState => "InquiryBacklightState",
+            InquiryCommand::BlockColorExposure => "InquiryBlockColorExposure",
+            InquiryCommand::BlockImage => "InquiryBlockImage",
+            InquiryCommand::ImageFlip => "InquiryImageFlip",
+            InquiryCommand::AutoFocusSensitivity => "InquiryAutoFocusSensitivity",
+        }
+    }
+
     fn response_type(&self) -> Option<ViscaResponseType> {
         match self {
             InquiryCommand::PanTiltPosition => Some(ViscaResponseType::PanTiltPosition),
@@ -38,6 +213,139 @@ impl ViscaCommand for InquiryCommand {
             InquiryCommand::WhiteBalanceMode => Some(ViscaResponseType::WhiteBalanceMode),
             InquiryCommand::Luminance => Some(ViscaResponseType::Luminance),
             InquiryCommand::Contrast => Some(ViscaResponseType::Contrast),
+            InquiryCommand::ExposureCompensationPosition => {
+                Some(ViscaResponseType::ExposureCompensationPosition)
+            }
+            InquiryCommand::VideoTemplate => Some(ViscaResponseType::VideoTemplate),
+            InquiryCommand::Power => Some(ViscaResponseType::Power),
+            InquiryCommand::LensType => Some(ViscaResponseType::LensType),
+            InquiryCommand::KneePoint => Some(ViscaResponseType::KneePoint),
+            InquiryCommand::KneeSlope => Some(ViscaResponseType::KneeSlope),
+            InquiryCommand::BlackLevel => Some(ViscaResponseType::BlackLevel),
+            InquiryCommand::FocusMode => Some(ViscaResponseType::FocusMode),
+            InquiryCommand::AntiFlicker => Some(ViscaResponseType::AntiFlicker),
+            InquiryCommand::HorizontalFlip => Some(ViscaResponseType::HorizontalFlip),
+            InquiryCommand::VerticalFlip => Some(ViscaResponseType::VerticalFlip),
+            InquiryCommand::DateTime => Some(ViscaResponseType::DateTime),
+            InquiryCommand::UsbAudio => Some(ViscaResponseType::UsbAudio),
+            InquiryCommand::Version => Some(ViscaResponseType::Version),
+            InquiryCommand::SharpnessMode => Some(ViscaResponseType::SharpnessMode),
+            InquiryCommand::SharpnessPosition => Some(ViscaResponseType::SharpnessPosition),
+            InquiryCommand::GainPosition => Some(ViscaResponseType::Gain),
+            InquiryCommand::IrisPosition => Some(ViscaResponseType::Iris),
+            InquiryCommand::ColorTemperature => Some(ViscaResponseType::ColorTemperature),
+            InquiryCommand::Display => Some(ViscaResponseType::Display),
+            InquiryCommand::MotionSync => Some(ViscaResponseType::MotionSyncMode),
+            InquiryCommand::MotionSyncSpeed => Some(ViscaResponseType::MotionSyncSpeed),
+            InquiryCommand::LastPreset => Some(ViscaResponseType::LastPreset),
+            InquiryCommand::MenuOpen => Some(ViscaResponseType::MenuOpenClose),
+            InquiryCommand::Rtmp => Some(ViscaResponseType::Rtmp),
+            InquiryCommand::AeLevel => Some(ViscaResponseType::AeLevel),
+            InquiryCommand::ShutterPosition => Some(ViscaResponseType::Shutter),
+            InquiryCommand::BacklightState => Some(ViscaResponseType::Backlight),
+            InquiryCommand::BlockColorExposure => Some(ViscaResponseType::BlockColorExposure),
+            InquiryCommand::BlockImage => Some(ViscaResponseType::BlockImage),
+            InquiryCommand::ImageFlip => Some(ViscaResponseType::ImageFlip),
+            InquiryCommand::AutoFocusSensitivity => Some(ViscaResponseType::AutoFocusSensitivity),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn common_inquiries_have_their_opcode_and_response_type() {
+        let cases = [
+            (InquiryCommand::GainPosition, 0x4C, ViscaResponseType::Gain),
+            (InquiryCommand::IrisPosition, 0x4B, ViscaResponseType::Iris),
+            (
+                InquiryCommand::ShutterPosition,
+                0x4A,
+                ViscaResponseType::Shutter,
+            ),
+            (
+                InquiryCommand::BacklightState,
+                0x33,
+                ViscaResponseType::Backlight,
+            ),
+            (
+                InquiryCommand::FocusMode,
+                0x38,
+                ViscaResponseType::FocusMode,
+            ),
+            (InquiryCommand::Power, 0x00, ViscaResponseType::Power),
+        ];
+        for (inquiry, opcode, response_type) in cases {
+            assert_eq!(
+                inquiry.to_bytes().unwrap(),
+                [0x81, 0x09, 0x04, opcode, 0xFF],
+                "{:?}",
+                inquiry
+            );
+            assert_eq!(inquiry.response_type(), Some(response_type));
+        }
+    }
+
+    #[test]
+    fn every_inquiry_is_a_valid_frame_expecting_a_reply() {
+        for inquiry in InquiryCommand::all() {
+            assert!(inquiry.validate().is_ok(), "{:?}", inquiry);
+            assert!(inquiry.response_type().is_some(), "{:?}", inquiry);
+        }
+    }
+
+    /// Exhaustive, so a new variant fails to compile until it is listed here
+    /// and then fails the test below until it is added to `ALL`.
+    fn ordinal(inquiry: InquiryCommand) -> usize {
+        match inquiry {
+            InquiryCommand::PanTiltPosition => 0,
+            InquiryCommand::ZoomPosition => 1,
+            InquiryCommand::FocusPosition => 2,
+            InquiryCommand::ExposureMode => 3,
+            InquiryCommand::WhiteBalanceMode => 4,
+            InquiryCommand::Luminance => 5,
+            InquiryCommand::Contrast => 6,
+            InquiryCommand::ExposureCompensationPosition => 7,
+            InquiryCommand::VideoTemplate => 8,
+            InquiryCommand::Power => 9,
+            InquiryCommand::LensType => 10,
+            InquiryCommand::KneePoint => 11,
+            InquiryCommand::KneeSlope => 12,
+            InquiryCommand::BlackLevel => 13,
+            InquiryCommand::FocusMode => 14,
+            InquiryCommand::AntiFlicker => 15,
+            InquiryCommand::HorizontalFlip => 16,
+            InquiryCommand::VerticalFlip => 17,
+            InquiryCommand::DateTime => 18,
+            InquiryCommand::UsbAudio => 19,
+            InquiryCommand::Version => 20,
+            InquiryCommand::SharpnessMode => 21,
+            InquiryCommand::SharpnessPosition => 22,
+            InquiryCommand::GainPosition => 23,
+            InquiryCommand::IrisPosition => 24,
+            InquiryCommand::ShutterPosition => 25,
+            InquiryCommand::BacklightState => 26,
+            InquiryCommand::BlockColorExposure => 27,
+            InquiryCommand::BlockImage => 28,
+            InquiryCommand::ImageFlip => 29,
+            InquiryCommand::AutoFocusSensitivity => 30,
+            InquiryCommand::ColorTemperature => 31,
+            InquiryCommand::Display => 32,
+            InquiryCommand::MotionSync => 33,
+            InquiryCommand::MotionSyncSpeed => 34,
+            InquiryCommand::LastPreset => 35,
+            InquiryCommand::MenuOpen => 36,
+            InquiryCommand::AeLevel => 37,
+            InquiryCommand::Rtmp => 38,
+        }
+    }
+
+    #[test]
+    fn all_lists_every_variant_once() {
+        let mut ordinals: Vec<usize> = InquiryCommand::all().iter().copied().map(ordinal).collect();
+        ordinals.sort_unstable();
+        assert_eq!(ordinals, (0..39).collect::<Vec<_>>());
+    }
+}
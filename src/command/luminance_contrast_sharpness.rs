@@ -14,8 +14,11 @@ impl ViscaCommand for LuminanceCommand {
                 0x81, 0x01, 0x04, 0xA1, 0x00, 0x00, 0x00, self.value, 0xFF,
             ])
         } else {
-            Err(ViscaError::InvalidParameter(
-                "Luminance value must be in the range 0..=14".into(),
+            Err(ViscaError::out_of_range(
+                "Luminance value",
+                self.value,
+                0,
+                14,
             ))
         }
     }
@@ -36,8 +39,11 @@ impl ViscaCommand for ContrastCommand {
                 0x81, 0x01, 0x04, 0xA2, 0x00, 0x00, 0x00, self.value, 0xFF,
             ])
         } else {
-            Err(ViscaError::InvalidParameter(
-                "Contrast value must be in the range 0..=14".into(),
+            Err(ViscaError::out_of_range(
+                "Contrast value",
+                self.value,
+                0,
+                14,
             ))
         }
     }
@@ -47,24 +53,150 @@ impl ViscaCommand for ContrastCommand {
     }
 }
 
-pub struct SharpnessCommand {
-    pub value: u8,
+/// Sharpness (aperture) control. `Direct` takes `0..=11` and, like
+/// `Up`/`Down`, only applies in manual sharpness mode.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SharpnessCommand {
+    Auto,
+    Manual,
+    Reset,
+    Up,
+    Down,
+    Direct(u8),
 }
 
 impl ViscaCommand for SharpnessCommand {
     fn to_bytes(&self) -> Result<Vec<u8>, ViscaError> {
-        if self.value <= 11 {
-            Ok(vec![
-                0x81, 0x01, 0x04, 0x42, 0x00, 0x00, 0x00, self.value, 0xFF,
-            ])
-        } else {
-            Err(ViscaError::InvalidParameter(
-                "Sharpness value must be in the range 0..=11".into(),
-            ))
+        match self {
+            SharpnessCommand::Auto => Ok(vec![0x81, 0x01, 0x04, 0x05, 0x02, 0xFF]),
+            SharpnessCommand::Manual => Ok(vec![0x81, 0x01, 0x04, 0x05, 0x03, 0xFF]),
+            SharpnessCommand::Reset => Ok(vec![0x81, 0x01, 0x04, 0x02, 0x00, 0xFF]),
+            SharpnessCommand::Up => Ok(vec![0x81, 0x01, 0x04, 0x02, 0x02, 0xFF]),
+            SharpnessCommand::Down => Ok(vec![0x81, 0x01, 0x04, 0x02, 0x03, 0xFF]),
+            SharpnessCommand::Direct(value) => {
+                if *value <= 11 {
+                    Ok(vec![0x81, 0x01, 0x04, 0x42, 0x00, 0x00, 0x00, *value, 0xFF])
+                } else {
+                    Err(ViscaError::out_of_range("Sharpness value", *value, 0, 11))
+                }
+            }
         }
     }
 
     fn response_type(&self) -> Option<ViscaResponseType> {
         None
     }
+
+    fn name(&self) -> &'static str {
+        match self {
+            SharpnessCommand::Auto => "SharpnessAuto",
+            SharpnessCommand::Manual => "SharpnessManual",
+            SharpnessCommand::Reset => "SharpnessReset",
+            SharpnessCommand::Up => "SharpnessUp",
+            SharpnessCommand::Down => "SharpnessDown",
+            SharpnessCommand::Direct(_) => "SharpnessDirect",
+        }
+    }
+}
+
+/// Switches sharpness between automatic and manual (`81 01 04 05 0p FF`).
+pub struct SharpnessModeCommand {
+    pub auto: bool,
+}
+
+impl ViscaCommand for SharpnessModeCommand {
+    fn to_bytes(&self) -> Result<Vec<u8>, ViscaError> {
+        let mode = if self.auto { 0x02 } else { 0x03 };
+        Ok(vec![0x81, 0x01, 0x04, 0x05, mode, 0xFF])
+    }
+
+    fn response_type(&self) -> Option<ViscaResponseType> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::{response::parse_visca_response, InquiryCommand};
+    use crate::{ViscaInquiryResponse, ViscaResponse};
+
+    #[test]
+    fn sharpness_mode_frames_and_reply() {
+        assert_eq!(
+            InquiryCommand::SharpnessMode.to_bytes().unwrap(),
+            [0x81, 0x09, 0x04, 0x05, 0xFF]
+        );
+        for (auto, mode) in [(true, 0x02), (false, 0x03)] {
+            assert_eq!(
+                SharpnessModeCommand { auto }.to_bytes().unwrap(),
+                [0x81, 0x01, 0x04, 0x05, mode, 0xFF]
+            );
+            assert!(matches!(
+                parse_visca_response(&[0x90, 0x50, mode, 0xFF], &ViscaResponseType::SharpnessMode),
+                Ok(ViscaResponse::InquiryResponse(ViscaInquiryResponse::SharpnessMode { auto: a }))
+                    if a == auto
+            ));
+        }
+    }
+
+    #[test]
+    fn sharpness_position_reply() {
+        assert_eq!(
+            InquiryCommand::SharpnessPosition.to_bytes().unwrap(),
+            [0x81, 0x09, 0x04, 0x42, 0xFF]
+        );
+        assert!(matches!(
+            parse_visca_response(
+                &[0x90, 0x50, 0x00, 0x00, 0x00, 0x0B, 0xFF],
+                &ViscaResponseType::SharpnessPosition
+            ),
+            Ok(ViscaResponse::InquiryResponse(
+                ViscaInquiryResponse::SharpnessPosition { value: 0x0B }
+            ))
+        ));
+        assert!(parse_visca_response(
+            &[0x90, 0x50, 0x0B, 0xFF],
+            &ViscaResponseType::SharpnessPosition
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn sharpness_command_frames() {
+        let cases: [(SharpnessCommand, &[u8]); 7] = [
+            (
+                SharpnessCommand::Auto,
+                &[0x81, 0x01, 0x04, 0x05, 0x02, 0xFF],
+            ),
+            (
+                SharpnessCommand::Manual,
+                &[0x81, 0x01, 0x04, 0x05, 0x03, 0xFF],
+            ),
+            (
+                SharpnessCommand::Reset,
+                &[0x81, 0x01, 0x04, 0x02, 0x00, 0xFF],
+            ),
+            (SharpnessCommand::Up, &[0x81, 0x01, 0x04, 0x02, 0x02, 0xFF]),
+            (
+                SharpnessCommand::Down,
+                &[0x81, 0x01, 0x04, 0x02, 0x03, 0xFF],
+            ),
+            (
+                SharpnessCommand::Direct(0),
+                &[0x81, 0x01, 0x04, 0x42, 0x00, 0x00, 0x00, 0x00, 0xFF],
+            ),
+            (
+                SharpnessCommand::Direct(11),
+                &[0x81, 0x01, 0x04, 0x42, 0x00, 0x00, 0x00, 0x0B, 0xFF],
+            ),
+        ];
+        for (command, frame) in cases {
+            assert_eq!(command.to_bytes().unwrap(), frame, "{}", command.name());
+        }
+        assert!(matches!(
+            SharpnessCommand::Direct(12).to_bytes(),
+            Err(ViscaError::ParameterOutOfRange { max: 11, .. })
+        ));
+    }
 }
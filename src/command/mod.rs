@@ -1,3 +1,6 @@
+pub mod broadcast;
+pub mod catalog;
+pub mod codec;
 pub mod exposure;
 pub mod flip;
 pub mod focus;
@@ -7,46 +10,461 @@ pub mod luminance_contrast_sharpness;
 pub mod pan_tilt;
 pub mod power;
 pub mod preset;
+pub mod raw;
 pub mod response;
+pub mod system;
 pub mod white_balance;
 pub mod zoom;
 
-pub use exposure::ExposureCommand;
+pub use broadcast::{BlackLevelCommand, KneeCommand};
+pub use exposure::ExposureCompensationCommand;
 pub use exposure::ExposureMode;
-pub use flip::ImageFlipCommand;
-pub use focus::FocusCommand;
+pub use exposure::{
+    AeLevelCommand, AntiFlickerCommand, AntiFlickerMode, ExposureCommand, ExposureDirectCommand,
+    ExposureSettings, GainCommand,
+};
+pub use flip::{Flip, ImageFlipCommand, MirrorCommand};
+pub use focus::{FocusCommand, FocusMode};
 pub use image::BacklightCommand;
 pub use inquiry::InquiryCommand;
-pub use luminance_contrast_sharpness::{ContrastCommand, LuminanceCommand, SharpnessCommand};
-pub use pan_tilt::PanTiltCommand;
-pub use power::PowerCommand;
-pub use preset::PresetCommand;
-pub use response::{ViscaResponse, ViscaResponseType};
+pub use luminance_contrast_sharpness::{
+    ContrastCommand, LuminanceCommand, SharpnessCommand, SharpnessModeCommand,
+};
+pub use pan_tilt::{
+    MotionSyncCommand, PanTiltAbsoluteCommand, PanTiltCommand, PanTiltRampCommand,
+    PanTiltRelativeCommand,
+};
+pub use power::{Power, PowerCommand};
+pub use preset::{PresetCommand, PresetSpeedCommand};
+pub use raw::RawCommand;
+pub use response::{ReplyPattern, ViscaResponse, ViscaResponseType};
+pub use system::{
+    CancelCommand, DateTimeCommand, DisplayCommand, IfClearCommand, LensTypeCommand, RtmpCommand,
+    TitleDisplayCommand, UsbAudioCommand, VideoTemplateCommand,
+};
+pub use white_balance::ColorTemperatureCommand;
 pub use white_balance::WhiteBalanceCommand;
 pub use white_balance::WhiteBalanceMode;
-pub use zoom::ZoomCommand;
+pub use zoom::{ZoomCommand, ZoomFocusDirectCommand};
 
-use crate::ViscaError;
+use std::time::Duration;
+
+use crate::{CameraModel, ViscaError};
 
 pub trait ViscaCommand {
     fn to_bytes(&self) -> Result<Vec<u8>, ViscaError>;
     fn response_type(&self) -> Option<ViscaResponseType>;
+
+    /// Encodes the command, validating parameters against the given profile.
+    ///
+    /// Commands without model-specific ranges fall back to `to_bytes`.
+    fn to_bytes_for(&self, _model: CameraModel) -> Result<Vec<u8>, ViscaError> {
+        self.to_bytes()
+    }
+
+    /// Checks the encoded frame before it is written to a transport.
+    fn validate(&self) -> Result<(), ViscaError> {
+        validate_frame(&self.to_bytes()?)
+    }
+
+    /// Short human-readable name for logs and errors, e.g. `"ZoomDirect"`.
+    /// Defaults to the type name without its `Command` suffix.
+    fn name(&self) -> &'static str {
+        let type_name = std::any::type_name::<Self>();
+        let type_name = type_name.rsplit("::").next().unwrap_or(type_name);
+        type_name.strip_suffix("Command").unwrap_or(type_name)
+    }
+
+    /// How long past a read timeout the Completion is still waited for once
+    /// the command is ACKed. Commands that move the head to a target only
+    /// complete when the move has physically finished, which can take longer
+    /// than the read timeout; `None`, the default, ends the wait at the
+    /// first read timeout.
+    fn completion_timeout(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Replies the camera sends for this command. Inquiries are answered
+    /// without an ACK; control commands get an ACK and a completion.
+    fn reply_pattern(&self) -> ReplyPattern {
+        if self.response_type().is_some() {
+            ReplyPattern::CompletionOnly
+        } else {
+            ReplyPattern::AckAndCompletion
+        }
+    }
+}
+
+/// A command whose reply is decoded as a caller-chosen response type.
+pub struct ResponseTypeOverride<'a> {
+    command: &'a dyn ViscaCommand,
+    response_type: ViscaResponseType,
+}
+
+impl ViscaCommand for ResponseTypeOverride<'_> {
+    fn to_bytes(&self) -> Result<Vec<u8>, ViscaError> {
+        self.command.to_bytes()
+    }
+
+    fn response_type(&self) -> Option<ViscaResponseType> {
+        Some(self.response_type)
+    }
+
+    fn to_bytes_for(&self, model: CameraModel) -> Result<Vec<u8>, ViscaError> {
+        self.command.to_bytes_for(model)
+    }
+
+    fn reply_pattern(&self) -> ReplyPattern {
+        self.command.reply_pattern()
+    }
+
+    fn completion_timeout(&self) -> Option<Duration> {
+        self.command.completion_timeout()
+    }
+
+    fn name(&self) -> &'static str {
+        self.command.name()
+    }
+}
+
+/// Advanced: forces the reply to `command` to be decoded as `response_type`
+/// instead of `command.response_type()`, for firmware whose payload shape
+/// differs from the default. Pass the result to `send_command_and_wait` or
+/// `Camera::send`.
+pub fn with_response_type(
+    command: &dyn ViscaCommand,
+    response_type: ViscaResponseType,
+) -> ResponseTypeOverride<'_> {
+    ResponseTypeOverride {
+        command,
+        response_type,
+    }
+}
+
+/// Rejects frames with a `0xFF` anywhere but the final byte, since the
+/// camera would read it as the terminator and misparse the rest.
+pub fn finalize(frame: Vec<u8>) -> Result<Vec<u8>, ViscaError> {
+    match frame.split_last() {
+        Some((0xFF, body)) if !body.contains(&0xFF) => Ok(frame),
+        _ => Err(ViscaError::InvalidParameter(format!(
+            "Frame must contain 0xFF only as its terminator: {:02X?}",
+            frame
+        ))),
+    }
+}
+
+/// Checks a command frame is well formed: a `0x8x` header, a single `0xFF`
+/// terminator and a length of 3 to 16 bytes.
+pub fn validate_frame(frame: &[u8]) -> Result<(), ViscaError> {
+    if !(3..=16).contains(&frame.len()) {
+        return Err(ViscaError::InvalidParameter(format!(
+            "Frame length must be in the range 3..=16: {:02X?}",
+            frame
+        )));
+    }
+    if frame[0] & 0xF0 != 0x80 {
+        return Err(ViscaError::InvalidParameter(format!(
+            "Frame must start with a 0x8x header: {:02X?}",
+            frame
+        )));
+    }
+    finalize(frame.to_vec()).map(|_| ())
 }
 
 // ViscaInquiryResponse defines various response types for inquiry commands.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ViscaInquiryResponse {
-    PanTiltPosition { pan: i16, tilt: i16 },
+    PanTiltPosition {
+        pan: i16,
+        tilt: i16,
+    },
     Luminance(u8),
     Contrast(u8),
-    ZoomPosition { position: u16 },
-    FocusPosition { position: u16 },
-    Gain { gain: u8 },
-    WhiteBalance { mode: WhiteBalanceMode },
-    ExposureMode { mode: ExposureMode },
-    ExposureCompensation { value: i8 },
-    Backlight { status: bool },
-    ColorTemperature { temperature: u16 },
-    Hue { hue: u8 },
+    ZoomPosition {
+        position: u16,
+    },
+    FocusPosition {
+        position: u16,
+        /// Focus tracking state, on cameras that append it to the reply.
+        tracking: Option<bool>,
+    },
+    Gain {
+        gain: u8,
+    },
+    Iris {
+        iris: u8,
+    },
+    Shutter {
+        shutter: u8,
+    },
+    WhiteBalance {
+        mode: WhiteBalanceMode,
+    },
+    ExposureMode {
+        mode: ExposureMode,
+    },
+    ExposureCompensation {
+        value: i8,
+    },
+    Backlight {
+        status: bool,
+    },
+    /// Color temperature position, `0x00` (2500K) to
+    /// [`MAX_COLOR_TEMPERATURE`](Self::MAX_COLOR_TEMPERATURE) (8000K).
+    ColorTemperature {
+        temperature: u8,
+    },
+    Hue {
+        hue: u8,
+    },
+    VideoTemplate {
+        template: u8,
+    },
+    Power {
+        power: Power,
+    },
+    LensType {
+        lens: u8,
+    },
+    KneePoint {
+        point: u8,
+    },
+    KneeSlope {
+        slope: u8,
+    },
+    BlackLevel {
+        level: u8,
+    },
+    FocusMode {
+        mode: FocusMode,
+    },
+    AntiFlicker {
+        mode: AntiFlickerMode,
+    },
+    HorizontalFlip {
+        enabled: bool,
+    },
+    VerticalFlip {
+        enabled: bool,
+    },
+    DateTime {
+        year: u16,
+        month: u8,
+        day: u8,
+        hour: u8,
+        minute: u8,
+        second: u8,
+    },
+    UsbAudio {
+        enabled: bool,
+    },
+    Display {
+        enabled: bool,
+    },
+    MenuOpen {
+        open: bool,
+    },
+    /// Which RTMP streams are running.
+    Rtmp {
+        stream1: bool,
+        stream2: bool,
+    },
+    /// Whether AF sensitivity is low rather than high or normal.
+    AfSensitivity {
+        low: bool,
+    },
+    SharpnessMode {
+        auto: bool,
+    },
+    MotionSync {
+        enabled: bool,
+    },
+    MotionSyncSpeed {
+        speed: u8,
+    },
+    LastPreset {
+        number: u8,
+    },
+    AeLevel {
+        value: u8,
+    },
+    SharpnessPosition {
+        value: u8,
+    },
+    ImageFlip {
+        horizontal: bool,
+        vertical: bool,
+    },
+    /// Decoded `81 09 7E 7E 01 FF` block, see
+    /// [`ViscaResponseType::BlockColorExposure`].
+    BlockColorExposure {
+        r_gain: u8,
+        b_gain: u8,
+        wb_mode: WhiteBalanceMode,
+        /// `0x0..=0xB`.
+        saturation: u8,
+        exposure_mode: ExposureMode,
+        backlight: bool,
+        /// Whether exposure compensation is on.
+        exposure_compensation_on: bool,
+        shutter: u8,
+        iris: u8,
+        luminance: u8,
+        /// Position `0x0..=0xE`, `0x7` being 0 EV.
+        exposure_compensation: u8,
+    },
+    /// Decoded `81 09 7E 7E 03 FF` block, see
+    /// [`ViscaResponseType::BlockImage`]. Replaces the separate AF
+    /// sensitivity, flip, color gain and noise reduction inquiries with one
+    /// read.
+    BlockImage {
+        af_sensitivity: u8,
+        picture_flip: bool,
+        /// `0x0..=0xE`, 60% to 200%.
+        color_gain: u8,
+        horizontal_flip: bool,
+        vertical_flip: bool,
+        /// `0x0..=0x7`.
+        nr_2d_level: u8,
+        gain_limit: u8,
+    },
+    Version {
+        vendor_id: u16,
+        model_id: u16,
+        rom_version: u16,
+        max_socket: u8,
+    },
     // Add other specific inquiry responses as needed.
 }
+
+impl ViscaInquiryResponse {
+    /// Highest color temperature position, 8000K.
+    pub const MAX_COLOR_TEMPERATURE: u8 = 0x37;
+
+    /// Gain of a [`ViscaInquiryResponse::Gain`] reply in dB, per
+    /// [`CameraModel::gain_db`]. `None` for other replies.
+    pub fn gain_db(&self, model: CameraModel) -> Option<f32> {
+        match self {
+            ViscaInquiryResponse::Gain { gain } => model.gain_db(*gain),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mock::MockTransport, ViscaTransport};
+    use pan_tilt::{PanSpeed, PanTiltDirection, TiltSpeed};
+
+    /// A direct command that copies its parameter into the frame unchecked,
+    /// as a builder with bad parameter math would.
+    struct UncheckedDirect {
+        value: u8,
+    }
+
+    impl ViscaCommand for UncheckedDirect {
+        fn to_bytes(&self) -> Result<Vec<u8>, ViscaError> {
+            Ok(vec![
+                0x81, 0x01, 0x04, 0x47, 0x00, 0x00, 0x00, self.value, 0xFF,
+            ])
+        }
+
+        fn response_type(&self) -> Option<ViscaResponseType> {
+            None
+        }
+    }
+
+    #[test]
+    fn ff_inside_the_payload_is_rejected() {
+        assert!(UncheckedDirect { value: 0x0A }.validate().is_ok());
+        assert!(matches!(
+            UncheckedDirect { value: 0xFF }.validate(),
+            Err(ViscaError::InvalidParameter(_))
+        ));
+        assert!(finalize(vec![0x81, 0x01, 0xFF, 0x02, 0xFF]).is_err());
+        assert!(finalize(vec![0x81, 0x01, 0x02]).is_err());
+        assert_eq!(
+            finalize(vec![0x81, 0x01, 0x02, 0xFF]).unwrap(),
+            [0x81, 0x01, 0x02, 0xFF]
+        );
+    }
+
+    #[test]
+    fn smuggled_ff_never_reaches_the_transport() {
+        let mut transport = MockTransport::acking();
+        assert!(transport
+            .send_command(&UncheckedDirect { value: 0xFF })
+            .is_err());
+        assert!(transport.sent().is_empty());
+    }
+
+    #[test]
+    fn frames_need_a_header_and_a_sane_length() {
+        assert!(validate_frame(&[0x81, 0x01, 0xFF]).is_ok());
+        assert!(validate_frame(&[0x90, 0x01, 0xFF]).is_err());
+        assert!(validate_frame(&[0x81, 0xFF]).is_err());
+        let mut long = vec![0x81; 16];
+        long.push(0xFF);
+        assert!(validate_frame(&long).is_err());
+    }
+
+    #[test]
+    fn response_type_override_changes_how_the_reply_is_parsed() {
+        // A four-nibble reply reads as a zoom or a focus position depending
+        // on what the caller expects.
+        let reply: &[u8] = &[0x90, 0x50, 0x01, 0x02, 0x03, 0x04, 0xFF];
+        let mut transport = MockTransport::new();
+        transport.respond(&[0x81, 0x09], &[reply]);
+
+        let inquiry = InquiryCommand::ZoomPosition;
+        assert!(matches!(
+            crate::send_command_and_wait(&mut transport, &inquiry),
+            Ok(ViscaResponse::InquiryResponse(
+                ViscaInquiryResponse::ZoomPosition { position: 0x1234 }
+            ))
+        ));
+
+        let overridden = with_response_type(&inquiry, ViscaResponseType::FocusPosition);
+        assert_eq!(overridden.to_bytes().unwrap(), inquiry.to_bytes().unwrap());
+        assert!(matches!(
+            crate::send_command_and_wait(&mut transport, &overridden),
+            Ok(ViscaResponse::InquiryResponse(
+                ViscaInquiryResponse::FocusPosition {
+                    position: 0x1234,
+                    tracking: None
+                }
+            ))
+        ));
+    }
+
+    #[test]
+    fn commands_have_short_names() {
+        assert_eq!(ZoomCommand::Direct(0x0500).name(), "ZoomDirect");
+        assert_eq!(ZoomCommand::Stop.name(), "ZoomStop");
+        assert_eq!(
+            PanTiltCommand {
+                direction: PanTiltDirection::UpLeft,
+                pan_speed: PanSpeed::LOW_SPEED,
+                tilt_speed: TiltSpeed::LOW_SPEED,
+            }
+            .name(),
+            "PanTiltDrive"
+        );
+        assert_eq!(InquiryCommand::Power.name(), "InquiryPower");
+    }
+
+    #[test]
+    fn gain_db_follows_the_sony_table() {
+        let fr7 = CameraModel::SonyFr7;
+        for (gain, db) in [(0x00, -3.0), (0x01, 0.0), (0x05, 12.0), (0x0F, 42.0)] {
+            assert_eq!(ViscaInquiryResponse::Gain { gain }.gain_db(fr7), Some(db));
+        }
+        assert_eq!(ViscaInquiryResponse::Gain { gain: 0x10 }.gain_db(fr7), None);
+        assert_eq!(
+            ViscaInquiryResponse::Gain { gain: 0x05 }.gain_db(CameraModel::PtzOpticsG2),
+            None
+        );
+        assert_eq!(ViscaInquiryResponse::Iris { iris: 0x05 }.gain_db(fr7), None);
+    }
+}
@@ -1,7 +1,11 @@
+use std::time::Duration;
+
 use crate::command::ViscaCommand;
-use crate::error::ViscaError;
+use crate::error::{RangeFormat, ViscaError};
+use crate::model::CameraModel;
+use crate::DEFAULT_MOTION_COMPLETION_TIMEOUT;
 
-use super::ViscaResponseType;
+use super::{codec::encode_i16_nibbles, ViscaResponseType};
 
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum PanTiltDirection {
@@ -40,6 +44,55 @@ pub struct PanTiltCommand {
     pub tilt_speed: TiltSpeed,
 }
 
+impl PanTiltCommand {
+    /// Converts a joystick deflection to a drive command.
+    ///
+    /// `x` (right positive) and `y` (up positive) are in `-1.0..=1.0`. The
+    /// angle picks the nearest of the eight directions and the deflection
+    /// scales both speeds up to their maximum. Deflections within `deadzone`
+    /// of the centre produce `Stop`. Speeds are scaled to the default
+    /// profile's limits; see [`from_vector_for`](Self::from_vector_for).
+    pub fn from_vector(x: f32, y: f32, deadzone: f32) -> Self {
+        Self::from_vector_for(x, y, deadzone, CameraModel::default())
+    }
+
+    /// Like [`from_vector`](Self::from_vector), with the speeds scaled to and
+    /// clamped at `model`'s [`max_pan_tilt_speed`](CameraModel::max_pan_tilt_speed).
+    pub fn from_vector_for(x: f32, y: f32, deadzone: f32, model: CameraModel) -> Self {
+        // `min` would turn a NaN into 1.0, so check before clamping.
+        let magnitude = x.hypot(y);
+        if magnitude.is_nan() || magnitude <= deadzone {
+            return PanTiltCommand {
+                direction: PanTiltDirection::Stop,
+                pan_speed: PanSpeed::STOP,
+                tilt_speed: TiltSpeed::STOP,
+            };
+        }
+
+        const DIRECTIONS: [PanTiltDirection; 8] = [
+            PanTiltDirection::Right,
+            PanTiltDirection::UpRight,
+            PanTiltDirection::Up,
+            PanTiltDirection::UpLeft,
+            PanTiltDirection::Left,
+            PanTiltDirection::DownLeft,
+            PanTiltDirection::Down,
+            PanTiltDirection::DownRight,
+        ];
+        let magnitude = magnitude.min(1.0);
+        let octant = (y.atan2(x) / std::f32::consts::FRAC_PI_4).round() as i32;
+        let direction = DIRECTIONS[octant.rem_euclid(8) as usize];
+
+        let (max_pan, max_tilt) = model.max_pan_tilt_speed();
+        let scale = |max: u8| ((magnitude * f32::from(max)).round() as u8).clamp(1, max);
+        PanTiltCommand {
+            direction,
+            pan_speed: PanSpeed(scale(max_pan.min(PanSpeed::HIGH_SPEED.0))),
+            tilt_speed: TiltSpeed(scale(max_tilt.min(TiltSpeed::HIGH_SPEED.0))),
+        }
+    }
+}
+
 impl ViscaCommand for PanTiltCommand {
     fn to_bytes(&self) -> Result<Vec<u8>, ViscaError> {
         let (dir_byte1, dir_byte2) = self.direction.to_bytes();
@@ -63,6 +116,183 @@ impl ViscaCommand for PanTiltCommand {
     fn response_type(&self) -> Option<ViscaResponseType> {
         None
     }
+
+    fn completion_timeout(&self) -> Option<Duration> {
+        (self.direction == PanTiltDirection::Home).then_some(DEFAULT_MOTION_COMPLETION_TIMEOUT)
+    }
+
+    fn name(&self) -> &'static str {
+        match self.direction {
+            PanTiltDirection::Home => "PanTiltHome",
+            PanTiltDirection::Stop => "PanTiltStop",
+            _ => "PanTiltDrive",
+        }
+    }
+}
+
+fn position_bytes(
+    opcode: u8,
+    pan_speed: PanSpeed,
+    tilt_speed: TiltSpeed,
+    pan: i16,
+    tilt: i16,
+) -> Vec<u8> {
+    let mut bytes = vec![
+        0x81,
+        0x01,
+        0x06,
+        opcode,
+        pan_speed.get_value(),
+        tilt_speed.get_value(),
+    ];
+    bytes.extend_from_slice(&encode_i16_nibbles(pan));
+    bytes.extend_from_slice(&encode_i16_nibbles(tilt));
+    bytes.push(0xFF);
+    bytes
+}
+
+/// Moves to an absolute pan/tilt position.
+pub struct PanTiltAbsoluteCommand {
+    pub pan_speed: PanSpeed,
+    pub tilt_speed: TiltSpeed,
+    pub pan: i16,
+    pub tilt: i16,
+}
+
+impl ViscaCommand for PanTiltAbsoluteCommand {
+    fn to_bytes(&self) -> Result<Vec<u8>, ViscaError> {
+        Ok(position_bytes(
+            0x02,
+            self.pan_speed,
+            self.tilt_speed,
+            self.pan,
+            self.tilt,
+        ))
+    }
+
+    fn response_type(&self) -> Option<ViscaResponseType> {
+        None
+    }
+
+    fn completion_timeout(&self) -> Option<Duration> {
+        Some(DEFAULT_MOTION_COMPLETION_TIMEOUT)
+    }
+
+    fn name(&self) -> &'static str {
+        "PanTiltAbsolute"
+    }
+}
+
+/// Moves by a pan/tilt offset from the current position.
+pub struct PanTiltRelativeCommand {
+    pub pan_speed: PanSpeed,
+    pub tilt_speed: TiltSpeed,
+    pub pan: i16,
+    pub tilt: i16,
+}
+
+impl ViscaCommand for PanTiltRelativeCommand {
+    fn to_bytes(&self) -> Result<Vec<u8>, ViscaError> {
+        Ok(position_bytes(
+            0x03,
+            self.pan_speed,
+            self.tilt_speed,
+            self.pan,
+            self.tilt,
+        ))
+    }
+
+    fn response_type(&self) -> Option<ViscaResponseType> {
+        None
+    }
+
+    fn completion_timeout(&self) -> Option<Duration> {
+        Some(DEFAULT_MOTION_COMPLETION_TIMEOUT)
+    }
+
+    fn name(&self) -> &'static str {
+        "PanTiltRelative"
+    }
+}
+
+/// PTZOptics MotionSync, which scales pan/tilt and zoom speeds so preset
+/// moves arrive together (`81 0A 11 13 0p FF`), and its speed limit
+/// (`81 0A 11 14 0p FF`).
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum MotionSyncCommand {
+    On,
+    Off,
+    /// `1..=15`.
+    MaxSpeed(u8),
+}
+
+impl MotionSyncCommand {
+    pub const MAX_SPEED: u8 = 0x0F;
+}
+
+impl ViscaCommand for MotionSyncCommand {
+    fn to_bytes(&self) -> Result<Vec<u8>, ViscaError> {
+        match self {
+            MotionSyncCommand::On => Ok(vec![0x81, 0x0A, 0x11, 0x13, 0x02, 0xFF]),
+            MotionSyncCommand::Off => Ok(vec![0x81, 0x0A, 0x11, 0x13, 0x03, 0xFF]),
+            MotionSyncCommand::MaxSpeed(speed) => {
+                if (1..=Self::MAX_SPEED).contains(speed) {
+                    Ok(vec![0x81, 0x0A, 0x11, 0x14, *speed, 0xFF])
+                } else {
+                    Err(ViscaError::out_of_range(
+                        "MotionSync max speed",
+                        *speed,
+                        1,
+                        Self::MAX_SPEED.into(),
+                    ))
+                }
+            }
+        }
+    }
+
+    fn response_type(&self) -> Option<ViscaResponseType> {
+        None
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            MotionSyncCommand::On => "MotionSyncOn",
+            MotionSyncCommand::Off => "MotionSyncOff",
+            MotionSyncCommand::MaxSpeed(_) => "MotionSyncMaxSpeed",
+        }
+    }
+}
+
+/// Pan/tilt acceleration ramp curve, in the Sony extended command family
+/// (`81 01 7E 01 5B 0p FF`). Lower values ramp faster, higher values ease
+/// in and out more gently. PTZOptics G2 cameras don't support it.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PanTiltRampCommand {
+    /// `1..=3`.
+    pub accel: u8,
+}
+
+impl PanTiltRampCommand {
+    pub const MAX_ACCEL: u8 = 0x03;
+}
+
+impl ViscaCommand for PanTiltRampCommand {
+    fn to_bytes(&self) -> Result<Vec<u8>, ViscaError> {
+        if (1..=Self::MAX_ACCEL).contains(&self.accel) {
+            Ok(vec![0x81, 0x01, 0x7E, 0x01, 0x5B, self.accel, 0xFF])
+        } else {
+            Err(ViscaError::out_of_range(
+                "Pan/tilt ramp",
+                self.accel,
+                1,
+                Self::MAX_ACCEL.into(),
+            ))
+        }
+    }
+
+    fn response_type(&self) -> Option<ViscaResponseType> {
+        None
+    }
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -77,9 +307,8 @@ impl PanSpeed {
         if Self::is_valid_value(value) {
             Ok(PanSpeed(value))
         } else {
-            Err(ViscaError::InvalidParameter(
-                "Pan speed must be in the range 0x00..=0x18".into(),
-            ))
+            Err(ViscaError::out_of_range("Pan speed", value, 0, 24)
+                .with_range_format(RangeFormat::Hex))
         }
     }
 
@@ -104,9 +333,8 @@ impl TiltSpeed {
         if Self::is_valid_value(value) {
             Ok(TiltSpeed(value))
         } else {
-            Err(ViscaError::InvalidParameter(
-                "Tilt speed must be in the range 0x00..=0x14".into(),
-            ))
+            Err(ViscaError::out_of_range("Tilt speed", value, 0, 20)
+                .with_range_format(RangeFormat::Hex))
         }
     }
 
@@ -118,3 +346,120 @@ impl TiltSpeed {
         self.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drive(x: f32, y: f32) -> (PanTiltDirection, u8, u8) {
+        let command = PanTiltCommand::from_vector(x, y, 0.1);
+        (
+            command.direction,
+            command.pan_speed.get_value(),
+            command.tilt_speed.get_value(),
+        )
+    }
+
+    #[test]
+    fn full_deflection_picks_the_direction_at_top_speed() {
+        let d = std::f32::consts::FRAC_1_SQRT_2;
+        for (x, y, direction) in [
+            (1.0, 0.0, PanTiltDirection::Right),
+            (-1.0, 0.0, PanTiltDirection::Left),
+            (0.0, 1.0, PanTiltDirection::Up),
+            (0.0, -1.0, PanTiltDirection::Down),
+            (d, d, PanTiltDirection::UpRight),
+            (-d, d, PanTiltDirection::UpLeft),
+            (d, -d, PanTiltDirection::DownRight),
+            (-d, -d, PanTiltDirection::DownLeft),
+        ] {
+            assert_eq!(drive(x, y), (direction, 0x18, 0x14), "({}, {})", x, y);
+        }
+    }
+
+    #[test]
+    fn deflection_scales_the_speed() {
+        assert_eq!(drive(0.5, 0.0), (PanTiltDirection::Right, 0x0C, 0x0A));
+        // Beyond the unit circle is clamped to full speed.
+        assert_eq!(drive(2.0, 0.0), (PanTiltDirection::Right, 0x18, 0x14));
+        // Just outside the deadzone still moves.
+        assert_eq!(drive(0.0, 0.11), (PanTiltDirection::Up, 0x03, 0x02));
+    }
+
+    #[test]
+    fn inside_the_deadzone_is_stop() {
+        for (x, y) in [(0.0, 0.0), (0.05, -0.05), (0.1, 0.0), (f32::NAN, 0.5)] {
+            assert_eq!(
+                drive(x, y),
+                (PanTiltDirection::Stop, 0, 0),
+                "({}, {})",
+                x,
+                y
+            );
+        }
+        let command = PanTiltCommand::from_vector(0.0, 0.0, 0.1);
+        assert_eq!(
+            command.to_bytes().unwrap(),
+            [0x81, 0x01, 0x06, 0x01, 0x00, 0x00, 0x03, 0x03, 0xFF]
+        );
+    }
+
+    #[test]
+    fn motion_sync_max_speed_range() {
+        for speed in [1, MotionSyncCommand::MAX_SPEED] {
+            assert_eq!(
+                MotionSyncCommand::MaxSpeed(speed).to_bytes().unwrap(),
+                [0x81, 0x0A, 0x11, 0x14, speed, 0xFF]
+            );
+        }
+        for speed in [0, MotionSyncCommand::MAX_SPEED + 1] {
+            assert!(matches!(
+                MotionSyncCommand::MaxSpeed(speed).to_bytes(),
+                Err(ViscaError::ParameterOutOfRange {
+                    min: 1,
+                    max: 15,
+                    ..
+                })
+            ));
+        }
+    }
+
+    #[test]
+    fn ramp_frame_and_range() {
+        for accel in 1..=PanTiltRampCommand::MAX_ACCEL {
+            assert_eq!(
+                PanTiltRampCommand { accel }.to_bytes().unwrap(),
+                [0x81, 0x01, 0x7E, 0x01, 0x5B, accel, 0xFF]
+            );
+        }
+        for accel in [0, PanTiltRampCommand::MAX_ACCEL + 1] {
+            assert!(matches!(
+                PanTiltRampCommand { accel }.to_bytes(),
+                Err(ViscaError::ParameterOutOfRange { min: 1, max: 3, .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn motion_sync_mode_reply_is_zero_or_one() {
+        use crate::command::response::parse_visca_response;
+        use crate::{ViscaInquiryResponse, ViscaResponse};
+
+        let parse = |state: u8| {
+            parse_visca_response(
+                &[0x90, 0x50, state, 0xFF],
+                &ViscaResponseType::MotionSyncMode,
+            )
+        };
+        for (state, enabled) in [(0x00, false), (0x01, true)] {
+            assert!(matches!(
+                parse(state),
+                Ok(ViscaResponse::InquiryResponse(ViscaInquiryResponse::MotionSync { enabled: e }))
+                    if e == enabled
+            ));
+        }
+        // The set command's 02/03 are not replies.
+        assert!(parse(0x02).is_err());
+        assert!(parse(0x03).is_err());
+    }
+}
@@ -1,14 +1,27 @@
 use crate::command::ViscaCommand;
 use crate::error::ViscaError;
+use std::convert::TryFrom;
 
 use super::ViscaResponseType;
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Power {
     On = 0x02,
     Standby = 0x03,
 }
 
+impl TryFrom<u8> for Power {
+    type Error = ();
+
+    fn try_from(v: u8) -> Result<Self, Self::Error> {
+        match v {
+            0x02 => Ok(Power::On),
+            0x03 => Ok(Power::Standby),
+            _ => Err(()),
+        }
+    }
+}
+
 pub struct PowerCommand {
     pub power: Power,
 }
@@ -21,4 +34,25 @@ impl ViscaCommand for PowerCommand {
     fn response_type(&self) -> Option<ViscaResponseType> {
         None
     }
+
+    fn name(&self) -> &'static str {
+        match self.power {
+            Power::On => "PowerOn",
+            Power::Standby => "PowerStandby",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn power_try_from_maps_the_documented_bytes() {
+        assert_eq!(Power::try_from(0x02), Ok(Power::On));
+        assert_eq!(Power::try_from(0x03), Ok(Power::Standby));
+        for byte in [0x00, 0x01, 0x04, 0xFF] {
+            assert_eq!(Power::try_from(byte), Err(()));
+        }
+    }
 }
@@ -0,0 +1,82 @@
+use crate::command::{validate_frame, ViscaCommand};
+use crate::error::ViscaError;
+
+use super::ViscaResponseType;
+
+/// A caller-built frame, for opcodes the crate doesn't model yet.
+///
+/// The frame must include the `0x8x` header and `0xFF` terminator; it is
+/// checked with [`validate_frame`] before being passed through unchanged.
+pub struct RawCommand {
+    pub bytes: Vec<u8>,
+    pub response_type: Option<ViscaResponseType>,
+}
+
+impl ViscaCommand for RawCommand {
+    fn to_bytes(&self) -> Result<Vec<u8>, ViscaError> {
+        validate_frame(&self.bytes)?;
+        Ok(self.bytes.clone())
+    }
+
+    fn response_type(&self) -> Option<ViscaResponseType> {
+        self.response_type
+    }
+
+    fn name(&self) -> &'static str {
+        "Raw"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mock::MockTransport, send_command_and_wait, ViscaResponse};
+
+    #[test]
+    fn raw_frame_is_sent_unchanged_and_completes() {
+        let frame = vec![0x81, 0x01, 0x04, 0x3F, 0x02, 0x05, 0xFF];
+        let mut transport = MockTransport::acking();
+        let command = RawCommand {
+            bytes: frame.clone(),
+            response_type: None,
+        };
+        assert!(matches!(
+            send_command_and_wait(&mut transport, &command),
+            Ok(ViscaResponse::Completion(_))
+        ));
+        assert_eq!(transport.sent(), [frame]);
+    }
+
+    #[test]
+    fn raw_inquiry_is_parsed_with_its_response_type() {
+        let mut transport = MockTransport::new();
+        transport.reply(&[&[0x90, 0x50, 0x03, 0xFF]]);
+        let command = RawCommand {
+            bytes: vec![0x81, 0x09, 0x04, 0x00, 0xFF],
+            response_type: Some(ViscaResponseType::Power),
+        };
+        assert!(matches!(
+            send_command_and_wait(&mut transport, &command),
+            Ok(ViscaResponse::InquiryResponse(
+                crate::ViscaInquiryResponse::Power {
+                    power: crate::command::Power::Standby
+                }
+            ))
+        ));
+    }
+
+    #[test]
+    fn malformed_raw_frames_are_rejected() {
+        for bytes in [
+            vec![0x81, 0x01, 0x04],
+            vec![0x01, 0x01, 0x04, 0xFF],
+            vec![0x81, 0xFF, 0x04, 0xFF],
+        ] {
+            let command = RawCommand {
+                bytes,
+                response_type: None,
+            };
+            assert!(command.to_bytes().is_err());
+        }
+    }
+}
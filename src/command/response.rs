@@ -1,14 +1,58 @@
-use log::error;
+use log::{debug, error};
 
-use super::{ExposureMode, ViscaInquiryResponse, WhiteBalanceMode};
+use super::{
+    codec::{decode_i16_nibbles, decode_u16_nibbles, encode_i16_nibbles, encode_u16_nibbles},
+    AntiFlickerMode, DateTimeCommand, ExposureCompensationCommand, ExposureMode, Flip, FocusMode,
+    Power, ViscaInquiryResponse, WhiteBalanceMode,
+};
 use crate::error::ViscaError;
 
+/// A command socket (`0..=15`), the low nibble of ACK, Completion, error
+/// and cancel frames. Cameras typically use sockets 1 and 2.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Socket(u8);
+
+impl Socket {
+    pub const MAX: u8 = 0x0F;
+
+    pub fn new(socket: u8) -> Result<Self, ViscaError> {
+        if socket <= Self::MAX {
+            Ok(Socket(socket))
+        } else {
+            Err(ViscaError::out_of_range(
+                "Socket",
+                socket,
+                0,
+                Self::MAX.into(),
+            ))
+        }
+    }
+
+    /// The socket in the low nibble of a reply's second byte.
+    pub(crate) fn from_nibble(byte: u8) -> Self {
+        Socket(byte & 0x0F)
+    }
+
+    pub fn get(&self) -> u8 {
+        self.0
+    }
+}
+
+impl std::fmt::Display for Socket {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
 #[derive(Debug)]
 pub enum ViscaResponse {
-    Ack,
-    Completion,
+    Ack(Socket),
+    Completion(Socket),
     Error(ViscaError),
     InquiryResponse(ViscaInquiryResponse),
+    /// Unsolicited `90 38 FF` sent when the daisy chain changes, after
+    /// which addresses should be reassigned with AddressSet.
+    NetworkChange,
     Unknown(Vec<u8>),
 }
 
@@ -46,12 +90,17 @@ pub enum ViscaResponseType {
     TwoDNoiseReduction,
     MotionSyncMode,
     MotionSyncSpeed,
+    LastPreset,
+    AeLevel,
     FocusMode,
     FocusZone,
     AutoFocusSensitivity,
     FocusRange,
     MenuOpenClose,
     UsbAudio,
+    Display,
+    /// Which RTMP streams are running. Only on/off is exposed over VISCA;
+    /// the stream URL and key are provisioned through the camera's HTTP API.
     Rtmp,
     BlockLens,
     BlockColorExposure,
@@ -59,75 +108,193 @@ pub enum ViscaResponseType {
     BlockImage,
     ZoomWideStandard,
     ZoomTeleStandard,
+    VideoTemplate,
+    Power,
+    LensType,
+    KneePoint,
+    KneeSlope,
+    BlackLevel,
+    DateTime,
+    Version,
+    Gain,
+}
+
+impl ViscaResponseType {
+    /// Length of the complete reply frame, including header and terminator,
+    /// for inquiries with a fixed-length reply. `None` for block inquiries,
+    /// whose decoders check their own length, and for types without a
+    /// decoder. Pan/tilt and focus replies may be longer, see
+    /// `parse_visca_response`.
+    pub fn expected_len(&self) -> Option<usize> {
+        match self {
+            // 90 50 pq FF
+            ViscaResponseType::ExposureMode
+            | ViscaResponseType::WhiteBalanceMode
+            | ViscaResponseType::VideoTemplate
+            | ViscaResponseType::Power
+            | ViscaResponseType::LensType
+            | ViscaResponseType::FocusMode
+            | ViscaResponseType::AntiFlicker
+            | ViscaResponseType::HorizontalFlip
+            | ViscaResponseType::VerticalFlip
+            | ViscaResponseType::UsbAudio
+            | ViscaResponseType::Display
+            | ViscaResponseType::AutoFocusSensitivity
+            | ViscaResponseType::MenuOpenClose
+            | ViscaResponseType::Rtmp
+            | ViscaResponseType::ImageFlip
+            | ViscaResponseType::SharpnessMode
+            | ViscaResponseType::MotionSyncMode
+            | ViscaResponseType::MotionSyncSpeed
+            | ViscaResponseType::LastPreset
+            | ViscaResponseType::ColorTemperature
+            | ViscaResponseType::Backlight => Some(4),
+            // 90 50 0p 0q 0r 0s FF and 90 50 00 00 0p 0q FF
+            ViscaResponseType::ZoomPosition
+            | ViscaResponseType::FocusPosition
+            | ViscaResponseType::ExposureCompensationPosition
+            | ViscaResponseType::SharpnessPosition
+            | ViscaResponseType::Gain
+            | ViscaResponseType::Luminance
+            | ViscaResponseType::Contrast
+            | ViscaResponseType::Hue
+            | ViscaResponseType::Iris
+            | ViscaResponseType::Shutter
+            | ViscaResponseType::AeLevel
+            | ViscaResponseType::KneePoint
+            | ViscaResponseType::KneeSlope
+            | ViscaResponseType::BlackLevel => Some(7),
+            ViscaResponseType::DateTime => Some(9),
+            ViscaResponseType::Version => Some(10),
+            ViscaResponseType::PanTiltPosition => Some(11),
+            _ => None,
+        }
+    }
+}
+
+impl ViscaResponse {
+    /// Whether the camera sends this on its own rather than in reply to a
+    /// command:
+    ///
+    /// - [`ViscaResponse::NetworkChange`] when the daisy chain changes.
+    /// - [`ViscaResponse::Unknown`], which is how camera-initiated events
+    ///   without a decoder arrive, such as the menu being opened or closed
+    ///   from the IR remote.
+    pub fn is_unsolicited(&self) -> bool {
+        matches!(
+            self,
+            ViscaResponse::NetworkChange | ViscaResponse::Unknown(_)
+        )
+    }
+
+    /// Decodes a single reply frame.
+    ///
+    /// Frames come straight off the network, so this never panics: every
+    /// malformed input is reported as an `Err`.
+    pub fn from_bytes(
+        response: &[u8],
+        response_type: &ViscaResponseType,
+    ) -> Result<Self, ViscaError> {
+        parse_visca_response(response, response_type)
+    }
+}
+
+/// Address (1-7) of the camera that sent a reply, from the `z0` header byte
+/// where `z` is the address plus 8: `0x90` for camera 1, `0xB0` for camera 3.
+/// `None` if `header` is not a reply header.
+pub fn reply_address(header: u8) -> Option<u8> {
+    match header {
+        0x90..=0xF0 if header & 0x0F == 0 => Some((header >> 4) - 8),
+        _ => None,
+    }
 }
 
+/// Replies are accepted from any camera address; see [`reply_address`].
 pub fn parse_visca_response(
     response: &[u8],
     response_type: &ViscaResponseType,
 ) -> Result<ViscaResponse, ViscaError> {
-    if response.len() < 3 || response[0] != 0x90 || response[response.len() - 1] != 0xFF {
+    if response.len() < 3
+        || reply_address(response[0]).is_none()
+        || response[response.len() - 1] != 0xFF
+    {
         return Err(ViscaError::InvalidResponseFormat);
     }
 
     match response[1] {
-        0x40..=0x4F => Ok(ViscaResponse::Ack),
-        0x50..=0x5F => {
-            if response.len() == 3 {
-                return Ok(ViscaResponse::Completion);
+        0x50..=0x5F if response.len() > 3 => {
+            if let Some(expected) = response_type.expected_len() {
+                // Pan/tilt and focus replies may carry trailing status bytes.
+                let fits = match response_type {
+                    ViscaResponseType::PanTiltPosition | ViscaResponseType::FocusPosition => {
+                        response.len() >= expected
+                    }
+                    _ => response.len() == expected,
+                };
+                if !fits {
+                    return Err(ViscaError::InvalidResponseLength {
+                        expected,
+                        actual: response.len(),
+                    });
+                }
             }
 
             match response_type {
                 ViscaResponseType::PanTiltPosition => {
-                    if response.len() != 11 {
-                        return Err(ViscaError::InvalidResponseLength);
+                    // Standard frame is `90 50 0p 0p 0p 0p 0t 0t 0t 0t FF`. Some
+                    // firmware appends status bytes before the terminator; the
+                    // position nibbles keep the same offsets, so the extra
+                    // bytes are skipped.
+                    if response.len() > 11 {
+                        debug!(
+                            "Ignoring trailing pan/tilt status bytes: {:02X?}",
+                            &response[10..response.len() - 1]
+                        );
                     }
 
-                    let mut pan = (response[2] as i16) << 12;
-                    pan |= (response[3] as i16) << 8;
-                    pan |= (response[4] as i16) << 4;
-                    pan |= response[5] as i16;
-
-                    let mut tilt = (response[6] as i16) << 12;
-                    tilt |= (response[7] as i16) << 8;
-                    tilt |= (response[8] as i16) << 4;
-                    tilt |= response[9] as i16;
+                    let pan = decode_i16_nibbles(&response[2..6])?;
+                    let tilt = decode_i16_nibbles(&response[6..10])?;
 
                     Ok(ViscaResponse::InquiryResponse(
                         ViscaInquiryResponse::PanTiltPosition { pan, tilt },
                     ))
                 }
                 ViscaResponseType::ZoomPosition => {
-                    if response.len() != 7 {
-                        return Err(ViscaError::InvalidResponseLength);
-                    }
-
-                    let mut position = (response[2] as u16) << 12;
-                    position |= (response[3] as u16) << 8;
-                    position |= (response[4] as u16) << 4;
-                    position |= response[5] as u16;
+                    let position = decode_u16_nibbles(&response[2..6])?;
 
                     Ok(ViscaResponse::InquiryResponse(
                         ViscaInquiryResponse::ZoomPosition { position },
                     ))
                 }
-                ViscaResponseType::FocusPosition => {
-                    if response.len() != 7 {
-                        return Err(ViscaError::InvalidResponseLength);
+                ViscaResponseType::ColorTemperature => {
+                    // 90 50 pq FF, a position from 0x00 (2500K) to 0x37 (8000K)
+                    // per the G2 command list in docs/.
+                    let temperature = response[2];
+                    if temperature > ViscaInquiryResponse::MAX_COLOR_TEMPERATURE {
+                        return Err(ViscaError::UnexpectedResponseType);
                     }
-
-                    let mut position = (response[2] as u16) << 12;
-                    position |= (response[3] as u16) << 8;
-                    position |= (response[4] as u16) << 4;
-                    position |= response[5] as u16;
+                    Ok(ViscaResponse::InquiryResponse(
+                        ViscaInquiryResponse::ColorTemperature { temperature },
+                    ))
+                }
+                ViscaResponseType::FocusPosition => {
+                    // 90 50 0p 0q 0r 0s FF, or with focus tracking on some
+                    // models 90 50 0p 0q 0r 0s 0t FF where t is 2 (on) or 3 (off).
+                    let position = decode_u16_nibbles(&response[2..6])?;
+                    let tracking = match &response[6..response.len() - 1] {
+                        [] => None,
+                        [status] => Flip::try_from(*status).ok().map(bool::from),
+                        extra => {
+                            debug!("Ignoring trailing focus status bytes: {:02X?}", extra);
+                            None
+                        }
+                    };
 
                     Ok(ViscaResponse::InquiryResponse(
-                        ViscaInquiryResponse::FocusPosition { position },
+                        ViscaInquiryResponse::FocusPosition { position, tracking },
                     ))
                 }
                 ViscaResponseType::ExposureMode => {
-                    if response.len() != 4 {
-                        return Err(ViscaError::InvalidResponseLength);
-                    }
                     let mode = ExposureMode::try_from(response[2])
                         .map_err(|_| ViscaError::UnexpectedResponseType)?;
                     Ok(ViscaResponse::InquiryResponse(
@@ -135,22 +302,1263 @@ pub fn parse_visca_response(
                     ))
                 }
                 ViscaResponseType::WhiteBalanceMode => {
-                    if response.len() != 4 {
-                        return Err(ViscaError::InvalidResponseLength);
-                    }
                     let mode = WhiteBalanceMode::try_from(response[2])
                         .map_err(|_| ViscaError::UnexpectedResponseType)?;
                     Ok(ViscaResponse::InquiryResponse(
                         ViscaInquiryResponse::WhiteBalance { mode },
                     ))
                 }
-                _ => Ok(ViscaResponse::Completion),
+                ViscaResponseType::ExposureCompensationPosition => {
+                    let value = ExposureCompensationCommand::decode_step(byte_payload(response)?)?;
+                    Ok(ViscaResponse::InquiryResponse(
+                        ViscaInquiryResponse::ExposureCompensation { value },
+                    ))
+                }
+                ViscaResponseType::VideoTemplate => Ok(ViscaResponse::InquiryResponse(
+                    ViscaInquiryResponse::VideoTemplate {
+                        template: response[2],
+                    },
+                )),
+                ViscaResponseType::Power => {
+                    let power = Power::try_from(response[2])
+                        .map_err(|_| ViscaError::UnexpectedResponseType)?;
+                    Ok(ViscaResponse::InquiryResponse(
+                        ViscaInquiryResponse::Power { power },
+                    ))
+                }
+                ViscaResponseType::LensType => Ok(ViscaResponse::InquiryResponse(
+                    ViscaInquiryResponse::LensType { lens: response[2] },
+                )),
+                ViscaResponseType::FocusMode => {
+                    let mode = FocusMode::try_from(response[2])
+                        .map_err(|_| ViscaError::UnexpectedResponseType)?;
+                    Ok(ViscaResponse::InquiryResponse(
+                        ViscaInquiryResponse::FocusMode { mode },
+                    ))
+                }
+                ViscaResponseType::AntiFlicker => {
+                    let mode = AntiFlickerMode::try_from(response[2])
+                        .map_err(|_| ViscaError::UnexpectedResponseType)?;
+                    Ok(ViscaResponse::InquiryResponse(
+                        ViscaInquiryResponse::AntiFlicker { mode },
+                    ))
+                }
+                ViscaResponseType::HorizontalFlip => Ok(ViscaResponse::InquiryResponse(
+                    ViscaInquiryResponse::HorizontalFlip {
+                        enabled: on_off_payload(response)?,
+                    },
+                )),
+                ViscaResponseType::VerticalFlip => Ok(ViscaResponse::InquiryResponse(
+                    ViscaInquiryResponse::VerticalFlip {
+                        enabled: on_off_payload(response)?,
+                    },
+                )),
+                ViscaResponseType::ImageFlip => {
+                    // 90 50 0p FF, answering 81 09 04 A4 FF. The G2 command list
+                    // in docs/ gives p as 0 off, 1 horizontal flip, 2 vertical
+                    // flip and 3 both, i.e. bit 0 horizontal and bit 1 vertical.
+                    if response[2] > 0x03 {
+                        return Err(ViscaError::UnexpectedResponseType);
+                    }
+                    Ok(ViscaResponse::InquiryResponse(
+                        ViscaInquiryResponse::ImageFlip {
+                            horizontal: response[2] & 0x01 != 0,
+                            vertical: response[2] & 0x02 != 0,
+                        },
+                    ))
+                }
+                ViscaResponseType::UsbAudio => Ok(ViscaResponse::InquiryResponse(
+                    ViscaInquiryResponse::UsbAudio {
+                        enabled: on_off_payload(response)?,
+                    },
+                )),
+                ViscaResponseType::Display => Ok(ViscaResponse::InquiryResponse(
+                    ViscaInquiryResponse::Display {
+                        enabled: on_off_payload(response)?,
+                    },
+                )),
+                // 90 50 01 FF is high, 90 50 02 FF normal, 90 50 03 FF low.
+                ViscaResponseType::AutoFocusSensitivity => {
+                    let low = match response[2] {
+                        0x01 | 0x02 => false,
+                        0x03 => true,
+                        _ => return Err(ViscaError::UnexpectedResponseType),
+                    };
+                    Ok(ViscaResponse::InquiryResponse(
+                        ViscaInquiryResponse::AfSensitivity { low },
+                    ))
+                }
+                ViscaResponseType::MenuOpenClose => Ok(ViscaResponse::InquiryResponse(
+                    ViscaInquiryResponse::MenuOpen {
+                        open: on_off_payload(response)?,
+                    },
+                )),
+                // 90 50 0p FF with bit 0 for stream 1 and bit 1 for stream 2.
+                ViscaResponseType::Rtmp => {
+                    if response[2] > 0x03 {
+                        return Err(ViscaError::UnexpectedResponseType);
+                    }
+                    Ok(ViscaResponse::InquiryResponse(ViscaInquiryResponse::Rtmp {
+                        stream1: response[2] & 0x01 != 0,
+                        stream2: response[2] & 0x02 != 0,
+                    }))
+                }
+                // 90 50 0p FF where 0 is off and 1 on, unlike the 02/03 of the
+                // set command.
+                ViscaResponseType::MotionSyncMode => {
+                    let enabled = match response[2] {
+                        0x00 => false,
+                        0x01 => true,
+                        _ => return Err(ViscaError::UnexpectedResponseType),
+                    };
+                    Ok(ViscaResponse::InquiryResponse(
+                        ViscaInquiryResponse::MotionSync { enabled },
+                    ))
+                }
+                // 90 50 0p FF
+                ViscaResponseType::MotionSyncSpeed => Ok(ViscaResponse::InquiryResponse(
+                    ViscaInquiryResponse::MotionSyncSpeed {
+                        speed: response[2] & 0x0F,
+                    },
+                )),
+                ViscaResponseType::AeLevel => Ok(ViscaResponse::InquiryResponse(
+                    ViscaInquiryResponse::AeLevel {
+                        value: byte_payload(response)?,
+                    },
+                )),
+                // 90 50 pp FF
+                ViscaResponseType::LastPreset => Ok(ViscaResponse::InquiryResponse(
+                    ViscaInquiryResponse::LastPreset {
+                        number: response[2],
+                    },
+                )),
+                ViscaResponseType::SharpnessMode => Ok(ViscaResponse::InquiryResponse(
+                    ViscaInquiryResponse::SharpnessMode {
+                        auto: on_off_payload(response)?,
+                    },
+                )),
+                ViscaResponseType::SharpnessPosition => Ok(ViscaResponse::InquiryResponse(
+                    ViscaInquiryResponse::SharpnessPosition {
+                        value: byte_payload(response)?,
+                    },
+                )),
+                ViscaResponseType::Gain => {
+                    Ok(ViscaResponse::InquiryResponse(ViscaInquiryResponse::Gain {
+                        gain: byte_payload(response)?,
+                    }))
+                }
+                ViscaResponseType::Luminance => Ok(ViscaResponse::InquiryResponse(
+                    ViscaInquiryResponse::Luminance(byte_payload(response)?),
+                )),
+                ViscaResponseType::Contrast => Ok(ViscaResponse::InquiryResponse(
+                    ViscaInquiryResponse::Contrast(byte_payload(response)?),
+                )),
+                ViscaResponseType::Hue => {
+                    Ok(ViscaResponse::InquiryResponse(ViscaInquiryResponse::Hue {
+                        hue: byte_payload(response)?,
+                    }))
+                }
+                ViscaResponseType::Iris => {
+                    Ok(ViscaResponse::InquiryResponse(ViscaInquiryResponse::Iris {
+                        iris: byte_payload(response)?,
+                    }))
+                }
+                ViscaResponseType::Shutter => Ok(ViscaResponse::InquiryResponse(
+                    ViscaInquiryResponse::Shutter {
+                        shutter: byte_payload(response)?,
+                    },
+                )),
+                // 90 50 02 FF is on and 90 50 03 FF off; 00/01 are rejected.
+                ViscaResponseType::Backlight => Ok(ViscaResponse::InquiryResponse(
+                    ViscaInquiryResponse::Backlight {
+                        status: on_off_payload(response)?,
+                    },
+                )),
+                ViscaResponseType::BlockColorExposure => {
+                    // 90 50 0p 0p 0q 0q 0r 0s tt 0u vv ww 00 xx 0z FF, per the
+                    // block inquiry table in
+                    // docs/PTZOptics-G2-VISCA-over-IP-Command-List.pdf.
+                    //
+                    // | Offset | Field                                     |
+                    // |--------|-------------------------------------------|
+                    // | 2-3    | R gain, two nibbles                       |
+                    // | 4-5    | B gain, two nibbles                       |
+                    // | 6      | White balance mode                        |
+                    // | 7      | Saturation                                |
+                    // | 8      | Exposure mode                             |
+                    // | 9      | Bit 2 backlight, bit 1 exposure comp. on  |
+                    // | 10     | Shutter position                          |
+                    // | 11     | Iris position                             |
+                    // | 12     | Reserved, `00`                            |
+                    // | 13     | Luminance position                        |
+                    // | 14     | Exposure compensation position            |
+                    check_block_len(response)?;
+                    let pair = |high: u8, low: u8| ((high & 0x0F) << 4) | (low & 0x0F);
+                    let wb_mode = WhiteBalanceMode::try_from(response[6])
+                        .map_err(|_| ViscaError::UnexpectedResponseType)?;
+                    let exposure_mode = ExposureMode::try_from(response[8])
+                        .map_err(|_| ViscaError::UnexpectedResponseType)?;
+                    Ok(ViscaResponse::InquiryResponse(
+                        ViscaInquiryResponse::BlockColorExposure {
+                            r_gain: pair(response[2], response[3]),
+                            b_gain: pair(response[4], response[5]),
+                            wb_mode,
+                            saturation: response[7],
+                            exposure_mode,
+                            backlight: response[9] & 0x04 != 0,
+                            exposure_compensation_on: response[9] & 0x02 != 0,
+                            shutter: response[10],
+                            iris: response[11],
+                            luminance: response[13],
+                            exposure_compensation: response[14],
+                        },
+                    ))
+                }
+                ViscaResponseType::BlockImage => {
+                    // 90 50 00 00 00 00 00 00 00 0p 0q rr 0s 0t 0u FF, per the
+                    // block inquiry table in
+                    // docs/PTZOptics-G2-VISCA-over-IP-Command-List.pdf.
+                    //
+                    // | Offset | Field                                     |
+                    // |--------|-------------------------------------------|
+                    // | 2-8    | Reserved, `00`                            |
+                    // | 9      | AF sensitivity                            |
+                    // | 10     | Bit 0 picture flip                        |
+                    // | 11     | Bits 6-3 color gain                       |
+                    // | 12     | Flip: 0 off, 1 H, 2 V, 3 H and V          |
+                    // | 13     | Bits 2-0 2D noise reduction level         |
+                    // | 14     | Gain limit                                |
+                    check_block_len(response)?;
+                    if response[12] > 0x03 {
+                        return Err(ViscaError::UnexpectedResponseType);
+                    }
+                    Ok(ViscaResponse::InquiryResponse(
+                        ViscaInquiryResponse::BlockImage {
+                            af_sensitivity: response[9],
+                            picture_flip: response[10] & 0x01 != 0,
+                            color_gain: (response[11] >> 3) & 0x0F,
+                            horizontal_flip: response[12] & 0x01 != 0,
+                            vertical_flip: response[12] & 0x02 != 0,
+                            nr_2d_level: response[13] & 0x07,
+                            gain_limit: response[14],
+                        },
+                    ))
+                }
+                ViscaResponseType::KneePoint => Ok(ViscaResponse::InquiryResponse(
+                    ViscaInquiryResponse::KneePoint {
+                        point: byte_payload(response)?,
+                    },
+                )),
+                ViscaResponseType::KneeSlope => Ok(ViscaResponse::InquiryResponse(
+                    ViscaInquiryResponse::KneeSlope {
+                        slope: byte_payload(response)?,
+                    },
+                )),
+                ViscaResponseType::BlackLevel => Ok(ViscaResponse::InquiryResponse(
+                    ViscaInquiryResponse::BlackLevel {
+                        level: byte_payload(response)?,
+                    },
+                )),
+                ViscaResponseType::DateTime => {
+                    // 90 50 yy MM dd hh mm ss FF
+                    let DateTimeCommand {
+                        year,
+                        month,
+                        day,
+                        hour,
+                        minute,
+                        second,
+                    } = DateTimeCommand::from_bcd_fields(&response[2..8])?;
+                    Ok(ViscaResponse::InquiryResponse(
+                        ViscaInquiryResponse::DateTime {
+                            year,
+                            month,
+                            day,
+                            hour,
+                            minute,
+                            second,
+                        },
+                    ))
+                }
+                ViscaResponseType::Version => {
+                    // 90 50 GG GG HH HH JJ JJ KK FF
+                    Ok(ViscaResponse::InquiryResponse(
+                        ViscaInquiryResponse::Version {
+                            vendor_id: u16::from_be_bytes([response[2], response[3]]),
+                            model_id: u16::from_be_bytes([response[4], response[5]]),
+                            rom_version: u16::from_be_bytes([response[6], response[7]]),
+                            max_socket: response[8],
+                        },
+                    ))
+                }
+                // A payload the inquiry has no decoder for is not a
+                // completion, whatever its socket nibble says.
+                _ => Err(ViscaError::UnexpectedResponseType),
             }
         }
-        0x60..=0x6F => Err(ViscaError::from_code(response[2])),
+        _ => parse_status_response(response),
+    }
+}
+
+/// Decodes the `02` (on) / `03` (off) state carried by a `90 50 0p FF` reply.
+fn on_off_payload(response: &[u8]) -> Result<bool, ViscaError> {
+    if response.len() != 4 {
+        return Err(ViscaError::InvalidResponseLength {
+            expected: 4,
+            actual: response.len(),
+        });
+    }
+    Flip::try_from(response[2])
+        .map(bool::from)
+        .map_err(|_| ViscaError::UnexpectedResponseType)
+}
+
+/// Decodes the single byte carried by a `90 50 00 00 0p 0q FF` reply.
+fn byte_payload(response: &[u8]) -> Result<u8, ViscaError> {
+    if response.len() != 7 {
+        return Err(ViscaError::InvalidResponseLength {
+            expected: 7,
+            actual: response.len(),
+        });
+    }
+    Ok(((response[4] & 0x0F) << 4) | (response[5] & 0x0F))
+}
+
+/// Length of every block inquiry reply, header and terminator included.
+const BLOCK_REPLY_LEN: usize = 16;
+
+fn check_block_len(response: &[u8]) -> Result<(), ViscaError> {
+    if response.len() != BLOCK_REPLY_LEN {
+        return Err(ViscaError::InvalidResponseLength {
+            expected: BLOCK_REPLY_LEN,
+            actual: response.len(),
+        });
+    }
+    Ok(())
+}
+
+/// Decodes the ACK, completion and error replies to a command that expects
+/// no inquiry payload.
+pub fn parse_status_response(response: &[u8]) -> Result<ViscaResponse, ViscaError> {
+    if response.len() < 3
+        || reply_address(response[0]).is_none()
+        || response[response.len() - 1] != 0xFF
+    {
+        return Err(ViscaError::InvalidResponseFormat);
+    }
+
+    match response[1] {
+        0x38 if response.len() == 3 => Ok(ViscaResponse::NetworkChange),
+        0x40..=0x4F => Ok(ViscaResponse::Ack(Socket::from_nibble(response[1]))),
+        0x50..=0x5F => {
+            if response.len() != 3 {
+                return Err(ViscaError::UnexpectedResponseType);
+            }
+            Ok(ViscaResponse::Completion(Socket::from_nibble(response[1])))
+        }
+        0x60..=0x6F => {
+            if response.len() != 4 {
+                return Err(ViscaError::InvalidResponseLength {
+                    expected: 4,
+                    actual: response.len(),
+                });
+            }
+            Err(ViscaError::from_code(response[2]))
+        }
         _ => {
             error!("Unknown response: {:02X?}", response);
             Ok(ViscaResponse::Unknown(response.to_vec()))
         }
     }
 }
+
+/// Which replies a camera sends for a command, and so which one ends the
+/// wait for it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum ReplyPattern {
+    /// Only an ACK is sent; the wait ends on the ACK.
+    AckOnly,
+    /// Only a completion (or inquiry reply) is sent.
+    CompletionOnly,
+    /// An ACK followed by a completion; the wait ends on the completion.
+    #[default]
+    AckAndCompletion,
+}
+
+/// Builds the `90 4y FF` ACK a camera sends when it accepts a command.
+pub fn ack_frame(socket: Socket) -> Vec<u8> {
+    vec![0x90, 0x40 | socket.get(), 0xFF]
+}
+
+/// Builds the `90 5y FF` completion a camera sends when a command finishes.
+pub fn completion_frame(socket: Socket) -> Vec<u8> {
+    vec![0x90, 0x50 | socket.get(), 0xFF]
+}
+
+/// Builds the reply frame a camera sends for an inquiry, in the same layout
+/// `parse_visca_response` decodes.
+pub fn inquiry_frame(inquiry_response: &ViscaInquiryResponse) -> Result<Vec<u8>, ViscaError> {
+    let byte = |value: u8| [0x00, 0x00, value >> 4, value & 0x0F];
+
+    let payload: Vec<u8> = match inquiry_response {
+        // 90 50 0p 0p 0p 0p 0t 0t 0t 0t FF
+        ViscaInquiryResponse::PanTiltPosition { pan, tilt } => {
+            let mut payload = encode_i16_nibbles(*pan).to_vec();
+            payload.extend_from_slice(&encode_i16_nibbles(*tilt));
+            payload
+        }
+        // 90 50 0p 0q 0r 0s FF
+        ViscaInquiryResponse::ZoomPosition { position } => encode_u16_nibbles(*position).to_vec(),
+        // 90 50 0p 0q 0r 0s [0t] FF
+        ViscaInquiryResponse::FocusPosition { position, tracking } => {
+            let mut payload = encode_u16_nibbles(*position).to_vec();
+            payload.extend(tracking.map(|tracking| Flip::from(tracking) as u8));
+            payload
+        }
+        // 90 50 00 00 0p 0q FF
+        ViscaInquiryResponse::Luminance(value)
+        | ViscaInquiryResponse::Contrast(value)
+        | ViscaInquiryResponse::Gain { gain: value }
+        | ViscaInquiryResponse::Iris { iris: value }
+        | ViscaInquiryResponse::Shutter { shutter: value }
+        | ViscaInquiryResponse::AeLevel { value }
+        | ViscaInquiryResponse::Hue { hue: value }
+        | ViscaInquiryResponse::KneePoint { point: value }
+        | ViscaInquiryResponse::KneeSlope { slope: value }
+        | ViscaInquiryResponse::BlackLevel { level: value }
+        | ViscaInquiryResponse::SharpnessPosition { value } => byte(*value).to_vec(),
+        ViscaInquiryResponse::ExposureCompensation { value } => {
+            byte(ExposureCompensationCommand::encode_step(*value)?).to_vec()
+        }
+        // 90 50 pq FF
+        ViscaInquiryResponse::WhiteBalance { mode } => vec![*mode as u8],
+        ViscaInquiryResponse::ExposureMode { mode } => vec![*mode as u8],
+        ViscaInquiryResponse::MotionSyncSpeed { speed } => vec![*speed],
+        ViscaInquiryResponse::ColorTemperature { temperature } => vec![*temperature],
+        ViscaInquiryResponse::LastPreset { number } => vec![*number],
+        ViscaInquiryResponse::Backlight { status: enabled }
+        | ViscaInquiryResponse::HorizontalFlip { enabled }
+        | ViscaInquiryResponse::VerticalFlip { enabled }
+        | ViscaInquiryResponse::UsbAudio { enabled }
+        | ViscaInquiryResponse::Display { enabled }
+        | ViscaInquiryResponse::MenuOpen { open: enabled }
+        | ViscaInquiryResponse::SharpnessMode { auto: enabled } => {
+            vec![if *enabled { 0x02 } else { 0x03 }]
+        }
+        ViscaInquiryResponse::MotionSync { enabled } => vec![u8::from(*enabled)],
+        ViscaInquiryResponse::AfSensitivity { low } => vec![if *low { 0x03 } else { 0x02 }],
+        ViscaInquiryResponse::ImageFlip {
+            horizontal,
+            vertical,
+        } => vec![u8::from(*horizontal) | (u8::from(*vertical) << 1)],
+        ViscaInquiryResponse::Rtmp { stream1, stream2 } => {
+            vec![u8::from(*stream1) | (u8::from(*stream2) << 1)]
+        }
+        ViscaInquiryResponse::VideoTemplate { template } => vec![*template],
+        ViscaInquiryResponse::LensType { lens } => vec![*lens],
+        ViscaInquiryResponse::Power { power } => vec![*power as u8],
+        ViscaInquiryResponse::FocusMode { mode } => vec![*mode as u8],
+        ViscaInquiryResponse::AntiFlicker { mode } => vec![*mode as u8],
+        // 90 50 yy MM dd hh mm ss FF
+        ViscaInquiryResponse::DateTime {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+        } => DateTimeCommand {
+            year: *year,
+            month: *month,
+            day: *day,
+            hour: *hour,
+            minute: *minute,
+            second: *second,
+        }
+        .bcd_fields()?
+        .to_vec(),
+        // 90 50 0p 0p 0q 0q 0r 0s tt 0u vv ww 00 xx 0z FF
+        ViscaInquiryResponse::BlockColorExposure {
+            r_gain,
+            b_gain,
+            wb_mode,
+            saturation,
+            exposure_mode,
+            backlight,
+            exposure_compensation_on,
+            shutter,
+            iris,
+            luminance,
+            exposure_compensation,
+        } => vec![
+            r_gain >> 4,
+            r_gain & 0x0F,
+            b_gain >> 4,
+            b_gain & 0x0F,
+            *wb_mode as u8,
+            *saturation,
+            *exposure_mode as u8,
+            (u8::from(*backlight) << 2) | (u8::from(*exposure_compensation_on) << 1),
+            *shutter,
+            *iris,
+            0x00,
+            *luminance,
+            *exposure_compensation,
+        ],
+        // 90 50 00 00 00 00 00 00 00 0p 0q rr 0s 0t 0u FF
+        ViscaInquiryResponse::BlockImage {
+            af_sensitivity,
+            picture_flip,
+            color_gain,
+            horizontal_flip,
+            vertical_flip,
+            nr_2d_level,
+            gain_limit,
+        } => vec![
+            0x00,
+            0x00,
+            0x00,
+            0x00,
+            0x00,
+            0x00,
+            0x00,
+            *af_sensitivity,
+            u8::from(*picture_flip),
+            (color_gain & 0x0F) << 3,
+            u8::from(*horizontal_flip) | (u8::from(*vertical_flip) << 1),
+            nr_2d_level & 0x07,
+            *gain_limit,
+        ],
+        // 90 50 GG GG HH HH JJ JJ KK FF
+        ViscaInquiryResponse::Version {
+            vendor_id,
+            model_id,
+            rom_version,
+            max_socket,
+        } => {
+            let mut payload = vendor_id.to_be_bytes().to_vec();
+            payload.extend_from_slice(&model_id.to_be_bytes());
+            payload.extend_from_slice(&rom_version.to_be_bytes());
+            payload.push(*max_socket);
+            payload
+        }
+    };
+
+    let mut frame = vec![0x90, 0x50];
+    frame.extend(payload);
+    frame.push(0xFF);
+    Ok(frame)
+}
+
+/// Builds the `90 6y ee FF` error reply a camera sends for `err` in `socket`.
+///
+/// Errors without a wire code are reported as `CommandNotExecutable`.
+pub fn build_error_response(socket: Socket, err: &ViscaError) -> Vec<u8> {
+    let code = err.to_code().unwrap_or(0x41);
+    vec![0x90, 0x60 | socket.get(), code, 0xFF]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL_RESPONSE_TYPES: &[ViscaResponseType] = &[
+        ViscaResponseType::PanTiltPosition,
+        ViscaResponseType::ZoomPosition,
+        ViscaResponseType::FocusPosition,
+        ViscaResponseType::ExposureMode,
+        ViscaResponseType::WhiteBalanceMode,
+        ViscaResponseType::Luminance,
+        ViscaResponseType::Contrast,
+        ViscaResponseType::SharpnessMode,
+        ViscaResponseType::SharpnessPosition,
+        ViscaResponseType::HorizontalFlip,
+        ViscaResponseType::VerticalFlip,
+        ViscaResponseType::ImageFlip,
+        ViscaResponseType::BlackWhiteMode,
+        ViscaResponseType::ExposureCompensationMode,
+        ViscaResponseType::ExposureCompensationPosition,
+        ViscaResponseType::Backlight,
+        ViscaResponseType::Iris,
+        ViscaResponseType::Shutter,
+        ViscaResponseType::GainLimit,
+        ViscaResponseType::AntiFlicker,
+        ViscaResponseType::RedTuning,
+        ViscaResponseType::BlueTuning,
+        ViscaResponseType::Saturation,
+        ViscaResponseType::Hue,
+        ViscaResponseType::RedGain,
+        ViscaResponseType::BlueGain,
+        ViscaResponseType::ColorTemperature,
+        ViscaResponseType::AutoWhiteBalanceSensitivity,
+        ViscaResponseType::ThreeDNoiseReduction,
+        ViscaResponseType::TwoDNoiseReduction,
+        ViscaResponseType::MotionSyncMode,
+        ViscaResponseType::MotionSyncSpeed,
+        ViscaResponseType::LastPreset,
+        ViscaResponseType::AeLevel,
+        ViscaResponseType::FocusMode,
+        ViscaResponseType::FocusZone,
+        ViscaResponseType::AutoFocusSensitivity,
+        ViscaResponseType::FocusRange,
+        ViscaResponseType::MenuOpenClose,
+        ViscaResponseType::UsbAudio,
+        ViscaResponseType::Display,
+        ViscaResponseType::Rtmp,
+        ViscaResponseType::BlockLens,
+        ViscaResponseType::BlockColorExposure,
+        ViscaResponseType::BlockPowerImageEffect,
+        ViscaResponseType::BlockImage,
+        ViscaResponseType::ZoomWideStandard,
+        ViscaResponseType::ZoomTeleStandard,
+        ViscaResponseType::VideoTemplate,
+        ViscaResponseType::Power,
+        ViscaResponseType::LensType,
+        ViscaResponseType::KneePoint,
+        ViscaResponseType::KneeSlope,
+        ViscaResponseType::BlackLevel,
+        ViscaResponseType::DateTime,
+        ViscaResponseType::Version,
+        ViscaResponseType::Gain,
+    ];
+
+    /// xorshift64, so the fuzz inputs are the same on every run.
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn bytes(&mut self, len: usize) -> Vec<u8> {
+            (0..len).map(|_| self.next() as u8).collect()
+        }
+    }
+
+    #[test]
+    fn random_bytes_never_panic() {
+        let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+        for _ in 0..20_000 {
+            let len = (rng.next() % 24) as usize;
+            let bytes = rng.bytes(len);
+            for response_type in ALL_RESPONSE_TYPES {
+                let _ = parse_visca_response(&bytes, response_type);
+            }
+            let _ = parse_status_response(&bytes);
+            let _ = crate::split_frames(&bytes);
+        }
+    }
+
+    #[test]
+    fn well_framed_random_payloads_never_panic() {
+        let mut rng = Rng(0x2545_F491_4F6C_DD1D);
+        for _ in 0..5_000 {
+            for response_type in ALL_RESPONSE_TYPES {
+                let len = response_type
+                    .expected_len()
+                    .unwrap_or(4 + (rng.next() % 16) as usize);
+                let mut frame = rng.bytes(len);
+                frame[0] = 0x90;
+                frame[1] = 0x50 | (frame[1] & 0x0F);
+                frame[len - 1] = 0xFF;
+                let _ = parse_visca_response(&frame, response_type);
+            }
+        }
+    }
+
+    #[test]
+    fn truncated_and_unterminated_frames_are_errors() {
+        for response_type in ALL_RESPONSE_TYPES {
+            assert!(parse_visca_response(&[], response_type).is_err());
+            assert!(parse_visca_response(&[0x90], response_type).is_err());
+            assert!(parse_visca_response(&[0x90, 0x50, 0x02], response_type).is_err());
+            assert!(parse_visca_response(&[0x81, 0x50, 0xFF], response_type).is_err());
+        }
+    }
+
+    #[test]
+    fn exposure_compensation_decodes_to_signed_ev() {
+        let decode = |frame: &[u8]| match parse_visca_response(
+            frame,
+            &ViscaResponseType::ExposureCompensationPosition,
+        ) {
+            Ok(ViscaResponse::InquiryResponse(ViscaInquiryResponse::ExposureCompensation {
+                value,
+            })) => value,
+            other => panic!("unexpected {:?}", other),
+        };
+        assert_eq!(decode(&[0x90, 0x50, 0x00, 0x00, 0x00, 0x0A, 0xFF]), 3);
+        assert_eq!(decode(&[0x90, 0x50, 0x00, 0x00, 0x00, 0x04, 0xFF]), -3);
+        assert_eq!(decode(&[0x90, 0x50, 0x00, 0x00, 0x00, 0x07, 0xFF]), 0);
+        assert!(parse_visca_response(
+            &[0x90, 0x50, 0x00, 0x00, 0x00, 0x0F, 0xFF],
+            &ViscaResponseType::ExposureCompensationPosition
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn exposure_compensation_matches_the_set_encoding() {
+        use crate::ViscaCommand;
+
+        for value in [-3, 3] {
+            let set = ExposureCompensationCommand::Direct(value)
+                .to_bytes()
+                .unwrap();
+            let frame = [0x90, 0x50, 0x00, 0x00, set[6], set[7], 0xFF];
+            assert!(matches!(
+                parse_visca_response(&frame, &ViscaResponseType::ExposureCompensationPosition),
+                Ok(ViscaResponse::InquiryResponse(
+                    ViscaInquiryResponse::ExposureCompensation { value: decoded }
+                )) if decoded == value
+            ));
+        }
+    }
+
+    #[test]
+    fn error_frames_carry_the_wire_code() {
+        let socket = Socket::new(2).unwrap();
+        assert_eq!(
+            build_error_response(socket, &ViscaError::CommandBufferFull),
+            [0x90, 0x62, 0x03, 0xFF]
+        );
+        assert_eq!(
+            build_error_response(socket, &ViscaError::InvalidResponseFormat),
+            [0x90, 0x62, 0x41, 0xFF]
+        );
+        let frame = build_error_response(Socket::new(1).unwrap(), &ViscaError::SyntaxError);
+        assert!(matches!(
+            parse_status_response(&frame),
+            Err(ViscaError::SyntaxError)
+        ));
+    }
+
+    fn round_trip_samples() -> Vec<(ViscaInquiryResponse, ViscaResponseType)> {
+        use ViscaInquiryResponse as R;
+        use ViscaResponseType as T;
+
+        vec![
+            (
+                R::PanTiltPosition {
+                    pan: -1234,
+                    tilt: 567,
+                },
+                T::PanTiltPosition,
+            ),
+            (R::Luminance(0x0A), T::Luminance),
+            (R::Contrast(0x07), T::Contrast),
+            (R::ZoomPosition { position: 0x4000 }, T::ZoomPosition),
+            (
+                R::FocusPosition {
+                    position: 0x1234,
+                    tracking: None,
+                },
+                T::FocusPosition,
+            ),
+            (
+                R::FocusPosition {
+                    position: 0x1234,
+                    tracking: Some(true),
+                },
+                T::FocusPosition,
+            ),
+            (R::Gain { gain: 0x05 }, T::Gain),
+            (R::Iris { iris: 0x0C }, T::Iris),
+            (R::Shutter { shutter: 0x11 }, T::Shutter),
+            (
+                R::WhiteBalance {
+                    mode: WhiteBalanceMode::Outdoor,
+                },
+                T::WhiteBalanceMode,
+            ),
+            (
+                R::ExposureMode {
+                    mode: ExposureMode::Iris,
+                },
+                T::ExposureMode,
+            ),
+            (
+                R::ExposureCompensation { value: -7 },
+                T::ExposureCompensationPosition,
+            ),
+            (R::Backlight { status: true }, T::Backlight),
+            (
+                R::ColorTemperature { temperature: 0x37 },
+                T::ColorTemperature,
+            ),
+            (R::Hue { hue: 0x0E }, T::Hue),
+            (R::VideoTemplate { template: 0x02 }, T::VideoTemplate),
+            (
+                R::Power {
+                    power: Power::Standby,
+                },
+                T::Power,
+            ),
+            (R::LensType { lens: 0x01 }, T::LensType),
+            (R::KneePoint { point: 0x0C }, T::KneePoint),
+            (R::KneeSlope { slope: 0x0E }, T::KneeSlope),
+            (R::BlackLevel { level: 0x30 }, T::BlackLevel),
+            (
+                R::FocusMode {
+                    mode: FocusMode::Manual,
+                },
+                T::FocusMode,
+            ),
+            (
+                R::AntiFlicker {
+                    mode: AntiFlickerMode::Hz50,
+                },
+                T::AntiFlicker,
+            ),
+            (R::HorizontalFlip { enabled: true }, T::HorizontalFlip),
+            (R::VerticalFlip { enabled: false }, T::VerticalFlip),
+            (
+                R::DateTime {
+                    year: 2024,
+                    month: 12,
+                    day: 31,
+                    hour: 23,
+                    minute: 59,
+                    second: 58,
+                },
+                T::DateTime,
+            ),
+            (R::UsbAudio { enabled: true }, T::UsbAudio),
+            (R::Display { enabled: false }, T::Display),
+            (R::MenuOpen { open: true }, T::MenuOpenClose),
+            (
+                R::Rtmp {
+                    stream1: false,
+                    stream2: true,
+                },
+                T::Rtmp,
+            ),
+            (R::AfSensitivity { low: true }, T::AutoFocusSensitivity),
+            (R::SharpnessMode { auto: false }, T::SharpnessMode),
+            (R::MotionSync { enabled: true }, T::MotionSyncMode),
+            (R::MotionSyncSpeed { speed: 0x09 }, T::MotionSyncSpeed),
+            (R::LastPreset { number: 0x2A }, T::LastPreset),
+            (R::AeLevel { value: 0x0F }, T::AeLevel),
+            (R::SharpnessPosition { value: 0x0B }, T::SharpnessPosition),
+            (
+                R::ImageFlip {
+                    horizontal: true,
+                    vertical: false,
+                },
+                T::ImageFlip,
+            ),
+            (
+                R::BlockColorExposure {
+                    r_gain: 0xA5,
+                    b_gain: 0x3C,
+                    wb_mode: WhiteBalanceMode::Manual,
+                    saturation: 0x0B,
+                    exposure_mode: ExposureMode::Shutter,
+                    backlight: true,
+                    exposure_compensation_on: false,
+                    shutter: 0x11,
+                    iris: 0x0C,
+                    luminance: 0x0E,
+                    exposure_compensation: 0x07,
+                },
+                T::BlockColorExposure,
+            ),
+            (
+                R::BlockImage {
+                    af_sensitivity: 0x02,
+                    picture_flip: true,
+                    color_gain: 0x0E,
+                    horizontal_flip: false,
+                    vertical_flip: true,
+                    nr_2d_level: 0x05,
+                    gain_limit: 0x0A,
+                },
+                T::BlockImage,
+            ),
+            (
+                R::Version {
+                    vendor_id: 0x0109,
+                    model_id: 0x0A20,
+                    rom_version: 0x0123,
+                    max_socket: 0x02,
+                },
+                T::Version,
+            ),
+        ]
+    }
+
+    #[test]
+    fn expected_len_follows_the_documented_replies() {
+        for (response_type, len) in [
+            (ViscaResponseType::PanTiltPosition, 11),
+            (ViscaResponseType::ZoomPosition, 7),
+            (ViscaResponseType::FocusPosition, 7),
+            (ViscaResponseType::Luminance, 7),
+            (ViscaResponseType::Power, 4),
+            (ViscaResponseType::WhiteBalanceMode, 4),
+            (ViscaResponseType::SharpnessMode, 4),
+            (ViscaResponseType::DateTime, 9),
+            (ViscaResponseType::Version, 10),
+        ] {
+            assert_eq!(
+                response_type.expected_len(),
+                Some(len),
+                "{:?}",
+                response_type
+            );
+        }
+        for response_type in [
+            ViscaResponseType::BlockColorExposure,
+            ViscaResponseType::BlockImage,
+        ] {
+            assert_eq!(response_type.expected_len(), None, "{:?}", response_type);
+        }
+    }
+
+    #[test]
+    fn inquiry_frames_parse_back_to_the_same_response() {
+        for (response, response_type) in round_trip_samples() {
+            let frame = inquiry_frame(&response).unwrap();
+            if let Some(expected) = response_type.expected_len() {
+                if !matches!(
+                    response,
+                    ViscaInquiryResponse::FocusPosition {
+                        tracking: Some(_),
+                        ..
+                    }
+                ) {
+                    assert_eq!(frame.len(), expected, "{:?}", response);
+                }
+            }
+            match parse_visca_response(&frame, &response_type) {
+                Ok(ViscaResponse::InquiryResponse(parsed)) => assert_eq!(parsed, response),
+                other => panic!("{:?} parsed as {:?}", response, other),
+            }
+        }
+    }
+
+    #[test]
+    fn ack_and_completion_frames_parse_back() {
+        let socket = Socket::new(2).unwrap();
+        assert_eq!(ack_frame(socket), [0x90, 0x42, 0xFF]);
+        assert_eq!(completion_frame(socket), [0x90, 0x52, 0xFF]);
+        assert!(matches!(
+            parse_status_response(&ack_frame(socket)),
+            Ok(ViscaResponse::Ack(s)) if s == socket
+        ));
+        assert!(matches!(
+            parse_status_response(&completion_frame(socket)),
+            Ok(ViscaResponse::Completion(s)) if s == socket
+        ));
+    }
+
+    #[test]
+    fn payload_without_a_decoder_is_not_a_completion() {
+        assert!(matches!(
+            parse_visca_response(&[0x90, 0x50, 0x02, 0xFF], &ViscaResponseType::FocusZone),
+            Err(ViscaError::UnexpectedResponseType)
+        ));
+        assert!(matches!(
+            parse_visca_response(&[0x90, 0x51, 0xFF], &ViscaResponseType::FocusZone),
+            Ok(ViscaResponse::Completion(_))
+        ));
+    }
+
+    #[test]
+    fn pan_tilt_position_parses_standard_and_extended_frames() {
+        // pan = -2 (FFFE), tilt = 0x0123
+        let standard = [
+            0x90, 0x50, 0x0F, 0x0F, 0x0F, 0x0E, 0x00, 0x01, 0x02, 0x03, 0xFF,
+        ];
+        let mut extended = standard[..10].to_vec();
+        extended.extend_from_slice(&[0x00, 0x01, 0xFF]);
+
+        for frame in [&standard[..], &extended[..]] {
+            assert!(
+                matches!(
+                    parse_visca_response(frame, &ViscaResponseType::PanTiltPosition),
+                    Ok(ViscaResponse::InquiryResponse(
+                        ViscaInquiryResponse::PanTiltPosition {
+                            pan: -2,
+                            tilt: 0x0123
+                        }
+                    ))
+                ),
+                "{:02X?}",
+                frame
+            );
+        }
+    }
+
+    #[test]
+    fn short_pan_tilt_position_names_both_lengths() {
+        let frame = [0x90, 0x50, 0x0F, 0x0F, 0x0F, 0x0E, 0x00, 0x01, 0xFF];
+        assert!(matches!(
+            parse_visca_response(&frame, &ViscaResponseType::PanTiltPosition),
+            Err(ViscaError::InvalidResponseLength {
+                expected: 11,
+                actual: 9
+            })
+        ));
+    }
+
+    #[test]
+    fn network_change_is_recognised_by_both_parsers() {
+        let frame = [0x90, 0x38, 0xFF];
+        assert!(matches!(
+            parse_status_response(&frame),
+            Ok(ViscaResponse::NetworkChange)
+        ));
+        for response_type in ALL_RESPONSE_TYPES {
+            assert!(
+                matches!(
+                    parse_visca_response(&frame, response_type),
+                    Ok(ViscaResponse::NetworkChange)
+                ),
+                "{:?}",
+                response_type
+            );
+        }
+        assert!(ViscaResponse::NetworkChange.is_unsolicited());
+        assert!(!ViscaResponse::Ack(Socket::new(1).unwrap()).is_unsolicited());
+    }
+
+    #[test]
+    fn block_color_exposure_decodes_every_field() {
+        // Manual WB and exposure, backlight and exposure compensation on.
+        let frame = [
+            0x90, 0x50, 0x0A, 0x01, 0x0C, 0x08, 0x05, 0x04, 0x03, 0x06, 0x11, 0x0A, 0x00, 0x0D,
+            0x07, 0xFF,
+        ];
+        assert!(matches!(
+            parse_visca_response(&frame, &ViscaResponseType::BlockColorExposure),
+            Ok(ViscaResponse::InquiryResponse(response)) if response
+                == ViscaInquiryResponse::BlockColorExposure {
+                    r_gain: 0xA1,
+                    b_gain: 0xC8,
+                    wb_mode: WhiteBalanceMode::Manual,
+                    saturation: 0x04,
+                    exposure_mode: ExposureMode::Manual,
+                    backlight: true,
+                    exposure_compensation_on: true,
+                    shutter: 0x11,
+                    iris: 0x0A,
+                    luminance: 0x0D,
+                    exposure_compensation: 0x07,
+                }
+        ));
+
+        let mut unknown_wb_mode = frame;
+        unknown_wb_mode[6] = 0x0F;
+        assert!(
+            parse_visca_response(&unknown_wb_mode, &ViscaResponseType::BlockColorExposure).is_err()
+        );
+        assert!(parse_visca_response(&frame[1..], &ViscaResponseType::BlockColorExposure).is_err());
+    }
+
+    #[test]
+    fn block_image_decodes_every_field() {
+        // Low AF sensitivity, picture flip, color gain 0x0B, both flips.
+        let frame = [
+            0x90, 0x50, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0x01, 0x58, 0x03, 0x05,
+            0x04, 0xFF,
+        ];
+        assert!(matches!(
+            parse_visca_response(&frame, &ViscaResponseType::BlockImage),
+            Ok(ViscaResponse::InquiryResponse(response)) if response
+                == ViscaInquiryResponse::BlockImage {
+                    af_sensitivity: 0x03,
+                    picture_flip: true,
+                    color_gain: 0x0B,
+                    horizontal_flip: true,
+                    vertical_flip: true,
+                    nr_2d_level: 0x05,
+                    gain_limit: 0x04,
+                }
+        ));
+
+        let mut unknown_flip = frame;
+        unknown_flip[12] = 0x04;
+        assert!(parse_visca_response(&unknown_flip, &ViscaResponseType::BlockImage).is_err());
+        let short = [&frame[..2], &frame[3..]].concat();
+        assert!(matches!(
+            parse_visca_response(&short, &ViscaResponseType::BlockImage),
+            Err(ViscaError::InvalidResponseLength {
+                expected: 16,
+                actual: 15
+            })
+        ));
+    }
+
+    #[test]
+    fn af_sensitivity_has_its_own_opcode_and_decodes() {
+        use crate::command::{InquiryCommand, ViscaCommand};
+
+        let inquiry = InquiryCommand::AutoFocusSensitivity;
+        assert_eq!(inquiry.to_bytes().unwrap(), [0x81, 0x09, 0x04, 0x58, 0xFF]);
+        assert_eq!(
+            inquiry.response_type(),
+            Some(ViscaResponseType::AutoFocusSensitivity)
+        );
+        for other in [
+            InquiryCommand::FocusMode,
+            InquiryCommand::WhiteBalanceMode,
+            InquiryCommand::MotionSync,
+        ] {
+            assert_ne!(other.to_bytes().unwrap(), inquiry.to_bytes().unwrap());
+        }
+
+        // High, normal and low.
+        for (byte, low) in [(0x01, false), (0x02, false), (0x03, true)] {
+            assert!(matches!(
+                parse_visca_response(&[0x90, 0x50, byte, 0xFF], &ViscaResponseType::AutoFocusSensitivity),
+                Ok(ViscaResponse::InquiryResponse(ViscaInquiryResponse::AfSensitivity { low: l }))
+                    if l == low
+            ));
+        }
+        for byte in [0x00, 0x04] {
+            assert!(parse_visca_response(
+                &[0x90, 0x50, byte, 0xFF],
+                &ViscaResponseType::AutoFocusSensitivity
+            )
+            .is_err());
+        }
+    }
+
+    #[test]
+    fn sockets_are_validated() {
+        for socket in 0..=Socket::MAX {
+            assert_eq!(Socket::new(socket).unwrap().get(), socket);
+        }
+        for socket in [Socket::MAX + 1, 0x20, u8::MAX] {
+            assert!(matches!(
+                Socket::new(socket),
+                Err(ViscaError::ParameterOutOfRange { max: 15, .. })
+            ));
+        }
+        assert_eq!(Socket::new(2).unwrap().to_string(), "2");
+        assert!(matches!(
+            parse_status_response(&[0x90, 0x52, 0xFF]),
+            Ok(ViscaResponse::Completion(socket)) if socket == Socket::new(2).unwrap()
+        ));
+    }
+
+    /// The position and tracking status decoded from a focus position reply.
+    fn focus_position(frame: &[u8]) -> (u16, Option<bool>) {
+        match parse_visca_response(frame, &ViscaResponseType::FocusPosition) {
+            Ok(ViscaResponse::InquiryResponse(ViscaInquiryResponse::FocusPosition {
+                position,
+                tracking,
+            })) => (position, tracking),
+            other => panic!("expected a focus position, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn focus_position_with_and_without_tracking_status() {
+        let plain = [0x90, 0x50, 0x01, 0x02, 0x03, 0x04, 0xFF];
+        assert_eq!(focus_position(&plain), (0x1234, None));
+
+        for (status, tracking) in [(0x02, Some(true)), (0x03, Some(false)), (0x07, None)] {
+            let extended = [0x90, 0x50, 0x01, 0x02, 0x03, 0x04, status, 0xFF];
+            assert_eq!(
+                focus_position(&extended),
+                (0x1234, tracking),
+                "{:02X}",
+                status
+            );
+        }
+
+        // More trailing bytes than the documented status are ignored.
+        let trailing = [0x90, 0x50, 0x01, 0x02, 0x03, 0x04, 0x02, 0x00, 0xFF];
+        assert_eq!(focus_position(&trailing), (0x1234, None));
+
+        assert!(matches!(
+            parse_visca_response(
+                &[0x90, 0x50, 0x01, 0x02, 0x03, 0xFF],
+                &ViscaResponseType::FocusPosition
+            ),
+            Err(ViscaError::InvalidResponseLength {
+                expected: 7,
+                actual: 6
+            })
+        ));
+    }
+
+    #[test]
+    fn image_flip_decodes_all_four_combinations() {
+        use crate::command::{InquiryCommand, ViscaCommand};
+
+        // 04 68 is focus lock.
+        assert_eq!(
+            InquiryCommand::ImageFlip.to_bytes().unwrap(),
+            [0x81, 0x09, 0x04, 0xA4, 0xFF]
+        );
+        for (state, horizontal, vertical) in [
+            (0x00, false, false),
+            (0x01, true, false),
+            (0x02, false, true),
+            (0x03, true, true),
+        ] {
+            assert!(matches!(
+                parse_visca_response(&[0x90, 0x50, state, 0xFF], &ViscaResponseType::ImageFlip),
+                Ok(ViscaResponse::InquiryResponse(response)) if response
+                    == ViscaInquiryResponse::ImageFlip { horizontal, vertical }
+            ));
+        }
+        assert!(
+            parse_visca_response(&[0x90, 0x50, 0x04, 0xFF], &ViscaResponseType::ImageFlip).is_err()
+        );
+    }
+
+    #[test]
+    fn stray_high_bits_in_positions_are_masked() {
+        let noisy = [0x90, 0x50, 0xF1, 0xA2, 0x03, 0x24, 0xFF];
+        assert!(matches!(
+            parse_visca_response(&noisy, &ViscaResponseType::ZoomPosition),
+            Ok(ViscaResponse::InquiryResponse(
+                ViscaInquiryResponse::ZoomPosition { position: 0x1234 }
+            ))
+        ));
+        assert!(matches!(
+            parse_visca_response(&noisy, &ViscaResponseType::FocusPosition),
+            Ok(ViscaResponse::InquiryResponse(
+                ViscaInquiryResponse::FocusPosition {
+                    position: 0x1234,
+                    tracking: None,
+                }
+            ))
+        ));
+    }
+
+    #[test]
+    fn replies_decode_the_same_from_any_address() {
+        for (address, header) in [(1, 0x90), (3, 0xB0), (7, 0xF0)] {
+            assert_eq!(reply_address(header), Some(address));
+            assert!(matches!(
+                parse_status_response(&[header, 0x41, 0xFF]),
+                Ok(ViscaResponse::Ack(socket)) if socket == Socket::new(1).unwrap()
+            ));
+            assert!(matches!(
+                parse_status_response(&[header, 0x52, 0xFF]),
+                Ok(ViscaResponse::Completion(socket)) if socket == Socket::new(2).unwrap()
+            ));
+            assert!(matches!(
+                parse_visca_response(
+                    &[header, 0x50, 0x01, 0x02, 0x03, 0x04, 0xFF],
+                    &ViscaResponseType::ZoomPosition
+                ),
+                Ok(ViscaResponse::InquiryResponse(
+                    ViscaInquiryResponse::ZoomPosition { position: 0x1234 }
+                ))
+            ));
+        }
+        for header in [0x80, 0x91, 0x88] {
+            assert_eq!(reply_address(header), None);
+            assert!(parse_status_response(&[header, 0x41, 0xFF]).is_err());
+        }
+    }
+}
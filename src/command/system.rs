@@ -0,0 +1,490 @@
+use crate::command::ViscaCommand;
+use crate::error::{RangeFormat, ViscaError};
+
+use super::{flip::Flip, response::Socket, ViscaResponseType};
+
+/// Video template ids accepted by [`VideoTemplateCommand`], from the Video
+/// Template Direct row of docs/PTZOptics-G2-VISCA-over-IP-Command-List.pdf.
+/// The list documents no id-to-resolution mapping, so the output format
+/// itself still has to be chosen through the camera's web UI.
+const VIDEO_TEMPLATES: [(u8, &str); 4] = [
+    (0x01, "High"),
+    (0x02, "Medium"),
+    (0x03, "Low"),
+    (0x04, "Off"),
+];
+
+/// Looks up the description of a video template id.
+pub fn video_template_name(id: u8) -> Option<&'static str> {
+    VIDEO_TEMPLATES
+        .iter()
+        .find(|(template, _)| *template == id)
+        .map(|&(_, name)| name)
+}
+
+/// Selects a video template: `81 0B 01 0p FF`.
+pub struct VideoTemplateCommand {
+    pub template: u8,
+}
+
+impl ViscaCommand for VideoTemplateCommand {
+    fn to_bytes(&self) -> Result<Vec<u8>, ViscaError> {
+        if video_template_name(self.template).is_some() {
+            Ok(vec![0x81, 0x0B, 0x01, self.template, 0xFF])
+        } else {
+            Err(
+                ViscaError::out_of_range("Video template", self.template, 1, 4)
+                    .with_range_format(RangeFormat::Hex),
+            )
+        }
+    }
+
+    fn response_type(&self) -> Option<ViscaResponseType> {
+        None
+    }
+}
+
+/// Lens ids accepted by [`LensTypeCommand`], from the Lens Type row of
+/// docs/PTZOptics-G2-VISCA-over-IP-Command-List.pdf.
+const LENS_TYPES: [(u8, &str); 2] = [(0x00, "Type 1"), (0x01, "Type 2")];
+
+/// Looks up the description of a lens id.
+pub fn lens_type_name(id: u8) -> Option<&'static str> {
+    LENS_TYPES
+        .iter()
+        .find(|(lens, _)| *lens == id)
+        .map(|&(_, name)| name)
+}
+
+/// Declares the mounted lens on interchangeable-lens cameras.
+pub struct LensTypeCommand {
+    pub lens: u8,
+}
+
+impl ViscaCommand for LensTypeCommand {
+    fn to_bytes(&self) -> Result<Vec<u8>, ViscaError> {
+        if lens_type_name(self.lens).is_some() {
+            Ok(vec![0x81, 0x0A, 0x01, 0x04, 0x1B, self.lens, 0xFF])
+        } else {
+            Err(ViscaError::out_of_range("Lens type", self.lens, 0, 1)
+                .with_range_format(RangeFormat::Hex))
+        }
+    }
+
+    fn response_type(&self) -> Option<ViscaResponseType> {
+        None
+    }
+}
+
+/// Sets the camera clock shown in the on-screen timestamp.
+///
+/// Each field is sent as one BCD byte, with the year as its offset from
+/// 2000: `81 01 04 70 yy MM dd hh mm ss FF`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct DateTimeCommand {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+}
+
+impl DateTimeCommand {
+    /// The six BCD bytes carried by both the command and the inquiry reply.
+    pub(crate) fn bcd_fields(&self) -> Result<[u8; 6], ViscaError> {
+        if !(2000..=2099).contains(&self.year) {
+            return Err(ViscaError::out_of_range("Year", self.year, 2000, 2099));
+        }
+        if !(1..=12).contains(&self.month) {
+            return Err(ViscaError::out_of_range("Month", self.month, 1, 12));
+        }
+        let days = days_in_month(self.year, self.month);
+        if !(1..=days).contains(&self.day) {
+            return Err(ViscaError::out_of_range("Day", self.day, 1, days.into()));
+        }
+        if self.hour > 23 {
+            return Err(ViscaError::out_of_range("Hour", self.hour, 0, 23));
+        }
+        if self.minute > 59 {
+            return Err(ViscaError::out_of_range("Minute", self.minute, 0, 59));
+        }
+        if self.second > 59 {
+            return Err(ViscaError::out_of_range("Second", self.second, 0, 59));
+        }
+
+        Ok([
+            to_bcd((self.year - 2000) as u8),
+            to_bcd(self.month),
+            to_bcd(self.day),
+            to_bcd(self.hour),
+            to_bcd(self.minute),
+            to_bcd(self.second),
+        ])
+    }
+
+    /// Inverse of [`bcd_fields`](Self::bcd_fields).
+    pub(crate) fn from_bcd_fields(fields: &[u8]) -> Result<Self, ViscaError> {
+        let [year, month, day, hour, minute, second] = fields else {
+            return Err(ViscaError::InvalidResponseLength {
+                expected: 6,
+                actual: fields.len(),
+            });
+        };
+        let decode = |byte: &u8| from_bcd(*byte).ok_or(ViscaError::InvalidResponseFormat);
+        let date_time = DateTimeCommand {
+            year: 2000 + u16::from(decode(year)?),
+            month: decode(month)?,
+            day: decode(day)?,
+            hour: decode(hour)?,
+            minute: decode(minute)?,
+            second: decode(second)?,
+        };
+        date_time.bcd_fields()?;
+        Ok(date_time)
+    }
+}
+
+impl ViscaCommand for DateTimeCommand {
+    fn to_bytes(&self) -> Result<Vec<u8>, ViscaError> {
+        let mut bytes = vec![0x81, 0x01, 0x04, 0x70];
+        bytes.extend_from_slice(&self.bcd_fields()?);
+        bytes.push(0xFF);
+        Ok(bytes)
+    }
+
+    fn response_type(&self) -> Option<ViscaResponseType> {
+        None
+    }
+}
+
+fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        2 if year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400)) => {
+            29
+        }
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Packs a value below 100 into two BCD digits.
+fn to_bcd(value: u8) -> u8 {
+    ((value / 10) << 4) | (value % 10)
+}
+
+/// Unpacks two BCD digits, rejecting nibbles above 9.
+fn from_bcd(byte: u8) -> Option<u8> {
+    let (tens, ones) = (byte >> 4, byte & 0x0F);
+    (tens <= 9 && ones <= 9).then_some(tens * 10 + ones)
+}
+
+/// Clears the camera's command buffers, cancelling anything still queued
+/// (`81 01 00 01 FF`). The camera answers with a completion only, see
+/// [`CameraModel::reply_pattern`](crate::CameraModel::reply_pattern).
+pub struct IfClearCommand;
+
+impl ViscaCommand for IfClearCommand {
+    fn to_bytes(&self) -> Result<Vec<u8>, ViscaError> {
+        Ok(vec![0x81, 0x01, 0x00, 0x01, 0xFF])
+    }
+
+    fn response_type(&self) -> Option<ViscaResponseType> {
+        None
+    }
+}
+
+/// Cancels the command running in `socket` (`81 2p FF`). The camera
+/// answers with a `CommandCanceled` error on that socket, or `NoSocket` if
+/// nothing was running there, so a successful cancel sent with
+/// [`crate::send_command_and_wait`] returns `Err(CommandCanceled)`.
+pub struct CancelCommand {
+    pub socket: Socket,
+}
+
+impl ViscaCommand for CancelCommand {
+    fn to_bytes(&self) -> Result<Vec<u8>, ViscaError> {
+        Ok(vec![0x81, 0x20 | self.socket.get(), 0xFF])
+    }
+
+    fn response_type(&self) -> Option<ViscaResponseType> {
+        None
+    }
+}
+
+/// Enables or disables audio over the USB output
+/// (`81 2A 02 A0 04 0p FF`).
+pub struct UsbAudioCommand {
+    pub enabled: bool,
+}
+
+impl ViscaCommand for UsbAudioCommand {
+    fn to_bytes(&self) -> Result<Vec<u8>, ViscaError> {
+        Ok(vec![
+            0x81,
+            0x2A,
+            0x02,
+            0xA0,
+            0x04,
+            Flip::from(self.enabled) as u8,
+            0xFF,
+        ])
+    }
+
+    fn response_type(&self) -> Option<ViscaResponseType> {
+        None
+    }
+}
+
+/// Starts or stops RTMP stream 1 or 2 (`81 0A 11 A8 sp FF`, `s` the
+/// stream). The stream URL and key are not exposed over VISCA, so they
+/// have to be provisioned through the camera's HTTP API first.
+pub struct RtmpCommand {
+    pub stream: u8,
+    pub enabled: bool,
+}
+
+impl ViscaCommand for RtmpCommand {
+    fn to_bytes(&self) -> Result<Vec<u8>, ViscaError> {
+        if !(1..=2).contains(&self.stream) {
+            return Err(ViscaError::out_of_range("RTMP stream", self.stream, 1, 2));
+        }
+        Ok(vec![
+            0x81,
+            0x0A,
+            0x11,
+            0xA8,
+            (self.stream << 4) | Flip::from(self.enabled) as u8,
+            0xFF,
+        ])
+    }
+
+    fn response_type(&self) -> Option<ViscaResponseType> {
+        None
+    }
+}
+
+/// Shows or hides the on-screen display of camera status
+/// (`81 01 04 15 0p FF`).
+pub struct DisplayCommand {
+    pub enabled: bool,
+}
+
+impl ViscaCommand for DisplayCommand {
+    fn to_bytes(&self) -> Result<Vec<u8>, ViscaError> {
+        Ok(vec![
+            0x81,
+            0x01,
+            0x04,
+            0x15,
+            Flip::from(self.enabled) as u8,
+            0xFF,
+        ])
+    }
+
+    fn response_type(&self) -> Option<ViscaResponseType> {
+        None
+    }
+}
+
+/// Shows or hides the title overlay, typically the camera name
+/// (`81 01 04 74 2F FF` on, `81 01 04 74 3F FF` off). The camera has no
+/// inquiry for it.
+pub struct TitleDisplayCommand {
+    pub enabled: bool,
+}
+
+impl ViscaCommand for TitleDisplayCommand {
+    fn to_bytes(&self) -> Result<Vec<u8>, ViscaError> {
+        let state = if self.enabled { 0x2F } else { 0x3F };
+        Ok(vec![0x81, 0x01, 0x04, 0x74, state, 0xFF])
+    }
+
+    fn response_type(&self) -> Option<ViscaResponseType> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn video_template_frames_cover_the_documented_ids() {
+        for (id, _) in VIDEO_TEMPLATES {
+            assert_eq!(
+                VideoTemplateCommand { template: id }.to_bytes().unwrap(),
+                [0x81, 0x0B, 0x01, id, 0xFF]
+            );
+        }
+        assert_eq!(video_template_name(0x02), Some("Medium"));
+        for id in [0x00, 0x05] {
+            assert!(VideoTemplateCommand { template: id }.to_bytes().is_err());
+            assert_eq!(video_template_name(id), None);
+        }
+    }
+
+    #[test]
+    fn lens_type_frames_cover_the_documented_ids() {
+        for (id, _) in LENS_TYPES {
+            assert_eq!(
+                LensTypeCommand { lens: id }.to_bytes().unwrap(),
+                [0x81, 0x0A, 0x01, 0x04, 0x1B, id, 0xFF]
+            );
+        }
+        assert_eq!(lens_type_name(0x01), Some("Type 2"));
+        assert!(LensTypeCommand { lens: 0x02 }.to_bytes().is_err());
+        assert_eq!(lens_type_name(0x02), None);
+    }
+
+    fn date_time(
+        year: u16,
+        month: u8,
+        day: u8,
+        hour: u8,
+        minute: u8,
+        second: u8,
+    ) -> DateTimeCommand {
+        DateTimeCommand {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+        }
+    }
+
+    #[test]
+    fn date_time_encodes_each_field_as_bcd() {
+        // Month 12 and hour 23 must come out as 0x12 and 0x23, not 0x0C and 0x17.
+        assert_eq!(
+            date_time(2024, 12, 31, 23, 59, 59).to_bytes().unwrap(),
+            [0x81, 0x01, 0x04, 0x70, 0x24, 0x12, 0x31, 0x23, 0x59, 0x59, 0xFF]
+        );
+        assert_eq!(
+            date_time(2000, 1, 1, 0, 0, 0).to_bytes().unwrap(),
+            [0x81, 0x01, 0x04, 0x70, 0x00, 0x01, 0x01, 0x00, 0x00, 0x00, 0xFF]
+        );
+    }
+
+    #[test]
+    fn date_time_rejects_out_of_range_fields() {
+        for invalid in [
+            date_time(1999, 12, 31, 23, 59, 59),
+            date_time(2100, 1, 1, 0, 0, 0),
+            date_time(2024, 13, 1, 0, 0, 0),
+            date_time(2024, 0, 1, 0, 0, 0),
+            date_time(2024, 12, 32, 0, 0, 0),
+            date_time(2024, 4, 31, 0, 0, 0),
+            date_time(2023, 2, 29, 0, 0, 0),
+            date_time(2024, 12, 31, 24, 0, 0),
+            date_time(2024, 12, 31, 23, 60, 0),
+            date_time(2024, 12, 31, 23, 59, 60),
+        ] {
+            assert!(invalid.to_bytes().is_err(), "{:?}", invalid);
+        }
+        assert!(date_time(2024, 2, 29, 0, 0, 0).to_bytes().is_ok());
+    }
+
+    #[test]
+    fn date_time_fields_decode_back() {
+        let set = date_time(2024, 12, 31, 23, 59, 59);
+        assert_eq!(
+            DateTimeCommand::from_bcd_fields(&set.bcd_fields().unwrap()).unwrap(),
+            set
+        );
+        // 0x1A is not a BCD digit pair, and 0x13 is no month.
+        assert!(DateTimeCommand::from_bcd_fields(&[0x24, 0x1A, 0x01, 0, 0, 0]).is_err());
+        assert!(DateTimeCommand::from_bcd_fields(&[0x24, 0x13, 0x01, 0, 0, 0]).is_err());
+        assert!(DateTimeCommand::from_bcd_fields(&[0x24, 0x12]).is_err());
+    }
+
+    #[test]
+    fn usb_audio_frames_and_reply_for_both_states() {
+        use crate::command::{response::parse_visca_response, InquiryCommand};
+        use crate::{ViscaInquiryResponse, ViscaResponse};
+
+        assert_eq!(
+            InquiryCommand::UsbAudio.to_bytes().unwrap(),
+            [0x81, 0x2A, 0x02, 0xA0, 0x04, 0xFF]
+        );
+        for (enabled, state) in [(true, 0x02), (false, 0x03)] {
+            assert_eq!(
+                UsbAudioCommand { enabled }.to_bytes().unwrap(),
+                [0x81, 0x2A, 0x02, 0xA0, 0x04, state, 0xFF]
+            );
+            assert!(matches!(
+                parse_visca_response(&[0x90, 0x50, state, 0xFF], &ViscaResponseType::UsbAudio),
+                Ok(ViscaResponse::InquiryResponse(ViscaInquiryResponse::UsbAudio { enabled: e }))
+                    if e == enabled
+            ));
+        }
+    }
+
+    #[test]
+    fn rtmp_frames_and_reply_for_each_stream() {
+        use crate::command::{response::parse_visca_response, InquiryCommand};
+        use crate::{ViscaInquiryResponse, ViscaResponse};
+
+        for (stream, enabled, byte) in [(1, true, 0x12), (1, false, 0x13), (2, true, 0x22)] {
+            assert_eq!(
+                RtmpCommand { stream, enabled }.to_bytes().unwrap(),
+                [0x81, 0x0A, 0x11, 0xA8, byte, 0xFF]
+            );
+        }
+        for stream in [0, 3] {
+            assert!(RtmpCommand {
+                stream,
+                enabled: true
+            }
+            .to_bytes()
+            .is_err());
+        }
+
+        assert_eq!(
+            InquiryCommand::Rtmp.to_bytes().unwrap(),
+            [0x81, 0x09, 0x11, 0x53, 0xFF]
+        );
+        for (state, one, two) in [
+            (0x00, false, false),
+            (0x01, true, false),
+            (0x02, false, true),
+            (0x03, true, true),
+        ] {
+            assert!(matches!(
+                parse_visca_response(&[0x90, 0x50, state, 0xFF], &ViscaResponseType::Rtmp),
+                Ok(ViscaResponse::InquiryResponse(ViscaInquiryResponse::Rtmp { stream1, stream2 }))
+                    if stream1 == one && stream2 == two
+            ));
+        }
+        assert!(parse_visca_response(&[0x90, 0x50, 0x04, 0xFF], &ViscaResponseType::Rtmp).is_err());
+    }
+
+    #[test]
+    fn title_display_frames_for_both_states() {
+        assert_eq!(
+            TitleDisplayCommand { enabled: true }.to_bytes().unwrap(),
+            [0x81, 0x01, 0x04, 0x74, 0x2F, 0xFF]
+        );
+        assert_eq!(
+            TitleDisplayCommand { enabled: false }.to_bytes().unwrap(),
+            [0x81, 0x01, 0x04, 0x74, 0x3F, 0xFF]
+        );
+    }
+
+    #[test]
+    fn cancel_targets_the_socket() {
+        for socket in [0, 1, 2, Socket::MAX] {
+            assert_eq!(
+                CancelCommand {
+                    socket: Socket::new(socket).unwrap()
+                }
+                .to_bytes()
+                .unwrap(),
+                [0x81, 0x20 | socket, 0xFF]
+            );
+        }
+    }
+}
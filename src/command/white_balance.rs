@@ -4,7 +4,7 @@ use std::convert::TryFrom;
 
 use super::ViscaResponseType;
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum WhiteBalanceMode {
     Auto = 0x00,
     Indoor = 0x01,
@@ -28,6 +28,38 @@ impl ViscaCommand for WhiteBalanceCommand {
     }
 }
 
+/// Nudges the color temperature, for use in
+/// [`WhiteBalanceMode::ColorTemperature`]; other modes reject it.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ColorTemperatureCommand {
+    Reset,
+    Up,
+    Down,
+}
+
+impl ViscaCommand for ColorTemperatureCommand {
+    fn to_bytes(&self) -> Result<Vec<u8>, ViscaError> {
+        let step = match self {
+            ColorTemperatureCommand::Reset => 0x00,
+            ColorTemperatureCommand::Up => 0x02,
+            ColorTemperatureCommand::Down => 0x03,
+        };
+        Ok(vec![0x81, 0x01, 0x04, 0x20, step, 0xFF])
+    }
+
+    fn response_type(&self) -> Option<ViscaResponseType> {
+        None
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            ColorTemperatureCommand::Reset => "ColorTemperatureReset",
+            ColorTemperatureCommand::Up => "ColorTemperatureUp",
+            ColorTemperatureCommand::Down => "ColorTemperatureDown",
+        }
+    }
+}
+
 impl TryFrom<u8> for WhiteBalanceMode {
     type Error = ();
 
@@ -43,3 +75,62 @@ impl TryFrom<u8> for WhiteBalanceMode {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn color_temperature_steps() {
+        for (step, byte) in [
+            (ColorTemperatureCommand::Reset, 0x00),
+            (ColorTemperatureCommand::Up, 0x02),
+            (ColorTemperatureCommand::Down, 0x03),
+        ] {
+            assert_eq!(
+                step.to_bytes().unwrap(),
+                [0x81, 0x01, 0x04, 0x20, byte, 0xFF]
+            );
+        }
+    }
+
+    #[test]
+    fn white_balance_mode_round_trips() {
+        for mode in [
+            WhiteBalanceMode::Auto,
+            WhiteBalanceMode::Indoor,
+            WhiteBalanceMode::Outdoor,
+            WhiteBalanceMode::OnePush,
+            WhiteBalanceMode::Manual,
+            WhiteBalanceMode::ColorTemperature,
+        ] {
+            assert_eq!(WhiteBalanceMode::try_from(mode as u8), Ok(mode));
+        }
+        assert!(WhiteBalanceMode::try_from(0x04).is_err());
+    }
+
+    #[test]
+    fn color_temperature_reply_is_a_single_position_byte() {
+        use crate::{command::response::parse_visca_response, ViscaInquiryResponse, ViscaResponse};
+
+        let parse =
+            |frame: &[u8]| parse_visca_response(frame, &ViscaResponseType::ColorTemperature);
+        assert_eq!(ViscaResponseType::ColorTemperature.expected_len(), Some(4));
+        for temperature in [0x00, 0x1A, ViscaInquiryResponse::MAX_COLOR_TEMPERATURE] {
+            assert!(matches!(
+                parse(&[0x90, 0x50, temperature, 0xFF]),
+                Ok(ViscaResponse::InquiryResponse(ViscaInquiryResponse::ColorTemperature {
+                    temperature: t
+                })) if t == temperature
+            ));
+        }
+        assert!(parse(&[0x90, 0x50, 0x38, 0xFF]).is_err());
+        assert!(matches!(
+            parse(&[0x90, 0x50, 0x00, 0x00, 0x03, 0x07, 0xFF]),
+            Err(ViscaError::InvalidResponseLength {
+                expected: 4,
+                actual: 7
+            })
+        ));
+    }
+}
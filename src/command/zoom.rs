@@ -1,7 +1,8 @@
 use crate::command::ViscaCommand;
 use crate::error::ViscaError;
+use crate::CameraModel;
 
-use super::ViscaResponseType;
+use super::{codec::encode_u16_nibbles, ViscaResponseType};
 
 #[derive(Debug)]
 pub enum ZoomCommand {
@@ -15,43 +16,153 @@ pub enum ZoomCommand {
 
 impl ViscaCommand for ZoomCommand {
     fn to_bytes(&self) -> Result<Vec<u8>, ViscaError> {
+        self.to_bytes_for(CameraModel::default())
+    }
+
+    fn response_type(&self) -> Option<ViscaResponseType> {
+        match self {
+            ZoomCommand::WideStandard => Some(ViscaResponseType::ZoomWideStandard),
+            ZoomCommand::TeleStandard => Some(ViscaResponseType::ZoomTeleStandard),
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            ZoomCommand::Stop => "ZoomStop",
+            ZoomCommand::TeleStandard => "ZoomTeleStandard",
+            ZoomCommand::WideStandard => "ZoomWideStandard",
+            ZoomCommand::TeleVariable(_) => "ZoomTeleVariable",
+            ZoomCommand::WideVariable(_) => "ZoomWideVariable",
+            ZoomCommand::Direct(_) => "ZoomDirect",
+        }
+    }
+
+    /// The variable speed range depends on the profile, see
+    /// [`CameraModel::max_zoom_speed`].
+    fn to_bytes_for(&self, model: CameraModel) -> Result<Vec<u8>, ViscaError> {
+        let max_speed = model.max_zoom_speed();
         match self {
             ZoomCommand::Stop => Ok(vec![0x81, 0x01, 0x04, 0x07, 0x00, 0xFF]),
             ZoomCommand::TeleStandard => Ok(vec![0x81, 0x01, 0x04, 0x07, 0x02, 0xFF]),
             ZoomCommand::WideStandard => Ok(vec![0x81, 0x01, 0x04, 0x07, 0x03, 0xFF]),
             ZoomCommand::TeleVariable(speed) => {
-                if *speed <= 7 {
+                if *speed <= max_speed {
                     Ok(vec![0x81, 0x01, 0x04, 0x07, 0x20 | speed, 0xFF])
                 } else {
-                    Err(ViscaError::InvalidParameter(
-                        "Zoom speed must be in the range 0..=7".into(),
+                    Err(ViscaError::out_of_range(
+                        "Zoom speed",
+                        *speed,
+                        0,
+                        max_speed.into(),
                     ))
                 }
             }
             ZoomCommand::WideVariable(speed) => {
-                if *speed <= 7 {
+                if *speed <= max_speed {
                     Ok(vec![0x81, 0x01, 0x04, 0x07, 0x30 | speed, 0xFF])
                 } else {
-                    Err(ViscaError::InvalidParameter(
-                        "Zoom speed must be in the range 0..=7".into(),
+                    Err(ViscaError::out_of_range(
+                        "Zoom speed",
+                        *speed,
+                        0,
+                        max_speed.into(),
                     ))
                 }
             }
             ZoomCommand::Direct(position) => {
-                let p = (*position >> 12) as u8;
-                let q = (*position >> 8) as u8;
-                let r = (*position >> 4) as u8;
-                let s = (*position & 0x0F) as u8;
+                let [p, q, r, s] = encode_u16_nibbles(*position);
                 Ok(vec![0x81, 0x01, 0x04, 0x47, p, q, r, s, 0xFF])
             }
         }
     }
+}
+
+/// Moves zoom and focus to absolute positions together
+/// (`81 01 04 47 0p 0q 0r 0s 0t 0u 0v 0w FF`), for coordinated rack moves.
+///
+/// Only profiles where [`CameraModel::supports_zoom_focus_direct`] holds can
+/// encode it; [`crate::Camera::zoom_focus_direct`] falls back to separate
+/// zoom and focus commands on the others.
+pub struct ZoomFocusDirectCommand {
+    pub zoom: u16,
+    pub focus: u16,
+}
+
+impl ViscaCommand for ZoomFocusDirectCommand {
+    fn to_bytes(&self) -> Result<Vec<u8>, ViscaError> {
+        self.to_bytes_for(CameraModel::Generic)
+    }
 
     fn response_type(&self) -> Option<ViscaResponseType> {
-        match self {
-            ZoomCommand::WideStandard => Some(ViscaResponseType::ZoomWideStandard),
-            ZoomCommand::TeleStandard => Some(ViscaResponseType::ZoomTeleStandard),
-            _ => None,
+        None
+    }
+
+    fn to_bytes_for(&self, model: CameraModel) -> Result<Vec<u8>, ViscaError> {
+        if !model.supports_zoom_focus_direct() {
+            return Err(ViscaError::InvalidParameter(format!(
+                "Zoom/focus direct is not supported by {:?}",
+                model
+            )));
+        }
+        let mut bytes = vec![0x81, 0x01, 0x04, 0x47];
+        bytes.extend_from_slice(&encode_u16_nibbles(self.zoom));
+        bytes.extend_from_slice(&encode_u16_nibbles(self.focus));
+        bytes.push(0xFF);
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zoom_focus_direct_packs_both_positions() {
+        let command = ZoomFocusDirectCommand {
+            zoom: 0x1234,
+            focus: 0xABCD,
+        };
+        assert_eq!(
+            command.to_bytes().unwrap(),
+            [0x81, 0x01, 0x04, 0x47, 0x01, 0x02, 0x03, 0x04, 0x0A, 0x0B, 0x0C, 0x0D, 0xFF]
+        );
+        assert!(matches!(
+            command.to_bytes_for(CameraModel::PtzOpticsG2),
+            Err(ViscaError::InvalidParameter(_))
+        ));
+    }
+
+    #[test]
+    fn variable_zoom_speed_boundaries_follow_the_profile() {
+        let tele = |speed, model| ZoomCommand::TeleVariable(speed).to_bytes_for(model);
+        let wide = |speed, model| ZoomCommand::WideVariable(speed).to_bytes_for(model);
+        for model in [CameraModel::PtzOpticsG2, CameraModel::SonyFr7] {
+            assert_eq!(
+                tele(7, model).unwrap(),
+                [0x81, 0x01, 0x04, 0x07, 0x27, 0xFF]
+            );
+            assert_eq!(
+                wide(7, model).unwrap(),
+                [0x81, 0x01, 0x04, 0x07, 0x37, 0xFF]
+            );
+            assert!(matches!(
+                tele(8, model),
+                Err(ViscaError::ParameterOutOfRange {
+                    value: 8,
+                    max: 7,
+                    ..
+                })
+            ));
+            assert!(wide(8, model).is_err());
         }
+        let generic = CameraModel::Generic;
+        assert_eq!(tele(8, generic).unwrap()[4], 0x28);
+        assert_eq!(tele(0xF, generic).unwrap()[4], 0x2F);
+        assert_eq!(wide(0xF, generic).unwrap()[4], 0x3F);
+        assert!(matches!(
+            wide(0x10, generic),
+            Err(ViscaError::ParameterOutOfRange { max: 0xF, .. })
+        ));
     }
 }
@@ -0,0 +1,206 @@
+use log::debug;
+
+use crate::command::response::{
+    parse_status_response, parse_visca_response, reply_address, Socket,
+};
+use crate::{ViscaError, ViscaResponse, ViscaResponseType};
+
+struct Pending<Id> {
+    id: Id,
+    response_type: Option<ViscaResponseType>,
+    socket: Option<Socket>,
+}
+
+/// Matches replies to outstanding requests when several are in flight.
+///
+/// Commands are tied to a socket by their ACK (`90 4y FF`) and released by
+/// the Completion or error on that socket. Inquiry replies carry no socket;
+/// cameras answer inquiries in order, so each goes to the oldest outstanding
+/// inquiry.
+pub struct Demux<Id> {
+    pending: Vec<Pending<Id>>,
+}
+
+impl<Id: Clone> Default for Demux<Id> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Id: Clone> Demux<Id> {
+    pub fn new() -> Self {
+        Demux {
+            pending: Vec::new(),
+        }
+    }
+
+    /// Registers a request in the order it was sent. `response_type` is the
+    /// command's [`crate::ViscaCommand::response_type`].
+    pub fn push(&mut self, id: Id, response_type: Option<ViscaResponseType>) {
+        self.pending.push(Pending {
+            id,
+            response_type,
+            socket: None,
+        });
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Routes one frame to its request. Final replies remove the request;
+    /// an ACK only binds it to a socket. Returns `None` for frames that match
+    /// nothing outstanding.
+    pub fn dispatch(&mut self, frame: &[u8]) -> Option<(Id, ViscaResponse)> {
+        if frame.len() < 3 || reply_address(frame[0]).is_none() || frame[frame.len() - 1] != 0xFF {
+            debug!("Demux ignoring malformed frame: {:02X?}", frame);
+            return None;
+        }
+
+        let socket = Socket::from_nibble(frame[1]);
+        match frame[1] {
+            0x40..=0x4F => {
+                let pending = self
+                    .pending
+                    .iter_mut()
+                    .find(|p| p.response_type.is_none() && p.socket.is_none())?;
+                pending.socket = Some(socket);
+                Some((pending.id.clone(), ViscaResponse::Ack(socket)))
+            }
+            0x50..=0x5F if frame.len() == 3 => {
+                let index = self.command_index(socket)?;
+                Some((
+                    self.pending.remove(index).id,
+                    ViscaResponse::Completion(socket),
+                ))
+            }
+            0x50..=0x5F => {
+                let index = self.inquiry_index()?;
+                let pending = self.pending.remove(index);
+                let response_type = pending.response_type.as_ref()?;
+                let response =
+                    parse_visca_response(frame, response_type).unwrap_or_else(ViscaResponse::Error);
+                Some((pending.id, response))
+            }
+            0x60..=0x6F => {
+                let error = match parse_status_response(frame) {
+                    Err(e) => e,
+                    Ok(_) => ViscaError::InvalidResponseFormat,
+                };
+                let index = self
+                    .command_index(socket)
+                    .or_else(|| self.inquiry_index())?;
+                Some((self.pending.remove(index).id, ViscaResponse::Error(error)))
+            }
+            _ => None,
+        }
+    }
+
+    /// The oldest outstanding inquiry.
+    fn inquiry_index(&self) -> Option<usize> {
+        self.pending.iter().position(|p| p.response_type.is_some())
+    }
+
+    /// The command bound to `socket`, or failing that the oldest command that
+    /// never saw an ACK (cameras that skip the ACK for some commands).
+    fn command_index(&self, socket: Socket) -> Option<usize> {
+        self.pending
+            .iter()
+            .position(|p| p.socket == Some(socket))
+            .or_else(|| {
+                self.pending
+                    .iter()
+                    .position(|p| p.response_type.is_none() && p.socket.is_none())
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{command::Power, ViscaInquiryResponse};
+
+    #[test]
+    fn interleaved_replies_reach_their_requests() {
+        let mut demux = Demux::new();
+        demux.push("home", None);
+        demux.push("power", Some(ViscaResponseType::Power));
+        demux.push("zoom", None);
+        demux.push("zoom position", Some(ViscaResponseType::ZoomPosition));
+
+        let frames: [&[u8]; 6] = [
+            &[0x90, 0x41, 0xFF],
+            &[0x90, 0x50, 0x02, 0xFF],
+            &[0x90, 0x42, 0xFF],
+            &[0x90, 0x52, 0xFF],
+            &[0x90, 0x50, 0x01, 0x02, 0x03, 0x04, 0xFF],
+            &[0x90, 0x51, 0xFF],
+        ];
+        let routed: Vec<_> = frames
+            .iter()
+            .map(|frame| demux.dispatch(frame).unwrap())
+            .collect();
+
+        assert!(matches!(routed[0], ("home", ViscaResponse::Ack(s)) if s.get() == 1));
+        assert!(matches!(
+            routed[1],
+            (
+                "power",
+                ViscaResponse::InquiryResponse(ViscaInquiryResponse::Power { power: Power::On })
+            )
+        ));
+        assert!(matches!(routed[2], ("zoom", ViscaResponse::Ack(s)) if s.get() == 2));
+        assert!(matches!(routed[3], ("zoom", ViscaResponse::Completion(s)) if s.get() == 2));
+        assert!(matches!(
+            routed[4],
+            (
+                "zoom position",
+                ViscaResponse::InquiryResponse(ViscaInquiryResponse::ZoomPosition {
+                    position: 0x1234
+                })
+            )
+        ));
+        assert!(matches!(routed[5], ("home", ViscaResponse::Completion(s)) if s.get() == 1));
+        assert!(demux.is_empty());
+    }
+
+    #[test]
+    fn errors_release_the_command_on_their_socket() {
+        let mut demux = Demux::new();
+        demux.push(1, None);
+        demux.push(2, None);
+        demux.dispatch(&[0x90, 0x41, 0xFF]);
+        demux.dispatch(&[0x90, 0x42, 0xFF]);
+        assert!(matches!(
+            demux.dispatch(&[0x90, 0x62, 0x41, 0xFF]),
+            Some((2, ViscaResponse::Error(ViscaError::CommandNotExecutable)))
+        ));
+        assert_eq!(demux.len(), 1);
+    }
+
+    #[test]
+    fn completion_without_an_ack_goes_to_the_oldest_command() {
+        let mut demux = Demux::new();
+        demux.push("first", None);
+        demux.push("second", None);
+        assert!(matches!(
+            demux.dispatch(&[0x90, 0x51, 0xFF]),
+            Some(("first", ViscaResponse::Completion(_)))
+        ));
+    }
+
+    #[test]
+    fn unmatched_and_malformed_frames_are_ignored() {
+        let mut demux: Demux<u8> = Demux::new();
+        assert!(demux.dispatch(&[0x90, 0x41, 0xFF]).is_none());
+        assert!(demux.dispatch(&[0x90, 0x51, 0xFF]).is_none());
+        demux.push(1, None);
+        assert!(demux.dispatch(&[0x90, 0x41]).is_none());
+        assert!(demux.dispatch(&[0x81, 0x41, 0xFF]).is_none());
+        assert_eq!(demux.len(), 1);
+    }
+}
@@ -24,8 +24,8 @@ pub enum ViscaError {
     #[error("Invalid response format")]
     InvalidResponseFormat,
 
-    #[error("Invalid response length")]
-    InvalidResponseLength,
+    #[error("Invalid response length: expected {expected} bytes, got {actual}")]
+    InvalidResponseLength { expected: usize, actual: usize },
 
     #[error("Unexpected response type")]
     UnexpectedResponseType,
@@ -41,6 +41,25 @@ pub enum ViscaError {
 
     #[error("Invalid parameter: {0}")]
     InvalidParameter(String),
+
+    #[error("Invalid parameter: {field} must be in the range {}", format.range(*min, *max))]
+    ParameterOutOfRange {
+        field: &'static str,
+        value: i64,
+        min: i64,
+        max: i64,
+        /// How the bounds are written in the message.
+        format: RangeFormat,
+    },
+
+    #[error("Timed out after {0:?}")]
+    Timeout(std::time::Duration),
+
+    #[error("Camera is busy: command buffer still full after {attempts} attempts")]
+    Busy { attempts: u32 },
+
+    #[error("Setting not applied: expected {expected}, camera reports {actual}")]
+    VerificationFailed { expected: String, actual: String },
 }
 
 impl ViscaError {
@@ -54,6 +73,155 @@ impl ViscaError {
             _ => ViscaError::Unknown(code),
         }
     }
+
+    /// A `ParameterOutOfRange` error with decimal bounds; see
+    /// [`with_range_format`](Self::with_range_format).
+    pub fn out_of_range(field: &'static str, value: impl Into<i64>, min: i64, max: i64) -> Self {
+        ViscaError::ParameterOutOfRange {
+            field,
+            value: value.into(),
+            min,
+            max,
+            format: RangeFormat::Decimal,
+        }
+    }
+
+    /// Sets how a `ParameterOutOfRange` error writes its bounds, for fields
+    /// documented as wire bytes. Other errors are returned unchanged.
+    pub fn with_range_format(mut self, range_format: RangeFormat) -> Self {
+        if let ViscaError::ParameterOutOfRange { format, .. } = &mut self {
+            *format = range_format;
+        }
+        self
+    }
+
+    /// Inverse of [`from_code`](Self::from_code). Returns `None` for errors
+    /// that originate locally rather than on the wire.
+    pub fn to_code(&self) -> Option<u8> {
+        match self {
+            ViscaError::SyntaxError => Some(0x02),
+            ViscaError::CommandBufferFull => Some(0x03),
+            ViscaError::CommandCanceled => Some(0x04),
+            ViscaError::NoSocket => Some(0x05),
+            ViscaError::CommandNotExecutable => Some(0x41),
+            ViscaError::Unknown(code) => Some(*code),
+            _ => None,
+        }
+    }
+}
+
+/// How a [`ViscaError::ParameterOutOfRange`] message writes its bounds.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum RangeFormat {
+    /// `0..=89`
+    #[default]
+    Decimal,
+    /// `0x00..=0x59`
+    Hex,
+    /// `0x00..=0x59 (0-89)`
+    HexAndDecimal,
+}
+
+impl RangeFormat {
+    fn range(&self, min: i64, max: i64) -> String {
+        match self {
+            RangeFormat::Decimal => format!("{}..={}", min, max),
+            RangeFormat::Hex => format!("{:#04X}..={:#04X}", min, max),
+            RangeFormat::HexAndDecimal => {
+                format!("{} ({}-{})", RangeFormat::Hex.range(min, max), min, max)
+            }
+        }
+    }
+}
+
+/// Stable category of a [`ViscaError`], for API layers that report errors
+/// by code rather than by message. See [`ViscaError::kind`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ViscaErrorKind {
+    /// I/O failure other than a timeout.
+    Io,
+    /// No reply in time, from the socket or a wait for a Completion.
+    Timeout,
+    /// The camera rejected the command's syntax.
+    Syntax,
+    /// The camera's command buffer was full.
+    BufferFull,
+    /// The command buffer stayed full through every retry.
+    Busy,
+    Canceled,
+    NoSocket,
+    /// The camera can't execute the command in its current state.
+    NotExecutable,
+    /// An error code the camera sent that isn't one of the above.
+    CameraError,
+    /// A reply that couldn't be parsed or wasn't the expected kind.
+    InvalidResponse,
+    Transport,
+    /// A command parameter was rejected before anything was sent.
+    InvalidParameter,
+    /// A setting read back differs from what was sent.
+    VerificationFailed,
+}
+
+impl ViscaErrorKind {
+    /// Snake-case code, e.g. `"timeout"`. Stable across releases.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ViscaErrorKind::Io => "io",
+            ViscaErrorKind::Timeout => "timeout",
+            ViscaErrorKind::Syntax => "syntax_error",
+            ViscaErrorKind::BufferFull => "buffer_full",
+            ViscaErrorKind::Busy => "busy",
+            ViscaErrorKind::Canceled => "canceled",
+            ViscaErrorKind::NoSocket => "no_socket",
+            ViscaErrorKind::NotExecutable => "not_executable",
+            ViscaErrorKind::CameraError => "camera_error",
+            ViscaErrorKind::InvalidResponse => "invalid_response",
+            ViscaErrorKind::Transport => "transport",
+            ViscaErrorKind::InvalidParameter => "invalid_parameter",
+            ViscaErrorKind::VerificationFailed => "verification_failed",
+        }
+    }
+}
+
+impl ViscaError {
+    /// The error's category, independent of its `Display` wording.
+    pub fn kind(&self) -> ViscaErrorKind {
+        match self {
+            ViscaError::Io(e)
+                if matches!(
+                    e.kind(),
+                    io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock
+                ) =>
+            {
+                ViscaErrorKind::Timeout
+            }
+            ViscaError::Io(_) => ViscaErrorKind::Io,
+            ViscaError::Timeout(_) => ViscaErrorKind::Timeout,
+            ViscaError::SyntaxError => ViscaErrorKind::Syntax,
+            ViscaError::CommandBufferFull => ViscaErrorKind::BufferFull,
+            ViscaError::Busy { .. } => ViscaErrorKind::Busy,
+            ViscaError::CommandCanceled => ViscaErrorKind::Canceled,
+            ViscaError::NoSocket => ViscaErrorKind::NoSocket,
+            ViscaError::CommandNotExecutable => ViscaErrorKind::NotExecutable,
+            ViscaError::Unknown(_) => ViscaErrorKind::CameraError,
+            ViscaError::InvalidResponseFormat
+            | ViscaError::InvalidResponseLength { .. }
+            | ViscaError::UnexpectedResponseType
+            | ViscaError::ParseError(_) => ViscaErrorKind::InvalidResponse,
+            ViscaError::TransportError(_) => ViscaErrorKind::Transport,
+            ViscaError::InvalidParameter(_) | ViscaError::ParameterOutOfRange { .. } => {
+                ViscaErrorKind::InvalidParameter
+            }
+            ViscaError::VerificationFailed { .. } => ViscaErrorKind::VerificationFailed,
+        }
+    }
+
+    /// Shorthand for `self.kind().as_str()`.
+    pub fn code_str(&self) -> &'static str {
+        self.kind().as_str()
+    }
 }
 
 impl From<nom::Err<nom::error::Error<&[u8]>>> for ViscaError {
@@ -70,3 +238,194 @@ pub enum AppError {
     #[error("VISCA error: {0}")]
     Visca(#[from] ViscaError),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem::discriminant;
+
+    #[test]
+    fn from_code_inverts_to_code() {
+        let mappable = [
+            ViscaError::SyntaxError,
+            ViscaError::CommandBufferFull,
+            ViscaError::CommandCanceled,
+            ViscaError::NoSocket,
+            ViscaError::CommandNotExecutable,
+            ViscaError::Unknown(0x7A),
+        ];
+        for error in &mappable {
+            let code = error.to_code().unwrap();
+            let decoded = ViscaError::from_code(code);
+            assert_eq!(discriminant(&decoded), discriminant(error));
+            assert_eq!(decoded.to_code(), Some(code));
+        }
+        for code in u8::MIN..=u8::MAX {
+            assert_eq!(ViscaError::from_code(code).to_code(), Some(code));
+        }
+    }
+
+    #[test]
+    fn local_errors_have_no_wire_code() {
+        assert_eq!(ViscaError::InvalidResponseFormat.to_code(), None);
+        assert_eq!(ViscaError::Busy { attempts: 3 }.to_code(), None);
+        assert_eq!(
+            ViscaError::out_of_range("Preset", 300, 0, 254).to_code(),
+            None
+        );
+    }
+
+    /// The `field`, `value`, `min` and `max` of a `ParameterOutOfRange`.
+    fn range_fields(error: ViscaError) -> (&'static str, i64, i64, i64) {
+        match error {
+            ViscaError::ParameterOutOfRange {
+                field,
+                value,
+                min,
+                max,
+                ..
+            } => (field, value, min, max),
+            other => panic!("expected ParameterOutOfRange, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn range_checks_report_the_field_and_bounds() {
+        use crate::command::{
+            pan_tilt::PanSpeed,
+            preset::{PresetAction, MAX_PRESET},
+            LuminanceCommand, PresetCommand,
+        };
+        use crate::ViscaCommand;
+
+        assert_eq!(
+            range_fields(PanSpeed::new(0x19).unwrap_err()),
+            ("Pan speed", 0x19, 0, 24)
+        );
+        let preset = PresetCommand {
+            action: PresetAction::Recall,
+            preset_number: MAX_PRESET + 1,
+        };
+        assert_eq!(
+            range_fields(preset.to_bytes().unwrap_err()),
+            ("Preset number", MAX_PRESET as i64 + 1, 0, MAX_PRESET as i64)
+        );
+        assert_eq!(
+            range_fields(LuminanceCommand { value: 15 }.to_bytes().unwrap_err()),
+            ("Luminance value", 15, 0, 14)
+        );
+    }
+
+    #[test]
+    fn range_errors_keep_the_human_message() {
+        let error = || ViscaError::out_of_range("Preset number", 90, 0, 89);
+        assert_eq!(
+            error().to_string(),
+            "Invalid parameter: Preset number must be in the range 0..=89"
+        );
+        assert_eq!(
+            error().with_range_format(RangeFormat::Hex).to_string(),
+            "Invalid parameter: Preset number must be in the range 0x00..=0x59"
+        );
+        assert_eq!(
+            error()
+                .with_range_format(RangeFormat::HexAndDecimal)
+                .to_string(),
+            "Invalid parameter: Preset number must be in the range 0x00..=0x59 (0-89)"
+        );
+        // Only range errors take a format.
+        assert!(matches!(
+            ViscaError::SyntaxError.with_range_format(RangeFormat::Hex),
+            ViscaError::SyntaxError
+        ));
+    }
+
+    #[test]
+    fn every_variant_maps_to_a_kind_and_code() {
+        use ViscaErrorKind as K;
+        let cases = [
+            (
+                ViscaError::Io(io::ErrorKind::TimedOut.into()),
+                K::Timeout,
+                "timeout",
+            ),
+            (
+                ViscaError::Io(io::ErrorKind::WouldBlock.into()),
+                K::Timeout,
+                "timeout",
+            ),
+            (
+                ViscaError::Io(io::ErrorKind::ConnectionReset.into()),
+                K::Io,
+                "io",
+            ),
+            (
+                ViscaError::Timeout(std::time::Duration::from_secs(1)),
+                K::Timeout,
+                "timeout",
+            ),
+            (ViscaError::SyntaxError, K::Syntax, "syntax_error"),
+            (ViscaError::CommandBufferFull, K::BufferFull, "buffer_full"),
+            (ViscaError::Busy { attempts: 3 }, K::Busy, "busy"),
+            (ViscaError::CommandCanceled, K::Canceled, "canceled"),
+            (ViscaError::NoSocket, K::NoSocket, "no_socket"),
+            (
+                ViscaError::CommandNotExecutable,
+                K::NotExecutable,
+                "not_executable",
+            ),
+            (ViscaError::Unknown(0x7A), K::CameraError, "camera_error"),
+            (
+                ViscaError::InvalidResponseFormat,
+                K::InvalidResponse,
+                "invalid_response",
+            ),
+            (
+                ViscaError::InvalidResponseLength {
+                    expected: 7,
+                    actual: 4,
+                },
+                K::InvalidResponse,
+                "invalid_response",
+            ),
+            (
+                ViscaError::UnexpectedResponseType,
+                K::InvalidResponse,
+                "invalid_response",
+            ),
+            (
+                ViscaError::ParseError("x".into()),
+                K::InvalidResponse,
+                "invalid_response",
+            ),
+            (
+                ViscaError::TransportError("x".into()),
+                K::Transport,
+                "transport",
+            ),
+            (
+                ViscaError::InvalidParameter("x".into()),
+                K::InvalidParameter,
+                "invalid_parameter",
+            ),
+            (
+                ViscaError::out_of_range("Preset", 300, 0, 254),
+                K::InvalidParameter,
+                "invalid_parameter",
+            ),
+            (
+                ViscaError::VerificationFailed {
+                    expected: "a".into(),
+                    actual: "b".into(),
+                },
+                K::VerificationFailed,
+                "verification_failed",
+            ),
+        ];
+        for (error, kind, code) in cases {
+            assert_eq!(error.kind(), kind, "{:?}", error);
+            assert_eq!(error.code_str(), code, "{:?}", error);
+            assert_eq!(kind.as_str(), code);
+        }
+    }
+}
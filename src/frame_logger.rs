@@ -0,0 +1,114 @@
+use log::info;
+
+use crate::command::response::{parse_status_response, parse_visca_response};
+use crate::{ViscaCommand, ViscaError, ViscaResponse, ViscaResponseType, ViscaTransport};
+
+/// Wraps a transport and logs every frame with a decoded description, e.g.
+/// `-> ZoomDirect 81 01 04 47 00 05 00 00 FF` and `<- Completion socket 1`.
+///
+/// Replies are decoded against the response type of the last command sent;
+/// frames that don't decode are logged as a hex dump.
+pub struct FrameLogger<T> {
+    inner: T,
+    response_type: Option<ViscaResponseType>,
+}
+
+impl<T: ViscaTransport> FrameLogger<T> {
+    pub fn new(inner: T) -> Self {
+        FrameLogger {
+            inner,
+            response_type: None,
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T: ViscaTransport> ViscaTransport for FrameLogger<T> {
+    fn send_command(&mut self, command: &dyn ViscaCommand) -> Result<(), ViscaError> {
+        self.response_type = command.response_type();
+        match command.to_bytes() {
+            Ok(bytes) => info!("-> {} {}", command.name(), hex(&bytes)),
+            Err(e) => info!("-> {} (not encodable: {})", command.name(), e),
+        }
+        self.inner.send_command(command)
+    }
+
+    fn receive_response(&mut self) -> Result<Vec<Vec<u8>>, ViscaError> {
+        let frames = self.inner.receive_response()?;
+        for frame in &frames {
+            info!("<- {}", describe_reply(frame, self.response_type.as_ref()));
+        }
+        Ok(frames)
+    }
+}
+
+fn describe_reply(frame: &[u8], response_type: Option<&ViscaResponseType>) -> String {
+    let socket = frame.get(1).map_or(0, |byte| byte & 0x0F);
+    let parsed = match response_type {
+        Some(response_type) if frame.len() > 3 && frame[1] & 0xF0 == 0x50 => {
+            parse_visca_response(frame, response_type)
+        }
+        _ => parse_status_response(frame),
+    };
+    match parsed {
+        Ok(ViscaResponse::Ack(socket)) => format!("ACK socket {}", socket),
+        Ok(ViscaResponse::Completion(socket)) => format!("Completion socket {}", socket),
+        Ok(ViscaResponse::InquiryResponse(inquiry_response)) => format!("{:?}", inquiry_response),
+        Ok(ViscaResponse::NetworkChange) => "Network change".to_string(),
+        Ok(ViscaResponse::Error(e)) => format!("Error socket {}: {}", socket, e),
+        Err(e) if frame.len() == 4 && frame[1] & 0xF0 == 0x60 => {
+            format!("Error socket {}: {}", socket, e)
+        }
+        Ok(ViscaResponse::Unknown(_)) | Err(_) => hex(frame),
+    }
+}
+
+pub(crate) fn hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{:02X}", byte))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_zoom_inquiry_exchange_is_annotated() {
+        let zoom = Some(&ViscaResponseType::ZoomPosition);
+        assert_eq!(
+            describe_reply(&[0x90, 0x50, 0x00, 0x05, 0x00, 0x00, 0xFF], zoom),
+            "ZoomPosition { position: 1280 }"
+        );
+        assert_eq!(describe_reply(&[0x90, 0x41, 0xFF], None), "ACK socket 1");
+        assert_eq!(
+            describe_reply(&[0x90, 0x51, 0xFF], zoom),
+            "Completion socket 1"
+        );
+        assert_eq!(
+            describe_reply(&[0x90, 0x61, 0x41, 0xFF], None),
+            "Error socket 1: Command is not executable"
+        );
+    }
+
+    #[test]
+    fn undecodable_frames_fall_back_to_hex() {
+        assert_eq!(
+            describe_reply(&[0x90, 0x07, 0x01, 0xFF], None),
+            "90 07 01 FF"
+        );
+        assert_eq!(
+            describe_reply(
+                &[0x90, 0x50, 0x00, 0xFF],
+                Some(&ViscaResponseType::ZoomPosition)
+            ),
+            "90 50 00 FF"
+        );
+        assert_eq!(hex(&[0x81, 0x01, 0x04, 0x47]), "81 01 04 47");
+    }
+}
@@ -0,0 +1,204 @@
+use log::debug;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use crate::{split_frames, split_frames_dropping_echoes, ViscaError};
+
+/// How VISCA frames are carried on a link. Transports hand each encoded
+/// command to [`wrap`](Framing::wrap) and split what they receive with
+/// [`unwrap`](Framing::unwrap).
+pub trait Framing: Send {
+    fn wrap(&self, payload: &[u8]) -> Vec<u8>;
+
+    /// Extracts the `z0 .. FF` reply frames from received bytes.
+    fn unwrap<'a>(&self, buffer: &'a [u8]) -> Result<Vec<&'a [u8]>, ViscaError>;
+
+    /// Builds the packet that resets the link's sequence numbering and
+    /// zeroes the local counter. `None` for framings without a sequence.
+    fn sequence_reset(&self) -> Option<Vec<u8>> {
+        None
+    }
+}
+
+/// Frames sent as is and delimited by their `0xFF` terminator, as on serial
+/// links, TCP and most cameras' UDP port.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct RawFraming;
+
+impl Framing for RawFraming {
+    fn wrap(&self, payload: &[u8]) -> Vec<u8> {
+        payload.to_vec()
+    }
+
+    fn unwrap<'a>(&self, buffer: &'a [u8]) -> Result<Vec<&'a [u8]>, ViscaError> {
+        split_frames(buffer)
+    }
+}
+
+/// Like [`RawFraming`], but discards the `8x .. FF` command frames some
+/// USB-serial adapters echo back ahead of the camera's reply. Pass it to a
+/// transport's `with_framing` for links behind such an adapter.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct EchoDroppingFraming;
+
+impl Framing for EchoDroppingFraming {
+    fn wrap(&self, payload: &[u8]) -> Vec<u8> {
+        payload.to_vec()
+    }
+
+    fn unwrap<'a>(&self, buffer: &'a [u8]) -> Result<Vec<&'a [u8]>, ViscaError> {
+        split_frames_dropping_echoes(buffer)
+    }
+}
+
+/// Length of the Sony VISCA-over-IP header: payload type, payload length
+/// and sequence number.
+const VISCA_OVER_IP_HEADER_LEN: usize = 8;
+
+/// Sony VISCA-over-IP: each frame follows an 8-byte header with the payload
+/// type, payload length and a sequence number. The camera drops commands
+/// whose sequence number it doesn't expect.
+#[derive(Debug, Default)]
+pub struct ViscaOverIpFraming {
+    sequence: AtomicU32,
+}
+
+impl ViscaOverIpFraming {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sequence number the next wrapped packet will carry.
+    pub fn sequence(&self) -> u32 {
+        self.sequence.load(Ordering::Relaxed)
+    }
+
+    fn packet(&self, payload_type: [u8; 2], payload: &[u8]) -> Vec<u8> {
+        let sequence = self.sequence.fetch_add(1, Ordering::Relaxed);
+        let mut packet = Vec::with_capacity(VISCA_OVER_IP_HEADER_LEN + payload.len());
+        packet.extend_from_slice(&payload_type);
+        packet.extend_from_slice(&(payload.len() as u16).to_be_bytes());
+        packet.extend_from_slice(&sequence.to_be_bytes());
+        packet.extend_from_slice(payload);
+        packet
+    }
+}
+
+impl Framing for ViscaOverIpFraming {
+    fn wrap(&self, payload: &[u8]) -> Vec<u8> {
+        // Inquiries (`8x 09 ..`) and commands have distinct payload types.
+        let payload_type = match payload.get(1) {
+            Some(0x09) => [0x01, 0x10],
+            _ => [0x01, 0x00],
+        };
+        self.packet(payload_type, payload)
+    }
+
+    fn unwrap<'a>(&self, buffer: &'a [u8]) -> Result<Vec<&'a [u8]>, ViscaError> {
+        let mut frames = Vec::new();
+        let mut rest = buffer;
+        while !rest.is_empty() {
+            if rest.len() < VISCA_OVER_IP_HEADER_LEN {
+                return Err(ViscaError::InvalidResponseFormat);
+            }
+            let length = usize::from(u16::from_be_bytes([rest[2], rest[3]]));
+            let end = VISCA_OVER_IP_HEADER_LEN + length;
+            let payload = rest
+                .get(VISCA_OVER_IP_HEADER_LEN..end)
+                .ok_or(ViscaError::InvalidResponseFormat)?;
+            match rest[..2] {
+                [0x01, 0x11] => frames.extend(split_frames(payload)?),
+                _ => debug!("Ignoring VISCA-over-IP packet: {:02X?}", &rest[..end]),
+            }
+            rest = &rest[end..];
+        }
+        Ok(frames)
+    }
+
+    /// `02 00 00 01 ss ss ss ss 01`.
+    fn sequence_reset(&self) -> Option<Vec<u8>> {
+        let packet = self.packet([0x02, 0x00], &[0x01]);
+        self.sequence.store(0, Ordering::Relaxed);
+        Some(packet)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOME: [u8; 5] = [0x81, 0x01, 0x06, 0x04, 0xFF];
+    const ACK: [u8; 3] = [0x90, 0x41, 0xFF];
+    const COMPLETION: [u8; 3] = [0x90, 0x51, 0xFF];
+
+    #[test]
+    fn echoed_commands_are_dropped() {
+        let buffer = [&HOME[..], &ACK, &COMPLETION].concat();
+        assert_eq!(
+            EchoDroppingFraming.unwrap(&buffer).unwrap(),
+            [&ACK[..], &COMPLETION]
+        );
+        // An echo read on its own yields no replies.
+        assert!(EchoDroppingFraming.unwrap(&HOME).unwrap().is_empty());
+        assert!(EchoDroppingFraming.unwrap(&HOME[..4]).is_err());
+    }
+
+    #[test]
+    fn sequence_reset_packet_zeroes_the_counter() {
+        let framing = ViscaOverIpFraming::new();
+        framing.wrap(&HOME);
+        framing.wrap(&HOME);
+        assert_eq!(framing.sequence(), 2);
+        assert_eq!(
+            framing.sequence_reset().unwrap(),
+            [0x02, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x02, 0x01]
+        );
+        assert_eq!(framing.sequence(), 0);
+        assert!(RawFraming.sequence_reset().is_none());
+    }
+
+    #[test]
+    fn raw_framing_round_trips() {
+        assert_eq!(RawFraming.wrap(&HOME), HOME);
+        let buffer = [&ACK[..], &COMPLETION].concat();
+        assert_eq!(RawFraming.unwrap(&buffer).unwrap(), [&ACK[..], &COMPLETION]);
+        assert_eq!(EchoDroppingFraming.wrap(&HOME), HOME);
+    }
+
+    /// A camera's reply packet around `payload`.
+    fn reply_packet(payload: &[u8], sequence: u32) -> Vec<u8> {
+        let mut packet = vec![0x01, 0x11];
+        packet.extend_from_slice(&(payload.len() as u16).to_be_bytes());
+        packet.extend_from_slice(&sequence.to_be_bytes());
+        packet.extend_from_slice(payload);
+        packet
+    }
+
+    #[test]
+    fn visca_over_ip_wraps_with_type_length_and_sequence() {
+        let framing = ViscaOverIpFraming::new();
+        assert_eq!(
+            framing.wrap(&HOME),
+            [0x01, 0x00, 0x00, 0x05, 0, 0, 0, 0, 0x81, 0x01, 0x06, 0x04, 0xFF]
+        );
+        let inquiry = [0x81, 0x09, 0x04, 0x00, 0xFF];
+        assert_eq!(
+            framing.wrap(&inquiry)[..8],
+            [0x01, 0x10, 0x00, 0x05, 0, 0, 0, 1]
+        );
+        assert_eq!(framing.sequence(), 2);
+    }
+
+    #[test]
+    fn visca_over_ip_unwraps_reply_packets() {
+        let framing = ViscaOverIpFraming::new();
+        let buffer = [reply_packet(&ACK, 0), reply_packet(&COMPLETION, 0)].concat();
+        assert_eq!(framing.unwrap(&buffer).unwrap(), [&ACK[..], &COMPLETION]);
+
+        // Control replies are skipped; short or truncated packets are errors.
+        let control = [0x02, 0x01, 0x00, 0x01, 0, 0, 0, 0, 0x01];
+        assert!(framing.unwrap(&control).unwrap().is_empty());
+        assert!(framing.unwrap(&buffer[..7]).is_err());
+        let truncated = reply_packet(&ACK, 0);
+        assert!(framing.unwrap(&truncated[..truncated.len() - 1]).is_err());
+    }
+}
@@ -1,76 +1,291 @@
-use log::{debug, error};
+use log::{debug, error, warn};
 use std::{
     io::{self, Read, Write},
     net::{TcpStream, UdpSocket},
-    time::Duration,
+    time::{Duration, Instant},
+};
+
+pub mod camera;
+pub use camera::{
+    Camera, CameraBuilder, MoveGuard, PanTiltLimits, ParkTarget, PollHandle, PresetTour, Sequence,
 };
 
 pub mod command;
 pub use command::{
-    response::{parse_visca_response, ViscaResponse},
-    ViscaCommand, ViscaInquiryResponse, ViscaResponseType,
+    response::{
+        ack_frame, build_error_response, completion_frame, inquiry_frame, parse_status_response,
+        parse_visca_response, reply_address, ReplyPattern, Socket, ViscaResponse,
+    },
+    with_response_type, ViscaCommand, ViscaInquiryResponse, ViscaResponseType,
 };
 
+pub mod demux;
+pub use demux::Demux;
+
+pub mod framing;
+pub use framing::{EchoDroppingFraming, Framing, RawFraming, ViscaOverIpFraming};
+
+pub mod frame_logger;
+pub use frame_logger::FrameLogger;
+
+pub mod transaction;
+pub use transaction::{CommandTransaction, TransactionState};
+
+pub mod clock;
+pub use clock::{Clock, SystemClock};
+
+#[cfg(test)]
+mod mock;
+
 mod error;
-pub use error::{AppError, ViscaError};
+pub use error::{AppError, RangeFormat, ViscaError, ViscaErrorKind};
+
+#[cfg(feature = "metrics")]
+mod stats;
+#[cfg(feature = "metrics")]
+pub use stats::TransportStats;
+
+pub mod model;
+pub use model::{
+    CameraModel, Protocol, ZoomLens, DEFAULT_VISCA_TCP_PORT, DEFAULT_VISCA_UDP_PORT,
+    SONY_VISCA_IP_PORT,
+};
 
-pub trait ViscaTransport {
+/// Transports are `Send` so a boxed transport can be moved into a worker
+/// thread or shared behind a mutex.
+pub trait ViscaTransport: Send {
     fn send_command(&mut self, command: &dyn ViscaCommand) -> Result<(), ViscaError>;
     fn receive_response(&mut self) -> Result<Vec<Vec<u8>>, ViscaError>;
 }
 
+/// Upper bound on the bytes accumulated for one UDP reply.
+const MAX_UDP_REPLY_BYTES: usize = 256;
+/// How long the rest of a partially received UDP reply may take to arrive.
+const UDP_CONTINUATION_TIMEOUT: Duration = Duration::from_millis(500);
 pub struct UdpTransport {
     socket: UdpSocket,
     address: String,
+    reconnect: bool,
+    framing: Box<dyn Framing>,
+    /// Consecutive reply timeouts after which the sequence is reset, see
+    /// [`set_desync_recovery`](Self::set_desync_recovery).
+    desync_threshold: Option<u32>,
+    missed_replies: u32,
+    /// Payload of the last command sent, until anything is received in reply;
+    /// resent once after a desync recovery.
+    last_payload: Option<Vec<u8>>,
+    #[cfg(feature = "metrics")]
+    stats: TransportStats,
 }
 
+/// Reply timeouts in a row after which a VISCA-over-IP transport assumes the
+/// camera has lost track of the sequence number.
+const DEFAULT_DESYNC_THRESHOLD: u32 = 3;
+
 impl UdpTransport {
     pub fn new(address: &str) -> io::Result<Self> {
+        Self::with_framing(address, RawFraming)
+    }
+
+    /// Like [`new`](Self::new), but carries frames using `framing`.
+    pub fn with_framing(address: &str, framing: impl Framing + 'static) -> io::Result<Self> {
         let socket = UdpSocket::bind("0.0.0.0:0")?;
         socket.set_read_timeout(Some(Duration::from_secs(10)))?;
         socket.set_write_timeout(Some(Duration::from_secs(10)))?;
         Ok(Self {
             socket,
             address: address.to_string(),
+            reconnect: false,
+            framing: Box::new(framing),
+            desync_threshold: None,
+            missed_replies: 0,
+            last_payload: None,
+            #[cfg(feature = "metrics")]
+            stats: TransportStats::default(),
         })
     }
+
+    /// Like [`new`](Self::new), but a failed send or receive re-creates the
+    /// socket on a fresh ephemeral port and retries the command once. For
+    /// links where interface changes invalidate the socket.
+    ///
+    /// Framings with a sequence number have it reset with
+    /// [`reset_sequence`](Self::reset_sequence) on the new socket, as the
+    /// camera treats the new port as a new controller.
+    pub fn new_with_reconnect(address: &str) -> io::Result<Self> {
+        Ok(Self {
+            reconnect: true,
+            ..Self::new(address)?
+        })
+    }
+
+    /// Like [`new`](Self::new), using [`ViscaOverIpFraming`] with desync
+    /// recovery enabled. See [`reset_sequence`](Self::reset_sequence).
+    pub fn new_visca_over_ip(address: &str) -> io::Result<Self> {
+        let mut transport = Self::with_framing(address, ViscaOverIpFraming::new())?;
+        transport.set_desync_recovery(Some(DEFAULT_DESYNC_THRESHOLD));
+        Ok(transport)
+    }
+
+    /// After `threshold` reply timeouts in a row, resets the sequence with
+    /// [`reset_sequence`](Self::reset_sequence) and resends the last command
+    /// once, as a camera whose expected sequence number has drifted ignores
+    /// everything silently. `None` disables this; only framings with a
+    /// sequence number can recover.
+    pub fn set_desync_recovery(&mut self, threshold: Option<u32>) {
+        self.desync_threshold = threshold;
+        self.missed_replies = 0;
+    }
+
+    /// Overrides the default read timeout, which bounds how long a reply is
+    /// waited for.
+    pub fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        self.socket.set_read_timeout(timeout)
+    }
+
+    /// Sends the framing's sequence reset packet, zeroing the local counter,
+    /// and waits for the camera's reply. Needed on VISCA-over-IP after either
+    /// side restarts, until which the camera ignores the controller's
+    /// commands. Fails with `InvalidParameter` for framings without a
+    /// sequence number.
+    pub fn reset_sequence(&mut self) -> Result<(), ViscaError> {
+        let packet = self
+            .framing
+            .sequence_reset()
+            .ok_or_else(|| ViscaError::InvalidParameter("Framing has no sequence number".into()))?;
+        self.send_sequence_reset(&packet)
+    }
+
+    fn send_sequence_reset(&mut self, packet: &[u8]) -> Result<(), ViscaError> {
+        self.socket.send_to(packet, &self.address)?;
+        debug!("Sent sequence reset: {:02X?}", packet);
+
+        let mut buffer = [0u8; 64];
+        let (bytes_received, _) = self.socket.recv_from(&mut buffer)?;
+        match &buffer[..bytes_received] {
+            [0x02, 0x01, ..] => Ok(()),
+            reply => {
+                error!("Unexpected reply to sequence reset: {:02X?}", reply);
+                Err(ViscaError::UnexpectedResponseType)
+            }
+        }
+    }
+
+    #[cfg(feature = "metrics")]
+    pub fn stats(&self) -> &TransportStats {
+        &self.stats
+    }
 }
 
 pub struct TcpTransport {
     stream: TcpStream,
+    framing: Box<dyn Framing>,
+    #[cfg(feature = "metrics")]
+    stats: TransportStats,
 }
 
 impl TcpTransport {
     pub fn new(address: &str) -> io::Result<Self> {
+        Self::with_framing(address, RawFraming)
+    }
+
+    /// Like [`new`](Self::new), but carries frames using `framing`.
+    pub fn with_framing(address: &str, framing: impl Framing + 'static) -> io::Result<Self> {
         let stream = TcpStream::connect(address)?;
         stream.set_read_timeout(Some(Duration::from_secs(30)))?;
         stream.set_write_timeout(Some(Duration::from_secs(30)))?;
-        Ok(Self { stream })
+        Ok(Self {
+            stream,
+            framing: Box::new(framing),
+            #[cfg(feature = "metrics")]
+            stats: TransportStats::default(),
+        })
+    }
+
+    /// Overrides the default read timeout, which bounds how long a reply is
+    /// waited for.
+    pub fn set_read_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        self.stream.set_read_timeout(timeout)
+    }
+
+    #[cfg(feature = "metrics")]
+    pub fn stats(&self) -> &TransportStats {
+        &self.stats
     }
 }
 
-fn parse_response(buffer: &[u8]) -> Result<Vec<Vec<u8>>, ViscaError> {
-    let mut responses = Vec::new();
-    let mut response = Vec::new();
-    let mut start_index = false;
+/// Splits a buffer holding one or more `z0 .. FF` reply frames into
+/// borrowed slices, without copying. Bytes before a frame's header (`0x90`
+/// for camera 1, see [`reply_address`]) are skipped. A trailing frame
+/// missing its terminator is an error.
+pub fn split_frames(buffer: &[u8]) -> Result<Vec<&[u8]>, ViscaError> {
+    split_reply_frames(buffer, false)
+}
 
-    for &byte in buffer {
-        response.push(byte);
-        if byte == 0x90 {
-            start_index = true;
-        } else if byte == 0xFF && start_index {
-            responses.push(response.clone());
-            response.clear();
-            start_index = false;
+/// Like [`split_frames`], but also discards whole `8x .. FF` command
+/// frames, which some USB-serial adapters echo back before the reply. A
+/// trailing echo missing its terminator is an error like any other
+/// truncated frame. See [`EchoDroppingFraming`].
+pub fn split_frames_dropping_echoes(buffer: &[u8]) -> Result<Vec<&[u8]>, ViscaError> {
+    split_reply_frames(buffer, true)
+}
+
+fn split_reply_frames(buffer: &[u8], drop_echoes: bool) -> Result<Vec<&[u8]>, ViscaError> {
+    let mut frames = Vec::new();
+    let mut start = None;
+    let mut echo_start = None;
+
+    for (index, &byte) in buffer.iter().enumerate() {
+        if let Some(echo) = echo_start {
+            if byte == 0xFF {
+                debug!("Discarding echoed command: {:02X?}", &buffer[echo..=index]);
+                echo_start = None;
+            }
+        } else if drop_echoes && start.is_none() && (0x80..=0x8F).contains(&byte) {
+            echo_start = Some(index);
+        } else if start.is_none() && reply_address(byte).is_some() {
+            start = Some(index);
+        } else if let (0xFF, Some(frame_start)) = (byte, start) {
+            frames.push(&buffer[frame_start..=index]);
+            start = None;
         }
     }
 
-    if start_index {
+    if let Some(frame_start) = start.or(echo_start) {
         // Log an error if the response format is invalid
-        error!("Invalid response format detected: {:02X?}", response);
+        error!(
+            "Invalid response format detected: {:02X?}",
+            &buffer[frame_start..]
+        );
         return Err(ViscaError::InvalidResponseFormat);
     }
 
+    Ok(frames)
+}
+
+/// Whether `buffer` holds exactly one whole VISCA message: a command
+/// (`8x`) or reply (see [`reply_address`]) header, at least one payload
+/// byte and a single `0xFF` terminator at the end. For streaming readers
+/// deciding when to stop accumulating; a buffer holding several frames is
+/// not one message, split it with [`split_frames`].
+pub fn is_complete_frame(buffer: &[u8]) -> bool {
+    match buffer {
+        [header, body @ .., 0xFF] if !body.is_empty() => {
+            ((0x80..=0x8F).contains(header) || reply_address(*header).is_some())
+                && !body.contains(&0xFF)
+        }
+        _ => false,
+    }
+}
+
+/// Owning variant of [`Framing::unwrap`].
+fn parse_response(framing: &dyn Framing, buffer: &[u8]) -> Result<Vec<Vec<u8>>, ViscaError> {
+    let responses: Vec<Vec<u8>> = framing
+        .unwrap(buffer)?
+        .into_iter()
+        .map(<[u8]>::to_vec)
+        .collect();
+
     // Log the number of responses parsed
     debug!("Parsed {} responses from buffer", responses.len());
 
@@ -79,19 +294,148 @@ fn parse_response(buffer: &[u8]) -> Result<Vec<Vec<u8>>, ViscaError> {
 
 impl ViscaTransport for UdpTransport {
     fn send_command(&mut self, command: &dyn ViscaCommand) -> Result<(), ViscaError> {
-        let command_bytes = command.to_bytes()?;
-        self.socket
-            .send_to(&command_bytes, &self.address)
-            .map_err(ViscaError::Io)?;
-        Ok(())
+        let result = self.send_frame(command);
+        #[cfg(feature = "metrics")]
+        self.stats.record_result(&result);
+        result
     }
 
     fn receive_response(&mut self) -> Result<Vec<Vec<u8>>, ViscaError> {
+        let result = self.receive_frames();
+        #[cfg(feature = "metrics")]
+        self.stats.record_result(&result);
+        result
+    }
+}
+
+impl UdpTransport {
+    fn send_frame(&mut self, command: &dyn ViscaCommand) -> Result<(), ViscaError> {
+        command.validate()?;
+        let payload = command.to_bytes()?;
+        let mut command_bytes = self.framing.wrap(&payload);
+        self.last_payload = Some(payload.clone());
+        if let Err(e) = self.socket.send_to(&command_bytes, &self.address) {
+            if !self.reconnect {
+                return Err(ViscaError::Io(e));
+            }
+            error!("Send failed, rebinding UDP socket: {}", e);
+            self.rebind()?;
+            // Re-wrap so the resend carries the reset sequence number.
+            command_bytes = self.framing.wrap(&payload);
+            self.socket.send_to(&command_bytes, &self.address)?;
+        }
+        #[cfg(feature = "metrics")]
+        self.stats.record_send(command_bytes.len());
+        Ok(())
+    }
+
+    /// Replaces the socket with a new one on an ephemeral port, keeping the
+    /// configured timeouts, and resets the sequence if the framing has one.
+    fn rebind(&mut self) -> Result<(), ViscaError> {
+        let socket = UdpSocket::bind("0.0.0.0:0")?;
+        socket.set_read_timeout(self.socket.read_timeout()?)?;
+        socket.set_write_timeout(self.socket.write_timeout()?)?;
+        self.socket = socket;
+        // Building the packet zeroes the counter, so it is built only once.
+        if let Some(packet) = self.framing.sequence_reset() {
+            self.send_sequence_reset(&packet)?;
+        }
+        Ok(())
+    }
+
+    /// Rebinds after a receive error and resends the last command once,
+    /// returning its reply. Without a command to resend, fails with `err`.
+    fn reconnect_after_receive(&mut self, err: io::Error) -> Result<Vec<Vec<u8>>, ViscaError> {
+        error!("Receive failed, rebinding UDP socket: {}", err);
+        self.rebind()?;
+        let Some(payload) = self.last_payload.clone() else {
+            return Err(ViscaError::Io(err));
+        };
+        let command_bytes = self.framing.wrap(&payload);
+        self.socket.send_to(&command_bytes, &self.address)?;
+        #[cfg(feature = "metrics")]
+        self.stats.record_send(command_bytes.len());
+        self.read_frames()
+    }
+
+    /// Reads the next reply, counting commands that got no reply at all
+    /// towards a desync recovery. Timeouts while waiting for a Completion
+    /// after the ACK don't count.
+    fn receive_frames(&mut self) -> Result<Vec<Vec<u8>>, ViscaError> {
+        match self.read_frames() {
+            Err(ViscaError::Io(e)) if is_timeout(&e) && self.last_payload.is_some() => {
+                self.missed_replies += 1;
+                match self.desync_threshold {
+                    Some(threshold) if self.missed_replies >= threshold => self.recover_sequence(e),
+                    _ => Err(ViscaError::Io(e)),
+                }
+            }
+            Err(ViscaError::Io(e)) if self.reconnect && !is_timeout(&e) => {
+                self.reconnect_after_receive(e)
+            }
+            result => {
+                if result.is_ok() {
+                    self.missed_replies = 0;
+                    self.last_payload = None;
+                }
+                result
+            }
+        }
+    }
+
+    /// Resets the sequence and resends the last command once, returning its
+    /// reply. Without a command to resend, fails with `timeout`.
+    fn recover_sequence(&mut self, timeout: io::Error) -> Result<Vec<Vec<u8>>, ViscaError> {
+        warn!(
+            "No reply in {} attempts, resetting the sequence number",
+            self.missed_replies
+        );
+        self.missed_replies = 0;
+        let Some(payload) = self.last_payload.clone() else {
+            return Err(ViscaError::Io(timeout));
+        };
+        self.reset_sequence()?;
+        let command_bytes = self.framing.wrap(&payload);
+        self.socket.send_to(&command_bytes, &self.address)?;
+        #[cfg(feature = "metrics")]
+        self.stats.record_send(command_bytes.len());
+        self.read_frames()
+    }
+
+    fn read_frames(&mut self) -> Result<Vec<Vec<u8>>, ViscaError> {
+        let read_timeout = self.socket.read_timeout()?;
+        let received_data = self.receive_datagrams();
+        self.socket.set_read_timeout(read_timeout)?;
+
+        let responses = parse_response(&*self.framing, &received_data?)?;
+        #[cfg(feature = "metrics")]
+        self.stats.record_responses(responses.len());
+        Ok(responses)
+    }
+
+    /// Collects datagrams until one ends in `0xFF`. Once a reply has started
+    /// arriving, the rest must follow within `UDP_CONTINUATION_TIMEOUT` and
+    /// fit in `MAX_UDP_REPLY_BYTES`, so a reply truncated on a lossy link
+    /// fails promptly instead of waiting out the socket timeout.
+    fn receive_datagrams(&mut self) -> Result<Vec<u8>, ViscaError> {
         let mut buffer = [0u8; 1024];
         let mut received_data = Vec::new();
+        let mut deadline: Option<Instant> = None;
 
         loop {
+            if let Some(deadline) = deadline {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                if remaining.is_zero() {
+                    error!("Truncated response: {:02X?}", received_data);
+                    return Err(ViscaError::InvalidResponseFormat);
+                }
+                self.socket.set_read_timeout(Some(remaining))?;
+            }
+
             match self.socket.recv_from(&mut buffer) {
+                Ok((0, src)) => {
+                    debug!("Ignoring empty datagram from {}", src);
+                }
                 Ok((bytes_received, src)) => {
                     debug!(
                         "Received {} bytes from {}: {:02X?}",
@@ -99,10 +443,21 @@ impl ViscaTransport for UdpTransport {
                         src,
                         &buffer[..bytes_received]
                     );
+                    #[cfg(feature = "metrics")]
+                    self.stats.record_receive(bytes_received);
                     received_data.extend_from_slice(&buffer[..bytes_received]);
                     if buffer[bytes_received - 1] == 0xFF {
-                        break;
+                        return Ok(received_data);
+                    }
+                    if received_data.len() > MAX_UDP_REPLY_BYTES {
+                        error!("Response exceeds {} bytes", MAX_UDP_REPLY_BYTES);
+                        return Err(ViscaError::InvalidResponseFormat);
                     }
+                    deadline.get_or_insert_with(|| Instant::now() + UDP_CONTINUATION_TIMEOUT);
+                }
+                Err(e) if deadline.is_some() && is_timeout(&e) => {
+                    error!("Truncated response: {:02X?}", received_data);
+                    return Err(ViscaError::InvalidResponseFormat);
                 }
                 Err(e) => {
                     error!("Failed to receive response: {}", e);
@@ -110,33 +465,58 @@ impl ViscaTransport for UdpTransport {
                 }
             }
         }
-
-        parse_response(&received_data)
     }
 }
 
 impl ViscaTransport for TcpTransport {
     fn send_command(&mut self, command: &dyn ViscaCommand) -> Result<(), ViscaError> {
-        let command_bytes = command.to_bytes()?;
+        let result = self.send_frame(command);
+        #[cfg(feature = "metrics")]
+        self.stats.record_result(&result);
+        result
+    }
+
+    fn receive_response(&mut self) -> Result<Vec<Vec<u8>>, ViscaError> {
+        let result = self.receive_frames();
+        #[cfg(feature = "metrics")]
+        self.stats.record_result(&result);
+        result
+    }
+}
+
+impl TcpTransport {
+    fn send_frame(&mut self, command: &dyn ViscaCommand) -> Result<(), ViscaError> {
+        command.validate()?;
+        let command_bytes = self.framing.wrap(&command.to_bytes()?);
         self.stream
             .write_all(&command_bytes)
             .map_err(ViscaError::Io)?;
         debug!("Sent {} bytes: {:02X?}", command_bytes.len(), command_bytes);
+        #[cfg(feature = "metrics")]
+        self.stats.record_send(command_bytes.len());
         Ok(())
     }
 
-    fn receive_response(&mut self) -> Result<Vec<Vec<u8>>, ViscaError> {
+    fn receive_frames(&mut self) -> Result<Vec<Vec<u8>>, ViscaError> {
         let mut buffer = [0u8; 1024];
         let mut received_data = Vec::new();
 
         loop {
             match self.stream.read(&mut buffer) {
+                Ok(0) => {
+                    error!("Connection closed before a complete response was received");
+                    return Err(ViscaError::Io(io::Error::from(
+                        io::ErrorKind::UnexpectedEof,
+                    )));
+                }
                 Ok(bytes_received) => {
                     debug!(
                         "Received {} bytes: {:02X?}",
                         bytes_received,
                         &buffer[..bytes_received]
                     );
+                    #[cfg(feature = "metrics")]
+                    self.stats.record_receive(bytes_received);
                     received_data.extend_from_slice(&buffer[..bytes_received]);
                     if buffer[bytes_received - 1] == 0xFF {
                         break;
@@ -149,36 +529,151 @@ impl ViscaTransport for TcpTransport {
             }
         }
 
-        parse_response(&received_data)
+        let responses = parse_response(&*self.framing, &received_data)?;
+        #[cfg(feature = "metrics")]
+        self.stats.record_responses(responses.len());
+        Ok(responses)
+    }
+}
+
+/// Whether `error` is a socket read timing out, reported as `WouldBlock` on
+/// some platforms.
+fn is_timeout(error: &io::Error) -> bool {
+    matches!(
+        error.kind(),
+        io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+    )
+}
+
+/// The [`ViscaCommand::completion_timeout`] of the commands that move the
+/// head to a target: absolute and relative moves, home and preset recall.
+pub const DEFAULT_MOTION_COMPLETION_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How long to back off when the camera reports its command buffer is full.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct BackpressurePolicy {
+    pub max_attempts: u32,
+    pub delay: Duration,
+}
+
+impl Default for BackpressurePolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            delay: Duration::from_millis(50),
+        }
     }
 }
 
+/// Like [`send_command_and_wait`], but resends the same command after
+/// `policy.delay` while the camera answers `CommandBufferFull`. Reports
+/// `ViscaError::Busy` once `policy.max_attempts` sends have been rejected.
+pub fn send_command_with_retry(
+    transport: &mut dyn ViscaTransport,
+    command: &dyn ViscaCommand,
+    policy: &BackpressurePolicy,
+) -> Result<ViscaResponse, ViscaError> {
+    send_command_with_retry_routing(transport, command, policy, false, &SystemClock, &mut |_| ())
+}
+
+/// [`send_command_with_retry`] that hands unsolicited frames read while
+/// waiting to `unsolicited` instead of dropping them, and with `strict` fails
+/// on unknown frames; see [`CommandTransaction::strict`]. Retry delays and
+/// the completion deadline are measured with `clock`.
+pub(crate) fn send_command_with_retry_routing(
+    transport: &mut dyn ViscaTransport,
+    command: &dyn ViscaCommand,
+    policy: &BackpressurePolicy,
+    strict: bool,
+    clock: &dyn Clock,
+    unsolicited: &mut dyn FnMut(ViscaResponse),
+) -> Result<ViscaResponse, ViscaError> {
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        match send_command_and_wait_routing(transport, command, strict, clock, unsolicited) {
+            Err(ViscaError::CommandBufferFull) if attempts < policy.max_attempts => {
+                debug!(
+                    "Command buffer full, retrying in {:?} (attempt {}/{})",
+                    policy.delay, attempts, policy.max_attempts
+                );
+                clock.sleep(policy.delay);
+            }
+            Err(ViscaError::CommandBufferFull) => {
+                error!(
+                    "Command buffer still full after {} attempts of {}",
+                    attempts,
+                    command.name()
+                );
+                return Err(ViscaError::Busy { attempts });
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Sends `command` and reads replies until the one that ends the wait for
+/// it. An error reply (`90 6y ee FF`) ends the wait with that error, whether
+/// it arrives instead of the ACK or after it, as when a move is accepted and
+/// then hits a limit.
+///
+/// For a command with a [`ViscaCommand::completion_timeout`] a read timeout
+/// after the ACK doesn't end the wait: the Completion is waited for up to
+/// that long, then the call fails with `ViscaError::Timeout`.
 pub fn send_command_and_wait(
     transport: &mut dyn ViscaTransport,
     command: &dyn ViscaCommand,
 ) -> Result<ViscaResponse, ViscaError> {
+    send_command_and_wait_routing(transport, command, false, &SystemClock, &mut |_| ())
+}
+
+fn send_command_and_wait_routing(
+    transport: &mut dyn ViscaTransport,
+    command: &dyn ViscaCommand,
+    strict: bool,
+    clock: &dyn Clock,
+    unsolicited: &mut dyn FnMut(ViscaResponse),
+) -> Result<ViscaResponse, ViscaError> {
+    debug!("Sending {}", command.name());
     transport.send_command(command)?;
 
+    let completion_deadline = command
+        .completion_timeout()
+        .map(|timeout| (clock.now() + timeout, timeout));
+    let mut awaiting_completion = false;
+    let mut transaction = CommandTransaction::new(command).strict(strict);
     loop {
-        match transport.receive_response() {
-            Ok(responses) => {
-                for response in responses {
-                    let parsed_response =
-                        parse_and_handle_response(&response, command.response_type())?;
-                    match parsed_response {
-                        ViscaResponse::Completion | ViscaResponse::InquiryResponse(_) => {
-                            return Ok(parsed_response);
-                        }
-                        _ => continue,
+        let responses = match (transport.receive_response(), completion_deadline) {
+            (Err(ViscaError::Io(e)), Some((deadline, timeout)))
+                if awaiting_completion && is_timeout(&e) =>
+            {
+                if clock.now() >= deadline {
+                    error!("{} did not complete within {:?}", command.name(), timeout);
+                    return Err(ViscaError::Timeout(timeout));
+                }
+                debug!("Still waiting for {} to complete", command.name());
+                continue;
+            }
+            (result, _) => result?,
+        };
+        for response in responses {
+            match transaction.feed(&response) {
+                TransactionState::Done(response) => return Ok(response),
+                TransactionState::Failed(e) => return Err(e),
+                state @ (TransactionState::AwaitingAck
+                | TransactionState::AwaitingCompletion { .. }) => {
+                    awaiting_completion =
+                        matches!(state, TransactionState::AwaitingCompletion { .. });
+                    if let Some(response) = transaction.take_unsolicited() {
+                        unsolicited(response);
                     }
                 }
             }
-            Err(e) => return Err(e),
         }
     }
 }
 
-fn parse_and_handle_response(
+pub(crate) fn parse_and_handle_response(
     response: &[u8],
     response_type: Option<ViscaResponseType>,
 ) -> Result<ViscaResponse, ViscaError> {
@@ -199,8 +694,16 @@ fn parse_and_handle_response(
             }
         }
     } else {
-        error!("No response type provided for response: {:02X?}", response);
-        Err(ViscaError::UnexpectedResponseType)
+        match parse_status_response(response) {
+            Ok(visca_response) => {
+                log_response(&visca_response);
+                Ok(visca_response)
+            }
+            Err(e) => {
+                error!("Error processing response: {}", e);
+                Err(e)
+            }
+        }
     }
 }
 
@@ -219,8 +722,11 @@ fn log_inquiry_response(inquiry_response: &ViscaInquiryResponse) {
         ViscaInquiryResponse::ZoomPosition { position } => {
             debug!("Zoom Position: {:02X?}", position);
         }
-        ViscaInquiryResponse::FocusPosition { position } => {
-            debug!("Focus Position: {:02X?}", position);
+        ViscaInquiryResponse::FocusPosition { position, tracking } => {
+            debug!(
+                "Focus Position: {:02X?}, tracking: {:?}",
+                position, tracking
+            );
         }
         ViscaInquiryResponse::Gain { gain } => {
             debug!("Gain: {}", gain);
@@ -252,12 +758,456 @@ fn log_inquiry_response(inquiry_response: &ViscaInquiryResponse) {
 
 fn log_response(response: &ViscaResponse) {
     match response {
-        ViscaResponse::Ack => debug!("ACK received"),
-        ViscaResponse::Completion => debug!("Completion received"),
+        ViscaResponse::Ack(socket) => debug!("ACK received on socket {}", socket),
+        ViscaResponse::Completion(socket) => debug!("Completion received on socket {}", socket),
         ViscaResponse::Error(err) => error!("Error received: {:?}", err),
         ViscaResponse::InquiryResponse(inquiry_response) => {
             debug!("Inquiry response: {:?}", inquiry_response);
         }
+        ViscaResponse::NetworkChange => debug!("Network change received"),
         _ => (),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{clock::TestClock, command::RawCommand, mock::MockTransport};
+    use std::sync::Arc;
+
+    const HOME: [u8; 5] = [0x81, 0x01, 0x06, 0x04, 0xFF];
+    const ACK: [u8; 3] = [0x90, 0x41, 0xFF];
+    const COMPLETION: [u8; 3] = [0x90, 0x51, 0xFF];
+    const BUFFER_FULL: [u8; 4] = [0x90, 0x60, 0x03, 0xFF];
+
+    fn raw(bytes: &[u8]) -> RawCommand {
+        RawCommand {
+            bytes: bytes.to_vec(),
+            response_type: None,
+        }
+    }
+
+    #[test]
+    fn buffer_full_is_retried_until_the_command_completes() {
+        let mut mock = MockTransport::new();
+        mock.respond(&HOME, &[&BUFFER_FULL])
+            .respond(&HOME, &[&BUFFER_FULL])
+            .respond(&HOME, &[&ACK, &COMPLETION]);
+        let clock = TestClock::new();
+        let start = clock.now();
+        let policy = BackpressurePolicy::default();
+
+        let response = send_command_with_retry_routing(
+            &mut mock,
+            &raw(&HOME),
+            &policy,
+            false,
+            &clock,
+            &mut |_| (),
+        )
+        .unwrap();
+        assert!(matches!(response, ViscaResponse::Completion(_)));
+        assert_eq!(mock.sent().len(), 3);
+        assert_eq!(clock.now() - start, policy.delay * 2);
+    }
+
+    #[test]
+    fn buffer_full_on_every_attempt_is_busy() {
+        let mut mock = MockTransport::new();
+        mock.respond(&HOME, &[&BUFFER_FULL]);
+        let policy = BackpressurePolicy {
+            max_attempts: 3,
+            delay: Duration::from_millis(10),
+        };
+
+        let result = send_command_with_retry_routing(
+            &mut mock,
+            &raw(&HOME),
+            &policy,
+            false,
+            &TestClock::new(),
+            &mut |_| (),
+        );
+        assert!(matches!(result, Err(ViscaError::Busy { attempts: 3 })));
+        assert_eq!(mock.sent().len(), 3);
+    }
+
+    #[test]
+    fn split_frames_borrows_each_frame() {
+        let buffer = [0x90, 0x41, 0xFF, 0x90, 0x50, 0x02, 0xFF];
+        let frames = split_frames(&buffer).unwrap();
+        assert_eq!(frames, [&buffer[..3], &buffer[3..]]);
+        assert!(std::ptr::eq(frames[1], &buffer[3..]));
+        assert_eq!(
+            parse_response(&RawFraming, &buffer).unwrap(),
+            [vec![0x90, 0x41, 0xFF], vec![0x90, 0x50, 0x02, 0xFF]]
+        );
+    }
+
+    #[test]
+    fn split_frames_skips_bytes_before_a_header() {
+        let buffer = [0x00, 0x12, 0x90, 0x41, 0xFF];
+        assert_eq!(split_frames(&buffer).unwrap(), [&buffer[2..]]);
+        assert!(split_frames(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn split_frames_rejects_a_trailing_partial_frame() {
+        assert!(matches!(
+            split_frames(&[0x90, 0x41, 0xFF, 0x90, 0x51]),
+            Err(ViscaError::InvalidResponseFormat)
+        ));
+        assert!(split_frames(&[0x90]).is_err());
+    }
+
+    #[test]
+    fn transports_are_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<TcpTransport>();
+        assert_send::<UdpTransport>();
+        assert_send::<MockTransport>();
+        assert_send::<Box<dyn ViscaTransport>>();
+
+        let observer = MockTransport::acking();
+        let mut transport: Box<dyn ViscaTransport> = Box::new(observer.clone());
+        std::thread::spawn(move || send_command_and_wait(&mut *transport, &raw(&HOME)))
+            .join()
+            .unwrap()
+            .unwrap();
+        assert_eq!(observer.sent(), [HOME.to_vec()]);
+    }
+
+    /// A command that sends whatever frame it is given, unchecked.
+    struct Crafted(Vec<u8>);
+
+    impl ViscaCommand for Crafted {
+        fn to_bytes(&self) -> Result<Vec<u8>, ViscaError> {
+            Ok(self.0.clone())
+        }
+
+        fn response_type(&self) -> Option<ViscaResponseType> {
+            None
+        }
+    }
+
+    const MALFORMED: &[&[u8]] = &[
+        // Reply header instead of a command header.
+        &[0x90, 0x01, 0x06, 0x04, 0xFF],
+        // Missing terminator.
+        &[0x81, 0x01, 0x06, 0x04],
+        // Interior terminator.
+        &[0x81, 0x01, 0xFF, 0x04, 0xFF],
+        // Too short and too long.
+        &[0x81, 0xFF],
+        &[0x81, 0x01, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xFF],
+    ];
+
+    #[test]
+    fn malformed_frames_fail_validation() {
+        for frame in MALFORMED {
+            assert!(
+                matches!(
+                    Crafted(frame.to_vec()).validate(),
+                    Err(ViscaError::InvalidParameter(_))
+                ),
+                "{:02X?}",
+                frame
+            );
+        }
+        assert!(Crafted(HOME.to_vec()).validate().is_ok());
+    }
+
+    #[test]
+    fn udp_transport_validates_before_writing() {
+        let camera = UdpSocket::bind("127.0.0.1:0").unwrap();
+        camera
+            .set_read_timeout(Some(Duration::from_millis(200)))
+            .unwrap();
+        let mut transport = UdpTransport::new(&camera.local_addr().unwrap().to_string()).unwrap();
+
+        for frame in MALFORMED {
+            assert!(transport.send_command(&Crafted(frame.to_vec())).is_err());
+        }
+        transport.send_command(&Crafted(HOME.to_vec())).unwrap();
+        // Only the well-formed frame reached the socket.
+        let mut buffer = [0; 64];
+        let received = camera.recv(&mut buffer).unwrap();
+        assert_eq!(buffer[..received], HOME);
+        assert!(camera.recv(&mut buffer).is_err());
+    }
+
+    #[test]
+    fn truncated_udp_reply_fails_promptly() {
+        let camera = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let mut transport = UdpTransport::new(&camera.local_addr().unwrap().to_string()).unwrap();
+        transport.send_command(&Crafted(HOME.to_vec())).unwrap();
+        let mut buffer = [0; 64];
+        let (_, client) = camera.recv_from(&mut buffer).unwrap();
+
+        // The ACK arrives without its terminator and nothing follows.
+        camera.send_to(&[0x90, 0x41], client).unwrap();
+        let start = Instant::now();
+        assert!(matches!(
+            transport.receive_response(),
+            Err(ViscaError::InvalidResponseFormat)
+        ));
+        assert!(start.elapsed() < UDP_CONTINUATION_TIMEOUT * 4);
+
+        // An unterminated reply past the size cap fails without waiting.
+        camera
+            .send_to(&[0x90; MAX_UDP_REPLY_BYTES + 1], client)
+            .unwrap();
+        assert!(matches!(
+            transport.receive_response(),
+            Err(ViscaError::InvalidResponseFormat)
+        ));
+    }
+
+    /// A socket that can't reach an IPv4 camera, so every send fails.
+    fn unreachable_socket() -> UdpSocket {
+        UdpSocket::bind("[::1]:0").unwrap()
+    }
+
+    #[test]
+    fn failed_udp_send_rebinds_and_resends() {
+        let camera = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let address = camera.local_addr().unwrap().to_string();
+        let mut plain = UdpTransport::new(&address).unwrap();
+        plain.socket = unreachable_socket();
+        assert!(matches!(
+            plain.send_command(&Crafted(HOME.to_vec())),
+            Err(ViscaError::Io(_))
+        ));
+
+        let mut transport = UdpTransport::new_with_reconnect(&address).unwrap();
+        transport.socket = unreachable_socket();
+        transport.send_command(&Crafted(HOME.to_vec())).unwrap();
+        let mut buffer = [0; 64];
+        let received = camera.recv(&mut buffer).unwrap();
+        assert_eq!(buffer[..received], HOME);
+    }
+
+    #[test]
+    fn udp_rebind_resets_the_sequence_first() {
+        let camera = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let address = camera.local_addr().unwrap().to_string();
+        let mut transport = UdpTransport {
+            reconnect: true,
+            ..UdpTransport::new_visca_over_ip(&address).unwrap()
+        };
+        transport.send_command(&Crafted(HOME.to_vec())).unwrap();
+        let mut buffer = [0; 64];
+        camera.recv(&mut buffer).unwrap();
+
+        let camera = std::thread::spawn(move || {
+            let mut packets = Vec::new();
+            let mut buffer = [0; 64];
+            let (received, client) = camera.recv_from(&mut buffer).unwrap();
+            packets.push(buffer[..received].to_vec());
+            camera.send_to(&[0x02, 0x01], client).unwrap();
+            let received = camera.recv(&mut buffer).unwrap();
+            packets.push(buffer[..received].to_vec());
+            packets
+        });
+        transport.socket = unreachable_socket();
+        transport.send_command(&Crafted(HOME.to_vec())).unwrap();
+
+        let packets = camera.join().unwrap();
+        // One reset, numbered after the failed send's sequence 1.
+        assert_eq!(packets[0], [0x02, 0x00, 0x00, 0x01, 0, 0, 0, 0x02, 0x01]);
+        // The resend starts the new sequence at zero.
+        assert_eq!(packets[1][..8], [0x01, 0x00, 0x00, 0x05, 0, 0, 0, 0]);
+        assert_eq!(packets[1][8..], HOME);
+    }
+
+    #[test]
+    fn error_after_the_ack_ends_the_wait() {
+        const NOT_EXECUTABLE: [u8; 4] = [0x90, 0x61, 0x41, 0xFF];
+        // In the same read as the ACK, and in a later one.
+        let together = MockTransport::new();
+        together.respond(&HOME, &[&ACK, &NOT_EXECUTABLE]);
+        let later = MockTransport::new();
+        later.reply(&[&ACK]).reply(&[&NOT_EXECUTABLE]);
+
+        for mut mock in [together, later] {
+            assert!(matches!(
+                send_command_and_wait(&mut mock, &raw(&HOME)),
+                Err(ViscaError::CommandNotExecutable)
+            ));
+        }
+    }
+
+    #[test]
+    fn reset_sequence_restarts_numbering_at_zero() {
+        let camera = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let address = camera.local_addr().unwrap().to_string();
+        let mut transport = UdpTransport::new_visca_over_ip(&address).unwrap();
+        let mut buffer = [0; 64];
+        transport.send_command(&Crafted(HOME.to_vec())).unwrap();
+        let (_, client) = camera.recv_from(&mut buffer).unwrap();
+
+        // The reply is queued before the request, which the socket buffers.
+        camera.send_to(&[0x02, 0x01], client).unwrap();
+        transport.reset_sequence().unwrap();
+        let received = camera.recv(&mut buffer).unwrap();
+        assert_eq!(
+            buffer[..received],
+            [0x02, 0x00, 0x00, 0x01, 0, 0, 0, 0x01, 0x01]
+        );
+
+        transport.send_command(&Crafted(HOME.to_vec())).unwrap();
+        let received = camera.recv(&mut buffer).unwrap();
+        assert_eq!(buffer[4..8], [0, 0, 0, 0]);
+        assert_eq!(buffer[8..received], HOME);
+
+        camera.send_to(&[0x01, 0x11], client).unwrap();
+        assert!(matches!(
+            transport.reset_sequence(),
+            Err(ViscaError::UnexpectedResponseType)
+        ));
+        let mut raw = UdpTransport::new(&address).unwrap();
+        assert!(matches!(
+            raw.reset_sequence(),
+            Err(ViscaError::InvalidParameter(_))
+        ));
+    }
+
+    #[test]
+    fn missed_replies_reset_the_sequence_and_resend() {
+        let camera = UdpSocket::bind("127.0.0.1:0").unwrap();
+        camera
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        let address = camera.local_addr().unwrap().to_string();
+        let mut transport = UdpTransport::new_visca_over_ip(&address).unwrap();
+        transport.set_desync_recovery(Some(2));
+        transport
+            .socket
+            .set_read_timeout(Some(Duration::from_millis(50)))
+            .unwrap();
+
+        let camera = std::thread::spawn(move || {
+            let mut packets = Vec::new();
+            let mut buffer = [0; 64];
+            // The first send is ignored, as by a desynced camera.
+            let (_, client) = camera.recv_from(&mut buffer).unwrap();
+            for reply in [
+                &[0x02, 0x01][..],
+                &[0x01, 0x11, 0x00, 0x03, 0, 0, 0, 0, 0x90, 0x41, 0xFF],
+            ] {
+                let received = camera.recv(&mut buffer).unwrap();
+                packets.push(buffer[..received].to_vec());
+                camera.send_to(reply, client).unwrap();
+            }
+            packets
+        });
+        transport.send_command(&Crafted(HOME.to_vec())).unwrap();
+        assert!(matches!(
+            transport.receive_response(),
+            Err(ViscaError::Io(e)) if is_timeout(&e)
+        ));
+        assert_eq!(transport.receive_response().unwrap(), [ACK]);
+
+        let packets = camera.join().unwrap();
+        assert_eq!(packets[0][..2], [0x02, 0x00]);
+        // The resend starts the new sequence at zero.
+        assert_eq!(packets[1][4..8], [0, 0, 0, 0]);
+        assert_eq!(packets[1][8..], HOME);
+    }
+
+    #[test]
+    fn unknown_frames_are_skipped_unless_strict() {
+        const UNKNOWN: [u8; 4] = [0x90, 0x07, 0x01, 0xFF];
+        let mut mock = MockTransport::new();
+        mock.respond(&HOME, &[&ACK, &UNKNOWN, &COMPLETION]);
+        let mut unsolicited = Vec::new();
+
+        let lenient = send_command_and_wait_routing(
+            &mut mock,
+            &raw(&HOME),
+            false,
+            &TestClock::new(),
+            &mut |response| unsolicited.push(response),
+        );
+        assert!(matches!(lenient, Ok(ViscaResponse::Completion(_))));
+        assert!(matches!(
+            unsolicited.as_slice(),
+            [ViscaResponse::Unknown(frame)] if *frame == UNKNOWN
+        ));
+
+        let strict = send_command_and_wait_routing(
+            &mut mock,
+            &raw(&HOME),
+            true,
+            &TestClock::new(),
+            &mut |_| (),
+        );
+        assert!(matches!(
+            strict,
+            Err(ViscaError::ParseError(message)) if message.contains("07")
+        ));
+    }
+
+    fn recall() -> command::PresetCommand {
+        command::PresetCommand {
+            action: command::preset::PresetAction::Recall,
+            preset_number: 1,
+        }
+    }
+
+    #[test]
+    fn motion_completion_is_waited_for_past_read_timeouts() {
+        let clock = Arc::new(TestClock::new());
+        let mut mock = MockTransport::new().with_clock(clock.clone(), Duration::from_secs(20));
+        mock.reply(&[&ACK])
+            .timeout()
+            .timeout()
+            .reply(&[&COMPLETION]);
+        let start = clock.now();
+        assert!(matches!(
+            send_command_and_wait_routing(&mut mock, &recall(), false, &*clock, &mut |_| ()),
+            Ok(ViscaResponse::Completion { .. })
+        ));
+        assert_eq!(clock.now() - start, Duration::from_secs(40));
+    }
+
+    #[test]
+    fn motion_completion_gives_up_at_its_timeout() {
+        let clock = Arc::new(TestClock::new());
+        let mut mock = MockTransport::new().with_clock(clock.clone(), Duration::from_secs(20));
+        mock.reply(&[&ACK]);
+        assert!(matches!(
+            send_command_and_wait_routing(&mut mock, &recall(), false, &*clock, &mut |_| ()),
+            Err(ViscaError::Timeout(timeout)) if timeout == DEFAULT_MOTION_COMPLETION_TIMEOUT
+        ));
+
+        // Other commands end the wait at the first read timeout.
+        let mut mock = MockTransport::new().with_clock(clock.clone(), Duration::from_secs(20));
+        mock.reply(&[&ACK]).timeout().reply(&[&COMPLETION]);
+        assert!(matches!(
+            send_command_and_wait_routing(&mut mock, &raw(&HOME), false, &*clock, &mut |_| ()),
+            Err(ViscaError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn complete_frames_are_single_terminated_messages() {
+        for frame in [&HOME[..], &ACK, &COMPLETION, &[0x90, 0x50, 0x02, 0xFF]] {
+            assert!(is_complete_frame(frame), "{:02X?}", frame);
+        }
+        // Incomplete, or not starting with a header.
+        for buffer in [
+            &[][..],
+            &[0xFF],
+            &[0x90, 0xFF],
+            &HOME[..4],
+            &[0x50, 0x41, 0xFF],
+            &[0x01, 0x11, 0x00, 0x03, 0x90, 0x41, 0xFF],
+        ] {
+            assert!(!is_complete_frame(buffer), "{:02X?}", buffer);
+        }
+        // Several frames, or a frame and the start of the next.
+        assert!(!is_complete_frame(&[ACK, COMPLETION].concat()));
+        assert!(!is_complete_frame(&[&ACK[..], &[0x90, 0x51]].concat()));
+    }
+}
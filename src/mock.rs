@@ -0,0 +1,274 @@
+//! A scripted [`ViscaTransport`] for unit tests.
+//!
+//! Replies are queued up front, either directly with [`MockTransport::reply`]
+//! or as the answer to commands starting with a given prefix with
+//! [`MockTransport::respond`]. Every frame sent is recorded. Clones share the
+//! script and the record, so a test can keep one while a [`crate::Camera`]
+//! owns another.
+
+use std::{
+    collections::VecDeque,
+    io,
+    sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
+};
+
+use crate::{clock::TestClock, ViscaCommand, ViscaError, ViscaTransport};
+
+/// The result of one `receive_response` call.
+enum Reply {
+    Frames(Vec<Vec<u8>>),
+    Timeout,
+    Error(ViscaError),
+}
+
+/// Reply batches queued for each send of a command starting with the prefix.
+type Responder = (Vec<u8>, VecDeque<Vec<Vec<u8>>>);
+
+#[derive(Default)]
+struct Script {
+    replies: VecDeque<Reply>,
+    /// Replies queued whenever a command starting with the prefix is sent.
+    /// Each send takes the next batch; the last one repeats.
+    responders: Vec<Responder>,
+    /// Answer control commands no responder matches with ACK and Completion.
+    acking: bool,
+    send_errors: VecDeque<ViscaError>,
+    sent: Vec<Vec<u8>>,
+    /// Advanced by the given step on every timeout.
+    clock: Option<(Arc<TestClock>, Duration)>,
+}
+
+#[derive(Clone, Default)]
+pub(crate) struct MockTransport {
+    script: Arc<Mutex<Script>>,
+}
+
+impl MockTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Answers every control command no [`respond`](Self::respond) rule
+    /// matches with `90 41 FF` and `90 51 FF`.
+    pub fn acking() -> Self {
+        let transport = Self::new();
+        transport.lock().acking = true;
+        transport
+    }
+
+    /// Advances `clock` by `step` on every receive timeout.
+    pub fn with_clock(self, clock: Arc<TestClock>, step: Duration) -> Self {
+        self.lock().clock = Some((clock, step));
+        self
+    }
+
+    /// Queues the frames one `receive_response` call returns.
+    pub fn reply(&self, frames: &[&[u8]]) -> &Self {
+        let frames = frames.iter().map(|frame| frame.to_vec()).collect();
+        self.lock().replies.push_back(Reply::Frames(frames));
+        self
+    }
+
+    /// Queues a read timeout. Receiving with nothing queued times out too.
+    pub fn timeout(&self) -> &Self {
+        self.lock().replies.push_back(Reply::Timeout);
+        self
+    }
+
+    /// Queues an error for one `receive_response` call.
+    pub fn receive_error(&self, error: ViscaError) -> &Self {
+        self.lock().replies.push_back(Reply::Error(error));
+        self
+    }
+
+    /// Fails the next `send_command` with `error`.
+    pub fn send_error(&self, error: ViscaError) -> &Self {
+        self.lock().send_errors.push_back(error);
+        self
+    }
+
+    /// Queues `frames` as the reply each time a command starting with
+    /// `prefix` is sent. Calling this again for the same prefix adds a
+    /// batch for the next send; the last batch repeats.
+    pub fn respond(&self, prefix: &[u8], frames: &[&[u8]]) -> &Self {
+        let frames: Vec<Vec<u8>> = frames.iter().map(|frame| frame.to_vec()).collect();
+        let mut script = self.lock();
+        match script
+            .responders
+            .iter_mut()
+            .find(|(existing, _)| existing == prefix)
+        {
+            Some((_, batches)) => batches.push_back(frames),
+            None => script
+                .responders
+                .push((prefix.to_vec(), VecDeque::from([frames]))),
+        }
+        self
+    }
+
+    /// Every frame sent so far, in order.
+    pub fn sent(&self) -> Vec<Vec<u8>> {
+        self.lock().sent.clone()
+    }
+
+    /// Frames sent so far that start with `prefix`.
+    pub fn sent_with_prefix(&self, prefix: &[u8]) -> Vec<Vec<u8>> {
+        self.lock()
+            .sent
+            .iter()
+            .filter(|frame| frame.starts_with(prefix))
+            .cloned()
+            .collect()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Script> {
+        // A test that panicked mid-call has failed already.
+        self.script
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl ViscaTransport for MockTransport {
+    fn send_command(&mut self, command: &dyn ViscaCommand) -> Result<(), ViscaError> {
+        command.validate()?;
+        let frame = command.to_bytes()?;
+        let mut script = self.lock();
+        if let Some(error) = script.send_errors.pop_front() {
+            return Err(error);
+        }
+        script.sent.push(frame.clone());
+
+        let acking = script.acking;
+        let responder = script
+            .responders
+            .iter_mut()
+            .filter(|(prefix, _)| frame.starts_with(prefix))
+            .max_by_key(|(prefix, _)| prefix.len());
+        let reply = match responder {
+            Some((_, batches)) if batches.len() > 1 => batches.pop_front(),
+            Some((_, batches)) => batches.front().cloned(),
+            None if acking && command.response_type().is_none() => {
+                Some(vec![vec![0x90, 0x41, 0xFF], vec![0x90, 0x51, 0xFF]])
+            }
+            None => None,
+        };
+        if let Some(frames) = reply {
+            script.replies.push_back(Reply::Frames(frames));
+        }
+        Ok(())
+    }
+
+    fn receive_response(&mut self) -> Result<Vec<Vec<u8>>, ViscaError> {
+        let mut script = self.lock();
+        match script.replies.pop_front().unwrap_or(Reply::Timeout) {
+            Reply::Frames(frames) => Ok(frames),
+            Reply::Error(error) => Err(error),
+            Reply::Timeout => {
+                if let Some((clock, step)) = &script.clock {
+                    clock.advance(*step);
+                }
+                Err(ViscaError::Io(io::Error::from(io::ErrorKind::TimedOut)))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        command::{InquiryCommand, RawCommand},
+        Clock,
+    };
+
+    fn raw(bytes: &[u8]) -> RawCommand {
+        RawCommand {
+            bytes: bytes.to_vec(),
+            response_type: None,
+        }
+    }
+
+    #[test]
+    fn replies_come_back_in_order_then_time_out() {
+        let mut transport = MockTransport::new();
+        transport
+            .reply(&[&[0x90, 0x41, 0xFF]])
+            .reply(&[&[0x90, 0x51, 0xFF]]);
+        assert_eq!(transport.receive_response().unwrap(), [[0x90, 0x41, 0xFF]]);
+        assert_eq!(transport.receive_response().unwrap(), [[0x90, 0x51, 0xFF]]);
+        assert!(matches!(
+            transport.receive_response(),
+            Err(ViscaError::Io(e)) if e.kind() == io::ErrorKind::TimedOut
+        ));
+    }
+
+    #[test]
+    fn clones_share_the_record() {
+        let observer = MockTransport::acking();
+        let mut transport = observer.clone();
+        transport
+            .send_command(&raw(&[0x81, 0x01, 0x06, 0x04, 0xFF]))
+            .unwrap();
+        transport.send_command(&InquiryCommand::Power).unwrap();
+        assert_eq!(
+            observer.sent(),
+            [
+                vec![0x81, 0x01, 0x06, 0x04, 0xFF],
+                vec![0x81, 0x09, 0x04, 0x00, 0xFF]
+            ]
+        );
+        assert_eq!(observer.sent_with_prefix(&[0x81, 0x09]).len(), 1);
+        // Only the control command was auto-acknowledged.
+        assert_eq!(transport.receive_response().unwrap().len(), 2);
+        assert!(transport.receive_response().is_err());
+    }
+
+    #[test]
+    fn responders_step_through_batches_and_repeat_the_last() {
+        let mut transport = MockTransport::new();
+        transport
+            .respond(&[0x81, 0x09, 0x04, 0x00], &[&[0x90, 0x50, 0x02, 0xFF]])
+            .respond(&[0x81, 0x09, 0x04, 0x00], &[&[0x90, 0x50, 0x03, 0xFF]]);
+        let mut replies = Vec::new();
+        for _ in 0..3 {
+            transport.send_command(&InquiryCommand::Power).unwrap();
+            replies.push(transport.receive_response().unwrap()[0][2]);
+        }
+        assert_eq!(replies, [0x02, 0x03, 0x03]);
+    }
+
+    #[test]
+    fn scripted_errors_and_clock() {
+        let clock = Arc::new(TestClock::new());
+        let mut transport =
+            MockTransport::new().with_clock(clock.clone(), Duration::from_millis(100));
+        transport.send_error(ViscaError::TransportError("down".into()));
+        transport.receive_error(ViscaError::InvalidResponseFormat);
+
+        let command = raw(&[0x81, 0x01, 0x06, 0x04, 0xFF]);
+        assert!(transport.send_command(&command).is_err());
+        assert!(transport.send_command(&command).is_ok());
+        assert_eq!(transport.sent().len(), 1);
+
+        let start = clock.now();
+        assert!(matches!(
+            transport.receive_response(),
+            Err(ViscaError::InvalidResponseFormat)
+        ));
+        assert_eq!(clock.now(), start);
+        transport.timeout();
+        assert!(transport.receive_response().is_err());
+        assert_eq!(clock.now() - start, Duration::from_millis(100));
+    }
+
+    #[test]
+    fn invalid_frames_are_not_sent() {
+        let mut transport = MockTransport::new();
+        assert!(transport
+            .send_command(&raw(&[0x81, 0xFF, 0x01, 0xFF]))
+            .is_err());
+        assert!(transport.sent().is_empty());
+    }
+}
@@ -0,0 +1,392 @@
+use crate::command::ReplyPattern;
+
+/// Default VISCA-over-IP TCP port (PTZOptics).
+pub const DEFAULT_VISCA_TCP_PORT: u16 = 5678;
+/// Default VISCA-over-IP UDP port (PTZOptics).
+pub const DEFAULT_VISCA_UDP_PORT: u16 = 1259;
+/// Sony VISCA-over-IP port.
+pub const SONY_VISCA_IP_PORT: u16 = 52381;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Protocol {
+    Udp,
+    Tcp,
+}
+
+/// Camera profiles that tailor command validation to a specific model.
+///
+/// The conservative default is `PtzOpticsG2`, which is the only model whose
+/// byte sequences have been validated against the vendor documentation.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum CameraModel {
+    /// PTZOptics G2 series.
+    #[default]
+    PtzOpticsG2,
+    /// Sony FR7 cinema line PTZ.
+    SonyFr7,
+    /// Unknown camera. Validation only enforces what the wire format can
+    /// carry and leaves model-specific limits to the camera itself.
+    Generic,
+}
+
+/// Sony's vendor id in the version inquiry reply.
+const SONY_VENDOR_ID: u16 = 0x0001;
+/// PTZOptics' vendor id in the version inquiry reply.
+const PTZOPTICS_VENDOR_ID: u16 = 0x0109;
+
+/// `(vendor_id, model_id)` pairs from the version inquiry reply, and the
+/// profile each one selects.
+const KNOWN_VERSIONS: &[((u16, u16), CameraModel)] = &[
+    ((SONY_VENDOR_ID, 0x0711), CameraModel::SonyFr7),
+    ((PTZOPTICS_VENDOR_ID, 0x0A20), CameraModel::PtzOpticsG2),
+    ((PTZOPTICS_VENDOR_ID, 0x0A30), CameraModel::PtzOpticsG2),
+    ((PTZOPTICS_VENDOR_ID, 0x0A40), CameraModel::PtzOpticsG2),
+];
+
+/// Commands, by [`crate::ViscaCommand::name`], that each profile rejects.
+/// Anything not listed is assumed to be supported. The G2 command list in
+/// `docs/` has no last-preset inquiry, so neither named profile claims it.
+const PTZOPTICS_G2_UNSUPPORTED: &[&str] = &[
+    "ZoomFocusDirect",
+    "AeLevel",
+    "InquiryAeLevel",
+    "PanTiltRamp",
+    "InquiryLastPreset",
+];
+/// USB audio, RTMP and MotionSync are PTZOptics vendor extensions.
+const SONY_FR7_UNSUPPORTED: &[&str] = &[
+    "UsbAudio",
+    "InquiryUsbAudio",
+    "Rtmp",
+    "InquiryRtmp",
+    "MotionSyncOn",
+    "MotionSyncOff",
+    "MotionSyncMaxSpeed",
+    "InquiryMotionSync",
+    "InquiryMotionSyncSpeed",
+    "InquiryLastPreset",
+];
+
+/// Commands, by [`crate::ViscaCommand::name`], whose replies differ from
+/// the [`crate::ViscaCommand::reply_pattern`] default. An addressed
+/// IF_Clear is answered with a bare completion.
+const DEFAULT_REPLY_PATTERNS: &[(&str, ReplyPattern)] =
+    &[("IfClear", ReplyPattern::CompletionOnly)];
+
+/// Optical zoom ratio at each raw zoom position, from Sony's table for its
+/// 30x block cameras. Positions between entries are interpolated linearly.
+const SONY_ZOOM_RATIOS: &[(f32, u16)] = &[
+    (1.0, 0x0000),
+    (2.0, 0x16A1),
+    (3.0, 0x2063),
+    (4.0, 0x2628),
+    (5.0, 0x2A1D),
+    (6.0, 0x2D13),
+    (7.0, 0x2F6D),
+    (8.0, 0x3161),
+    (9.0, 0x330D),
+    (10.0, 0x3486),
+    (11.0, 0x35D7),
+    (12.0, 0x3709),
+    (13.0, 0x3820),
+    (14.0, 0x3920),
+    (15.0, 0x3A0A),
+    (16.0, 0x3ADD),
+    (17.0, 0x3B9C),
+    (18.0, 0x3C46),
+    (19.0, 0x3CDC),
+    (20.0, 0x3D60),
+    (21.0, 0x3DD4),
+    (22.0, 0x3E39),
+    (23.0, 0x3E90),
+    (24.0, 0x3EDC),
+    (25.0, 0x3F1E),
+    (26.0, 0x3F57),
+    (27.0, 0x3F8A),
+    (28.0, 0x3FB6),
+    (29.0, 0x3FDC),
+    (30.0, 0x4000),
+];
+
+/// A zoom lens whose ratio-to-position table is known, for converting
+/// between optical zoom ratios and raw zoom positions. The table belongs to
+/// the lens rather than the camera profile, as interchangeable-lens cameras
+/// such as the FR7 report positions that depend on the lens mounted.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ZoomLens {
+    /// Sony's 30x optical block-camera lens.
+    SonyBlock30x,
+}
+
+impl ZoomLens {
+    /// Ratio and position pairs, ascending in both.
+    fn table(&self) -> &'static [(f32, u16)] {
+        match self {
+            ZoomLens::SonyBlock30x => SONY_ZOOM_RATIOS,
+        }
+    }
+
+    /// Highest optical zoom ratio in the lens's table.
+    pub fn max_zoom_ratio(&self) -> f32 {
+        self.table().last().map_or(1.0, |&(ratio, _)| ratio)
+    }
+
+    /// Optical zoom ratio at a raw zoom position, positions past the end of
+    /// the table giving the maximum ratio.
+    pub fn zoom_ratio(&self, position: u16) -> f32 {
+        let table = self.table();
+        let upper = table
+            .iter()
+            .position(|&(_, p)| p >= position)
+            .unwrap_or(table.len() - 1);
+        if upper == 0 || table[upper].1 <= position {
+            return table[upper].0;
+        }
+        let (low_ratio, low) = table[upper - 1];
+        let (high_ratio, high) = table[upper];
+        let fraction = f32::from(position - low) / f32::from(high - low);
+        low_ratio + fraction * (high_ratio - low_ratio)
+    }
+
+    /// Raw zoom position for an optical zoom ratio, the inverse of
+    /// [`zoom_ratio`](Self::zoom_ratio). Ratios outside the table are clamped
+    /// to its range; `None` for a NaN or infinite ratio.
+    pub fn zoom_position(&self, ratio: f32) -> Option<u16> {
+        if !ratio.is_finite() {
+            return None;
+        }
+        let table = self.table();
+        let upper = table
+            .iter()
+            .position(|&(r, _)| r >= ratio)
+            .unwrap_or(table.len() - 1);
+        if upper == 0 || table[upper].0 <= ratio {
+            return Some(table[upper].1);
+        }
+        let (low_ratio, low) = table[upper - 1];
+        let (high_ratio, high) = table[upper];
+        let fraction = (ratio - low_ratio) / (high_ratio - low_ratio);
+        Some(low + (fraction * f32::from(high - low)).round() as u16)
+    }
+}
+
+impl CameraModel {
+    /// Picks the profile for the vendor and model ids reported by the
+    /// version inquiry. Unrecognised pairs get `Generic`, including other
+    /// models from a known vendor.
+    pub fn from_version(vendor_id: u16, model_id: u16) -> Self {
+        KNOWN_VERSIONS
+            .iter()
+            .find(|&&(ids, _)| ids == (vendor_id, model_id))
+            .map_or(CameraModel::Generic, |&(_, model)| model)
+    }
+
+    /// Port the camera listens on for VISCA over the given protocol.
+    pub fn default_port(&self, protocol: Protocol) -> u16 {
+        match (self, protocol) {
+            (CameraModel::SonyFr7, _) => SONY_VISCA_IP_PORT,
+            (_, Protocol::Udp) => DEFAULT_VISCA_UDP_PORT,
+            (_, Protocol::Tcp) => DEFAULT_VISCA_TCP_PORT,
+        }
+    }
+
+    /// Highest pan and tilt drive speeds. The profiles share the limits
+    /// [`PanSpeed`](crate::command::pan_tilt::PanSpeed) and
+    /// [`TiltSpeed`](crate::command::pan_tilt::TiltSpeed) validate, which no
+    /// profile may exceed.
+    ///
+    /// | Profile       | Pan           | Tilt          |
+    /// |---------------|---------------|---------------|
+    /// | `PtzOpticsG2` | `0x01..=0x18` | `0x01..=0x14` |
+    /// | `SonyFr7`     | `0x01..=0x18` | `0x01..=0x14` |
+    /// | `Generic`     | `0x01..=0x18` | `0x01..=0x14` |
+    pub fn max_pan_tilt_speed(&self) -> (u8, u8) {
+        match self {
+            CameraModel::PtzOpticsG2 | CameraModel::SonyFr7 | CameraModel::Generic => (0x18, 0x14),
+        }
+    }
+
+    /// Maximum speed accepted by the variable focus commands. The speed is
+    /// the low nibble of `0x2p`/`0x3p`; the PTZOptics G2 command list in
+    /// `docs/` gives Focus Far/Near adjustable speed as `81 01 04 08 2p FF`
+    /// with `p: 0x0 ~ 0x7`. No FR7 command list is in `docs/`, so that
+    /// profile keeps the same conservative range. Cameras whose lenses take
+    /// finer speed steps run without a named profile, so `Generic` allows
+    /// the whole nibble.
+    ///
+    /// | Profile       | Range     |
+    /// |---------------|-----------|
+    /// | `PtzOpticsG2` | `0..=7`   |
+    /// | `SonyFr7`     | `0..=7`   |
+    /// | `Generic`     | `0..=0xF` |
+    pub fn max_focus_speed(&self) -> u8 {
+        match self {
+            CameraModel::PtzOpticsG2 | CameraModel::SonyFr7 => 0x07,
+            CameraModel::Generic => 0x0F,
+        }
+    }
+
+    /// Maximum speed accepted by the variable zoom commands. The speed is
+    /// the low nibble of `0x2p`/`0x3p`; the PTZOptics G2 command list in
+    /// `docs/` gives `p: 0x0 ~ 0x7`, and the FR7 profile assumes the same.
+    /// `Generic` allows the whole nibble.
+    ///
+    /// | Profile       | Range     |
+    /// |---------------|-----------|
+    /// | `PtzOpticsG2` | `0..=7`   |
+    /// | `SonyFr7`     | `0..=7`   |
+    /// | `Generic`     | `0..=0xF` |
+    pub fn max_zoom_speed(&self) -> u8 {
+        match self {
+            CameraModel::PtzOpticsG2 | CameraModel::SonyFr7 => 0x07,
+            CameraModel::Generic => 0x0F,
+        }
+    }
+
+    /// Converts a gain position to dB where the profile's table is known.
+    ///
+    /// | Profile       | Positions      | dB                         |
+    /// |---------------|----------------|----------------------------|
+    /// | `SonyFr7`     | `0x00..=0x0F`  | `-3` to `+42`, 3 dB a step |
+    /// | `PtzOpticsG2` | -              | not documented             |
+    /// | `Generic`     | -              | unknown                    |
+    pub fn gain_db(&self, position: u8) -> Option<f32> {
+        match self {
+            CameraModel::SonyFr7 if position <= 0x0F => Some((f32::from(position) - 1.0) * 3.0),
+            _ => None,
+        }
+    }
+
+    /// Whether the combined zoom/focus direct command is accepted. Without
+    /// it, [`crate::Camera::zoom_focus_direct`] sends the two positions
+    /// separately.
+    pub fn supports_zoom_focus_direct(&self) -> bool {
+        self.supports("ZoomFocusDirect")
+    }
+
+    /// Replies the profile's firmware sends for the command named `name`,
+    /// where they differ from the command's own default. The profiles share
+    /// one table for now; a firmware that drops the ACK or the completion
+    /// for a command gets its own. [`crate::Camera::expect_reply`]
+    /// overrides this per camera.
+    pub fn reply_pattern(&self, name: &str) -> Option<ReplyPattern> {
+        let patterns = match self {
+            CameraModel::PtzOpticsG2 | CameraModel::SonyFr7 | CameraModel::Generic => {
+                DEFAULT_REPLY_PATTERNS
+            }
+        };
+        patterns
+            .iter()
+            .find(|&&(command, _)| command == name)
+            .map(|&(_, pattern)| pattern)
+    }
+
+    /// Whether the camera implements the command named `name`, as returned
+    /// by [`crate::ViscaCommand::name`]. Lets a UI hide controls that would
+    /// only be answered with `CommandNotExecutable`. `Generic` claims
+    /// everything.
+    pub fn supports(&self, name: &str) -> bool {
+        let unsupported = match self {
+            CameraModel::PtzOpticsG2 => PTZOPTICS_G2_UNSUPPORTED,
+            CameraModel::SonyFr7 => SONY_FR7_UNSUPPORTED,
+            CameraModel::Generic => &[],
+        };
+        !unsupported.contains(&name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn port_constants() {
+        assert_eq!(DEFAULT_VISCA_TCP_PORT, 5678);
+        assert_eq!(DEFAULT_VISCA_UDP_PORT, 1259);
+        assert_eq!(SONY_VISCA_IP_PORT, 52381);
+    }
+
+    #[test]
+    fn default_port_per_profile_and_protocol() {
+        for model in [CameraModel::PtzOpticsG2, CameraModel::Generic] {
+            assert_eq!(model.default_port(Protocol::Udp), DEFAULT_VISCA_UDP_PORT);
+            assert_eq!(model.default_port(Protocol::Tcp), DEFAULT_VISCA_TCP_PORT);
+        }
+        for protocol in [Protocol::Udp, Protocol::Tcp] {
+            assert_eq!(
+                CameraModel::SonyFr7.default_port(protocol),
+                SONY_VISCA_IP_PORT
+            );
+        }
+    }
+
+    #[test]
+    fn version_ids_map_to_profiles() {
+        assert_eq!(
+            CameraModel::from_version(SONY_VENDOR_ID, 0x0711),
+            CameraModel::SonyFr7
+        );
+        assert_eq!(
+            CameraModel::from_version(PTZOPTICS_VENDOR_ID, 0x0A30),
+            CameraModel::PtzOpticsG2
+        );
+        // An unknown model from a known vendor, and an unknown vendor.
+        assert_eq!(
+            CameraModel::from_version(SONY_VENDOR_ID, 0x0001),
+            CameraModel::Generic
+        );
+        assert_eq!(
+            CameraModel::from_version(0x1234, 0x0711),
+            CameraModel::Generic
+        );
+    }
+
+    #[test]
+    fn supports_follows_each_profile_table() {
+        use crate::command::{AeLevelCommand, InquiryCommand, UsbAudioCommand, ZoomCommand};
+        use crate::ViscaCommand;
+
+        let zoom = ZoomCommand::Direct(0).name();
+        let ae_level = AeLevelCommand { value: 0 }.name();
+        let usb_audio = UsbAudioCommand { enabled: true }.name();
+        let last_preset = InquiryCommand::LastPreset.name();
+
+        let g2 = CameraModel::PtzOpticsG2;
+        assert!(g2.supports(zoom) && g2.supports(usb_audio));
+        assert!(!g2.supports(ae_level) && !g2.supports(last_preset));
+        assert!(!g2.supports_zoom_focus_direct());
+
+        let fr7 = CameraModel::SonyFr7;
+        assert!(fr7.supports(zoom) && fr7.supports(ae_level));
+        assert!(!fr7.supports(usb_audio) && !fr7.supports(last_preset));
+
+        for name in [zoom, ae_level, usb_audio, last_preset, "Anything"] {
+            assert!(CameraModel::Generic.supports(name));
+        }
+    }
+
+    #[test]
+    fn zoom_position_inverts_the_sony_table() {
+        let lens = ZoomLens::SonyBlock30x;
+        assert_eq!(lens.zoom_position(1.0), Some(0x0000));
+        assert_eq!(lens.zoom_position(4.0), Some(0x2628));
+        assert_eq!(lens.zoom_position(12.0), Some(0x3709));
+        // Halfway between the 4x and 5x entries.
+        assert_eq!(lens.zoom_position(4.5), Some(0x2823));
+        for position in [0x0000, 0x2628, 0x2823, 0x3709, 0x4000] {
+            let ratio = lens.zoom_ratio(position);
+            assert_eq!(lens.zoom_position(ratio), Some(position));
+        }
+    }
+
+    #[test]
+    fn zoom_position_clamps_to_the_table() {
+        let lens = ZoomLens::SonyBlock30x;
+        assert_eq!(lens.max_zoom_ratio(), 30.0);
+        assert_eq!(lens.zoom_position(30.0), Some(0x4000));
+        assert_eq!(lens.zoom_position(45.0), Some(0x4000));
+        assert_eq!(lens.zoom_position(0.5), Some(0x0000));
+        assert_eq!(lens.zoom_position(f32::NAN), None);
+        assert_eq!(lens.zoom_position(f32::INFINITY), None);
+    }
+}
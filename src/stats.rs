@@ -0,0 +1,97 @@
+use std::io;
+
+use crate::ViscaError;
+
+/// Traffic counters kept by a transport when the `metrics` feature is on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TransportStats {
+    pub commands_sent: u64,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    pub responses_parsed: u64,
+    pub errors: u64,
+    pub timeouts: u64,
+}
+
+impl TransportStats {
+    pub(crate) fn record_send(&mut self, bytes: usize) {
+        self.commands_sent += 1;
+        self.bytes_sent += bytes as u64;
+    }
+
+    pub(crate) fn record_receive(&mut self, bytes: usize) {
+        self.bytes_received += bytes as u64;
+    }
+
+    pub(crate) fn record_responses(&mut self, count: usize) {
+        self.responses_parsed += count as u64;
+    }
+
+    /// Counts a failed operation, and additionally a timeout for socket
+    /// read/write timeouts.
+    pub(crate) fn record_result<T>(&mut self, result: &Result<T, ViscaError>) {
+        let Err(err) = result else {
+            return;
+        };
+        self.errors += 1;
+        if let ViscaError::Io(e) = err {
+            if matches!(
+                e.kind(),
+                io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+            ) {
+                self.timeouts += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{command::RawCommand, UdpTransport, ViscaTransport};
+    use std::{net::UdpSocket, time::Duration};
+
+    #[test]
+    fn counters_follow_a_udp_session() {
+        let camera = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let mut transport = UdpTransport::new(&camera.local_addr().unwrap().to_string()).unwrap();
+        transport
+            .set_read_timeout(Some(Duration::from_millis(50)))
+            .unwrap();
+        let home = RawCommand {
+            bytes: vec![0x81, 0x01, 0x06, 0x04, 0xFF],
+            response_type: None,
+        };
+
+        transport.send_command(&home).unwrap();
+        let mut buffer = [0; 64];
+        let (_, controller) = camera.recv_from(&mut buffer).unwrap();
+        camera
+            .send_to(&[0x90, 0x41, 0xFF, 0x90, 0x51, 0xFF], controller)
+            .unwrap();
+        assert_eq!(transport.receive_response().unwrap().len(), 2);
+        // Nothing more is sent, so the next read times out.
+        assert!(transport.receive_response().is_err());
+
+        assert_eq!(
+            *transport.stats(),
+            TransportStats {
+                commands_sent: 1,
+                bytes_sent: 5,
+                bytes_received: 6,
+                responses_parsed: 2,
+                errors: 1,
+                timeouts: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn only_read_and_write_timeouts_count_as_timeouts() {
+        let mut stats = TransportStats::default();
+        stats.record_result::<()>(&Ok(()));
+        stats.record_result::<()>(&Err(ViscaError::Io(io::ErrorKind::WouldBlock.into())));
+        stats.record_result::<()>(&Err(ViscaError::InvalidResponseFormat));
+        assert_eq!((stats.errors, stats.timeouts), (2, 1));
+    }
+}
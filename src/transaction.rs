@@ -0,0 +1,182 @@
+use log::debug;
+
+use crate::command::response::{ReplyPattern, Socket};
+use crate::{
+    parse_and_handle_response, ViscaCommand, ViscaError, ViscaResponse, ViscaResponseType,
+};
+
+/// Where a [`CommandTransaction`] stands after the last frame it was fed.
+#[derive(Debug)]
+pub enum TransactionState {
+    AwaitingAck,
+    /// ACKed on `socket`; waiting for the Completion.
+    AwaitingCompletion {
+        socket: Socket,
+    },
+    Done(ViscaResponse),
+    Failed(ViscaError),
+}
+
+/// Tracks the replies to one command, ACK then Completion (or an inquiry
+/// reply), without doing any I/O. Send the command however suits the caller
+/// and [`feed`](Self::feed) each received frame until the state is `Done` or
+/// `Failed`; frames fed after that are not meaningful.
+///
+/// [`crate::send_command_and_wait`] is this machine driven by a blocking
+/// read loop.
+pub struct CommandTransaction {
+    name: &'static str,
+    response_type: Option<ViscaResponseType>,
+    reply_pattern: ReplyPattern,
+    socket: Option<Socket>,
+    unsolicited: Option<ViscaResponse>,
+    strict: bool,
+}
+
+impl CommandTransaction {
+    pub fn new(command: &dyn ViscaCommand) -> Self {
+        CommandTransaction {
+            name: command.name(),
+            response_type: command.response_type(),
+            reply_pattern: command.reply_pattern(),
+            socket: None,
+            unsolicited: None,
+            strict: false,
+        }
+    }
+
+    /// When set, a frame that decodes as [`ViscaResponse::Unknown`] fails the
+    /// transaction with `ViscaError::ParseError` instead of being skipped.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Advances on one received frame. An error reply fails the transaction
+    /// whether it arrives instead of the ACK or after it.
+    pub fn feed(&mut self, frame: &[u8]) -> TransactionState {
+        // Error replies, before or after the ACK, are decoded as `Err`.
+        let response = match parse_and_handle_response(frame, self.response_type) {
+            Ok(response) => response,
+            Err(e) => return TransactionState::Failed(e),
+        };
+
+        match response {
+            // Once ACKed, only the Completion on our own socket ends the
+            // command; another socket's belongs to a different command.
+            ViscaResponse::Completion(socket) if self.socket.is_some_and(|s| s != socket) => {
+                debug!("{} skipping Completion on socket {}", self.name, socket);
+                self.waiting_state()
+            }
+            ViscaResponse::Completion(_) | ViscaResponse::InquiryResponse(_) => {
+                TransactionState::Done(response)
+            }
+            ViscaResponse::Ack(_) if self.reply_pattern == ReplyPattern::AckOnly => {
+                TransactionState::Done(response)
+            }
+            ViscaResponse::Ack(socket) => {
+                self.socket = Some(socket);
+                TransactionState::AwaitingCompletion { socket }
+            }
+            ViscaResponse::Unknown(bytes) if self.strict => TransactionState::Failed(
+                ViscaError::ParseError(format!("Unknown frame: {:02X?}", bytes)),
+            ),
+            _ => {
+                if response.is_unsolicited() {
+                    self.unsolicited = Some(response);
+                }
+                self.waiting_state()
+            }
+        }
+    }
+
+    /// Takes the unsolicited frame, such as a network change, that the last
+    /// [`feed`](Self::feed) received instead of a reply.
+    pub fn take_unsolicited(&mut self) -> Option<ViscaResponse> {
+        self.unsolicited.take()
+    }
+
+    fn waiting_state(&self) -> TransactionState {
+        match self.socket {
+            Some(socket) => TransactionState::AwaitingCompletion { socket },
+            None => TransactionState::AwaitingAck,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::{InquiryCommand, RawCommand};
+    use crate::ViscaInquiryResponse;
+
+    fn home() -> CommandTransaction {
+        CommandTransaction::new(&RawCommand {
+            bytes: vec![0x81, 0x01, 0x06, 0x04, 0xFF],
+            response_type: None,
+        })
+    }
+
+    #[test]
+    fn ack_then_completion_on_the_same_socket() {
+        let mut transaction = home();
+        assert!(matches!(
+            transaction.feed(&[0x90, 0x41, 0xFF]),
+            TransactionState::AwaitingCompletion { socket } if socket == Socket::new(1).unwrap()
+        ));
+        // Another command's Completion doesn't end this one.
+        assert!(matches!(
+            transaction.feed(&[0x90, 0x52, 0xFF]),
+            TransactionState::AwaitingCompletion { .. }
+        ));
+        assert!(matches!(
+            transaction.feed(&[0x90, 0x51, 0xFF]),
+            TransactionState::Done(ViscaResponse::Completion(_))
+        ));
+    }
+
+    #[test]
+    fn inquiry_reply_completes_the_transaction() {
+        let mut transaction = CommandTransaction::new(&InquiryCommand::Power);
+        assert!(matches!(
+            transaction.feed(&[0x90, 0x50, 0x02, 0xFF]),
+            TransactionState::Done(ViscaResponse::InquiryResponse(
+                ViscaInquiryResponse::Power { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn error_replies_fail_before_or_after_the_ack() {
+        let mut transaction = home();
+        assert!(matches!(
+            transaction.feed(&[0x90, 0x60, 0x02, 0xFF]),
+            TransactionState::Failed(ViscaError::SyntaxError)
+        ));
+
+        let mut transaction = home();
+        transaction.feed(&[0x90, 0x41, 0xFF]);
+        assert!(matches!(
+            transaction.feed(&[0x90, 0x61, 0x41, 0xFF]),
+            TransactionState::Failed(ViscaError::CommandNotExecutable)
+        ));
+        assert!(matches!(
+            transaction.feed(&[0x90, 0x41]),
+            TransactionState::Failed(_)
+        ));
+    }
+
+    #[test]
+    fn unsolicited_frames_are_set_aside() {
+        let mut transaction = home();
+        assert!(matches!(
+            transaction.feed(&[0x90, 0x38, 0xFF]),
+            TransactionState::AwaitingAck
+        ));
+        assert!(matches!(
+            transaction.take_unsolicited(),
+            Some(ViscaResponse::NetworkChange)
+        ));
+        assert!(transaction.take_unsolicited().is_none());
+    }
+}